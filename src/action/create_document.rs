@@ -1,4 +1,4 @@
-use {DocumentId, DocumentPath, Error, IntoDatabasePath, Revision, serde, std};
+use {DocumentId, DocumentPath, Error, IntoDatabasePath, Revision, WriteOutcome, serde, std};
 use document::WriteDocumentResponse;
 use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
 
@@ -35,7 +35,14 @@ impl<'a, C, P, T> CreateDocument<'a, T, P, C>
         self
     }
 
-    pub fn run(mut self) -> Result<(DocumentId, Revision), Error> {
+    pub fn run(self) -> Result<(DocumentId, Revision), Error> {
+        let (doc_id, rev, _) = try!(self.run_with_outcome());
+        Ok((doc_id, rev))
+    }
+
+    /// Executes the action, waits for the result, and also returns how
+    /// durably the server stored the document—see `WriteOutcome`.
+    pub fn run_with_outcome(mut self) -> Result<(DocumentId, Revision, WriteOutcome), Error> {
         self.transport.send(try!(self.make_request()),
                             JsonResponseDecoder::new(handle_response))
     }
@@ -57,11 +64,15 @@ impl<'a, C, P, T> CreateDocument<'a, T, P, C>
     }
 }
 
-fn handle_response(response: JsonResponse) -> Result<(DocumentId, Revision), Error> {
+fn handle_response(response: JsonResponse) -> Result<(DocumentId, Revision, WriteOutcome), Error> {
     match response.status_code() {
         StatusCode::Created => {
             let content: WriteDocumentResponse = try!(response.decode_content());
-            Ok((content.doc_id, content.revision))
+            Ok((content.doc_id, content.revision, WriteOutcome::Committed))
+        }
+        StatusCode::Accepted => {
+            let content: WriteDocumentResponse = try!(response.decode_content());
+            Ok((content.doc_id, content.revision, WriteOutcome::AcceptedNotDurable))
         }
 
         StatusCode::Conflict => Err(Error::document_conflict(&response)),
@@ -73,7 +84,7 @@ fn handle_response(response: JsonResponse) -> Result<(DocumentId, Revision), Err
 #[cfg(test)]
 mod tests {
 
-    use {DocumentId, Error, Revision, serde_json};
+    use {DocumentId, Error, Revision, WriteOutcome, serde_json};
     use super::*;
     use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
 
@@ -121,7 +132,23 @@ mod tests {
             .with_json_content_raw(r#"{"ok":true, "id": "foo", "rev": "1-1234567890abcdef1234567890abcdef"}"#)
             .unwrap();
 
-        let expected = (DocumentId::from("foo"), Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap());
+        let expected = (DocumentId::from("foo"),
+                        Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
+                        WriteOutcome::Committed);
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_accepted() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Accepted)
+            .with_json_content_raw(r#"{"ok":true, "id": "foo", "rev": "1-1234567890abcdef1234567890abcdef"}"#)
+            .unwrap();
+
+        let expected = (DocumentId::from("foo"),
+                        Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
+                        WriteOutcome::AcceptedNotDurable);
         let got = super::handle_response(response).unwrap();
         assert_eq!(expected, got);
     }
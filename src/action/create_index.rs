@@ -0,0 +1,180 @@
+//! Defines an action for creating a Mango index.
+
+use {DatabasePath, Error, IndexDef, IndexOutcome, IntoDatabasePath, serde_json, std};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Creates a Mango index, unless an identical index already exists.
+///
+/// Chill creates the index by sending an HTTP request to `POST /db/_index`
+/// with the index definition in the request body. The server's `result` field
+/// tells whether the server created the index or whether it already existed,
+/// so creating an index is idempotent—e.g., provisioning code may declare its
+/// required indexes on every start.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when creating an index.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::ServerResponse</code></td>
+///  <td>The index definition is invalid—e.g., it has no fields.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to create the index.</td>
+/// </tr>
+/// </table>
+///
+pub struct CreateIndex<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    index: &'a IndexDef,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> CreateIndex<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P, index: &'a IndexDef) -> Self {
+        CreateIndex {
+            transport: transport,
+            db_path: Some(db_path),
+            index: index,
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<IndexOutcome, Error> {
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+        let request = try!(self.make_request(&db_path));
+        self.transport.send(request, JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&self, db_path: &DatabasePath) -> Result<Request, Error> {
+        self.transport
+            .post(db_path.iter().chain(std::iter::once("_index")))
+            .with_accept_json()
+            .with_json_content(&self.index.to_json())
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<IndexOutcome, Error> {
+    match response.status_code() {
+        StatusCode::Ok | StatusCode::Created => {
+            let body: serde_json::Value = try!(response.decode_content());
+            let design_doc = match body.find("id").and_then(|x| x.as_str()) {
+                Some(x) => String::from(x),
+                None => return Err(Error::UnexpectedResponse("The index response has no design document id")),
+            };
+            let name = match body.find("name").and_then(|x| x.as_str()) {
+                Some(x) => String::from(x),
+                None => return Err(Error::UnexpectedResponse("The index response has no index name")),
+            };
+            match body.find("result").and_then(|x| x.as_str()) {
+                Some("created") => {
+                    Ok(IndexOutcome::Created {
+                        design_doc: design_doc,
+                        name: name,
+                    })
+                }
+                Some("exists") => {
+                    Ok(IndexOutcome::AlreadyExists {
+                        design_doc: design_doc,
+                        name: name,
+                    })
+                }
+                _ => Err(Error::UnexpectedResponse("The index response has no recognized result")),
+            }
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {Error, IndexDef, IndexOutcome, IntoDatabasePath, serde_json};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let index = IndexDef::new(vec!["type", "created_at"]);
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_object("index",
+                           |x| x.insert_array("fields", |x| x.push("type").push("created_at")))
+            .insert("type", "json")
+            .build();
+
+        let expected = transport.post(vec!["foo", "_index"]).with_accept_json().with_json_content(&body).unwrap();
+        let got = CreateIndex::new(&transport, "/foo", &index).make_request(&"/foo".into_database_path().unwrap());
+        assert_eq!(expected, got.unwrap());
+    }
+
+    #[test]
+    fn make_request_with_options() {
+
+        let transport = MockTransport::new();
+        let selector = serde_json::builder::ObjectBuilder::new().insert("archived", false).build();
+        let index = IndexDef::new(vec!["type"])
+            .with_name("type-index")
+            .with_design_document("by-type")
+            .with_partial_filter_selector(selector);
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_object("index", |x| {
+                x.insert_array("fields", |x| x.push("type"))
+                    .insert_object("partial_filter_selector", |x| x.insert("archived", false))
+            })
+            .insert("name", "type-index")
+            .insert("ddoc", "by-type")
+            .insert("type", "json")
+            .build();
+
+        let expected = transport.post(vec!["foo", "_index"]).with_accept_json().with_json_content(&body).unwrap();
+        let got = CreateIndex::new(&transport, "/foo", &index).make_request(&"/foo".into_database_path().unwrap());
+        assert_eq!(expected, got.unwrap());
+    }
+
+    #[test]
+    fn handle_response_ok_created() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"result":"created","id":"_design/by-type","name":"type-index"}"#)
+            .unwrap();
+        let expected = IndexOutcome::Created {
+            design_doc: String::from("_design/by-type"),
+            name: String::from("type-index"),
+        };
+        assert_eq!(expected, super::handle_response(response).unwrap());
+    }
+
+    #[test]
+    fn handle_response_ok_exists() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"result":"exists","id":"_design/by-type","name":"type-index"}"#)
+            .unwrap();
+        let got = super::handle_response(response).unwrap();
+        assert!(!got.is_created());
+        assert_eq!("_design/by-type", got.design_document());
+        assert_eq!("type-index", got.name());
+    }
+
+    #[test]
+    fn handle_response_nok_bad_request() {
+        let response = JsonResponseBuilder::new(StatusCode::BadRequest)
+            .with_json_content_raw(r#"{"error":"invalid_index_fields","reason":"Missing required key: fields"}"#)
+            .unwrap();
+        match super::handle_response(response) {
+            Err(Error::ServerResponse { status_code: StatusCode::BadRequest, .. }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Defines an action for deleting a database.
+
+use {Error, IntoDatabasePath, std};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Deletes a database, including all its documents.
+///
+/// Chill deletes the database by sending an HTTP request to `DELETE /db`. The
+/// deletion cannot be undone.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when deleting a database.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::Forbidden</code></td>
+///  <td>The server refuses to delete the database—e.g., a system
+///  database.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to delete the database.</td>
+/// </tr>
+/// </table>
+///
+pub struct DeleteDatabase<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+}
+
+impl<'a, P: IntoDatabasePath, T: Transport + 'a> DeleteDatabase<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P) -> Self {
+        DeleteDatabase {
+            transport: transport,
+            db_path: Some(db_path),
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<(), Error> {
+        self.transport.send(try!(self.make_request()),
+                            JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+        Ok(self.transport.delete(db_path.iter()).with_accept_json())
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<(), Error> {
+    match response.status_code() {
+        StatusCode::Ok | StatusCode::Accepted => Ok(()),
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        StatusCode::Forbidden => Err(Error::forbidden(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use Error;
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.delete(vec!["foo"]).with_accept_json();
+
+        let got = {
+            let mut action = DeleteDatabase::new(&transport, "/foo");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"ok":true}"#)
+            .unwrap();
+        super::handle_response(response).unwrap();
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Database does not exist."}"#)
+            .unwrap();
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" &&
+                                                        error_response.reason() == "Database does not exist." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(r#"{"error": "unauthorized", "reason": "You are not a server admin."}"#)
+            .unwrap();
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(ref error_response)) if error_response.error() == "unauthorized" &&
+                                                            error_response.reason() ==
+                                                            "You are not a server admin." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use {Error, IntoDocumentPath, Revision, std};
+use {Error, IntoDocumentPath, Revision, WriteOutcome, std};
 use action::query_keys::*;
 use document::WriteDocumentResponse;
 use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
@@ -21,7 +21,14 @@ impl<'a, P: IntoDocumentPath, T: Transport + 'a> DeleteDocument<'a, T, P> {
         }
     }
 
-    pub fn run(mut self) -> Result<Revision, Error> {
+    pub fn run(self) -> Result<Revision, Error> {
+        let (rev, _) = try!(self.run_with_outcome());
+        Ok(rev)
+    }
+
+    /// Executes the action, waits for the result, and also returns how
+    /// durably the server stored the write—see `WriteOutcome`.
+    pub fn run_with_outcome(mut self) -> Result<(Revision, WriteOutcome), Error> {
         self.transport.send(try!(self.make_request()),
                             JsonResponseDecoder::new(handle_response))
     }
@@ -32,11 +39,15 @@ impl<'a, P: IntoDocumentPath, T: Transport + 'a> DeleteDocument<'a, T, P> {
     }
 }
 
-fn handle_response(response: JsonResponse) -> Result<Revision, Error> {
+fn handle_response(response: JsonResponse) -> Result<(Revision, WriteOutcome), Error> {
     match response.status_code() {
         StatusCode::Ok => {
             let body: WriteDocumentResponse = try!(response.decode_content());
-            Ok(body.revision)
+            Ok((body.revision, WriteOutcome::Committed))
+        }
+        StatusCode::Accepted => {
+            let body: WriteDocumentResponse = try!(response.decode_content());
+            Ok((body.revision, WriteOutcome::AcceptedNotDurable))
         }
         StatusCode::Conflict => Err(Error::document_conflict(&response)),
         StatusCode::NotFound => Err(Error::not_found(&response)),
@@ -48,7 +59,7 @@ fn handle_response(response: JsonResponse) -> Result<Revision, Error> {
 #[cfg(test)]
 mod tests {
 
-    use {Error, Revision};
+    use {Error, Revision, WriteOutcome};
     use super::*;
     use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
 
@@ -76,7 +87,20 @@ mod tests {
             .with_json_content_raw(r#"{"ok":true,"id":"bar","rev":"42-1234567890abcdef1234567890abcdef"}"#)
             .unwrap();
 
-        let expected = Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap();
+        let expected = (Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(), WriteOutcome::Committed);
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_accepted() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Accepted)
+            .with_json_content_raw(r#"{"ok":true,"id":"bar","rev":"42-1234567890abcdef1234567890abcdef"}"#)
+            .unwrap();
+
+        let expected = (Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
+                        WriteOutcome::AcceptedNotDurable);
         let got = super::handle_response(response).unwrap();
         assert_eq!(expected, got);
     }
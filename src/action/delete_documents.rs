@@ -0,0 +1,140 @@
+//! Defines an action for deleting multiple documents by id from the CouchDB
+//! server.
+
+use {BulkDocsResult, DocumentId, Error, IntoDatabasePath, serde_json, std};
+use action::WriteDocuments;
+use action::read_revisions;
+use transport::{JsonResponseDecoder, Transport};
+
+/// Deletes multiple documents by id from the CouchDB server and returns the
+/// result.
+///
+/// Chill deletes the documents in two steps. First, it reads each document's
+/// current revision by sending an HTTP request to `POST /db/_all_docs` with
+/// the document ids as the `keys` field of the request body. Second, it
+/// deletes the documents by sending an HTTP request to `POST /db/_bulk_docs`
+/// with each document marked as deleted.
+///
+/// The result contains one entry per document id, in the same order as the
+/// ids. The action succeeds even if some documents fail to be deleted—check
+/// each `BulkDocsResult`. A document that does not exist, or is already
+/// deleted, yields a `not_found` error response in its entry.
+///
+/// The deletion is not atomic. If another client updates a document between
+/// the two steps, then that document's entry has a `conflict` error response.
+///
+pub struct DeleteDocuments<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    doc_ids: Vec<DocumentId>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> DeleteDocuments<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P, doc_ids: Vec<DocumentId>) -> Self {
+        DeleteDocuments {
+            transport: transport,
+            db_path: Some(db_path),
+            doc_ids: doc_ids,
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Vec<BulkDocsResult>, Error> {
+
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+
+        if self.doc_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let request = try!(read_revisions::make_request(self.transport, &db_path, &self.doc_ids));
+        let revisions = try!(self.transport.send(request, JsonResponseDecoder::new(read_revisions::handle_response)));
+
+        if revisions.len() != self.doc_ids.len() {
+            return Err(Error::UnexpectedResponse("The number of rows does not match the number of keys"));
+        }
+
+        let docs = self.doc_ids
+            .iter()
+            .zip(revisions.iter())
+            .filter_map(|(doc_id, revision)| {
+                revision.as_ref().ok().map(|revision| {
+                    serde_json::builder::ObjectBuilder::new()
+                        .insert("_id", doc_id)
+                        .insert("_rev", revision)
+                        .insert("_deleted", true)
+                        .build()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut written = if docs.is_empty() {
+            Vec::new().into_iter()
+        } else {
+            try!(WriteDocuments::new(self.transport, db_path, &docs).run()).into_iter()
+        };
+
+        let doc_ids = std::mem::replace(&mut self.doc_ids, Vec::new());
+        doc_ids.into_iter()
+            .zip(revisions.into_iter())
+            .map(|(doc_id, revision)| {
+                match revision {
+                    Ok(_) => {
+                        written.next()
+                            .ok_or(Error::UnexpectedResponse("The bulk write has fewer results than documents"))
+                    }
+                    Err(error_response) => Ok(BulkDocsResult::new_error(Some(doc_id), error_response)),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {BulkDocsResult, DocumentId, ErrorResponse, Revision};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn run_reports_missing_documents_per_row() {
+
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"total_rows": 1, "offset": 0, "rows": [
+                    {"key": "qux", "error": "not_found"},
+                    {"id": "bar", "key": "bar", "value": {"rev": "1-1234567890abcdef1234567890abcdef"}}
+                ]}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Created)
+                .with_json_content_raw(r#"[{"ok": true, "id": "bar", "rev": "2-1234567890abcdef1234567890abcdef"}]"#)
+                .unwrap());
+
+        let expected = vec![BulkDocsResult::new_error(Some(DocumentId::from("qux")),
+                                                      ErrorResponse::new("not_found", "missing")),
+                            BulkDocsResult::new_ok(DocumentId::from("bar"),
+                                                   Revision::parse("2-1234567890abcdef1234567890abcdef").unwrap())];
+
+        let got = DeleteDocuments::new(&transport, "/foo", vec![DocumentId::from("qux"), DocumentId::from("bar")])
+            .run()
+            .unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn run_skips_bulk_write_if_no_documents_exist() {
+
+        // The mock transport panics if sent a second request.
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"total_rows": 0, "offset": 0, "rows": [{"key": "qux", "error": "not_found"}]}"#)
+            .unwrap());
+
+        let expected = vec![BulkDocsResult::new_error(Some(DocumentId::from("qux")),
+                                                      ErrorResponse::new("not_found", "missing"))];
+
+        let got = DeleteDocuments::new(&transport, "/foo", vec![DocumentId::from("qux")]).run().unwrap();
+        assert_eq!(expected, got);
+    }
+}
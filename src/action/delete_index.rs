@@ -0,0 +1,155 @@
+//! Defines an action for deleting a Mango index.
+
+use {DatabasePath, Error, IntoDatabasePath, std};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+// The name of the built-in index on document ids, which every database has
+// and which the server refuses to delete.
+const ALL_DOCS_INDEX_NAME: &'static str = "_all_docs";
+
+/// Deletes a Mango index.
+///
+/// Chill deletes the index by sending an HTTP request to `DELETE
+/// /db/_index/{ddoc}/json/{name}`. The design document may be given with or
+/// without its `_design/` prefix—e.g., as returned by
+/// `IndexOutcome::design_document`.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when deleting an index.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::InvalidOptions</code></td>
+///  <td>The index is the built-in <code>_all_docs</code> index, which cannot
+///  be deleted, or the design document is empty.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database or the index does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to delete the index.</td>
+/// </tr>
+/// </table>
+///
+pub struct DeleteIndex<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    design_doc: &'a str,
+    name: &'a str,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> DeleteIndex<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P, design_doc: &'a str, name: &'a str) -> Self {
+        DeleteIndex {
+            transport: transport,
+            db_path: Some(db_path),
+            design_doc: design_doc,
+            name: name,
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<(), Error> {
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+        let request = try!(self.make_request(&db_path));
+        self.transport.send(request, JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&self, db_path: &DatabasePath) -> Result<Request, Error> {
+
+        let design_doc = self.design_doc.trim_left_matches("_design/");
+
+        if self.name == ALL_DOCS_INDEX_NAME {
+            return Err(Error::InvalidOptions("The built-in _all_docs index cannot be deleted"));
+        }
+
+        if design_doc.is_empty() {
+            return Err(Error::InvalidOptions("The index's design document must be nonempty"));
+        }
+
+        let path = db_path.iter().chain(vec!["_index", design_doc, "json", self.name]);
+        Ok(self.transport.delete(path).with_accept_json())
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<(), Error> {
+    match response.status_code() {
+        StatusCode::Ok => Ok(()),
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {Error, IntoDatabasePath};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.delete(vec!["foo", "_index", "by-type", "json", "type-index"]).with_accept_json();
+        let got = DeleteIndex::new(&transport, "/foo", "by-type", "type-index")
+            .make_request(&"/foo".into_database_path().unwrap());
+        assert_eq!(expected, got.unwrap());
+    }
+
+    #[test]
+    fn make_request_design_prefix() {
+        let transport = MockTransport::new();
+        let expected = transport.delete(vec!["foo", "_index", "by-type", "json", "type-index"]).with_accept_json();
+        let got = DeleteIndex::new(&transport, "/foo", "_design/by-type", "type-index")
+            .make_request(&"/foo".into_database_path().unwrap());
+        assert_eq!(expected, got.unwrap());
+    }
+
+    #[test]
+    fn make_request_nok_all_docs() {
+        let transport = MockTransport::new();
+        let got = DeleteIndex::new(&transport, "/foo", "by-type", "_all_docs")
+            .make_request(&"/foo".into_database_path().unwrap());
+        match got {
+            Err(Error::InvalidOptions(x)) if x.contains("_all_docs") => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn make_request_nok_empty_design_document() {
+        let transport = MockTransport::new();
+        for design_doc in vec!["", "_design/"] {
+            let got = DeleteIndex::new(&transport, "/foo", design_doc, "type-index")
+                .make_request(&"/foo".into_database_path().unwrap());
+            match got {
+                Err(Error::InvalidOptions(x)) if x.contains("design document") => (),
+                x @ _ => unexpected_result!(x),
+            }
+        }
+    }
+
+    #[test]
+    fn handle_response_ok() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"ok":true}"#)
+            .unwrap();
+        super::handle_response(response).unwrap();
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Index not found"}"#)
+            .unwrap();
+        match super::handle_response(response) {
+            Err(Error::NotFound(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 //! Defines an action for executing a view.
 
-use {DatabaseName, Error, IntoViewPath, ViewResponse, serde, std};
+use {DatabaseName, Design, DocumentId, Error, IntoViewPath, ReadPreference, ViewName, ViewResponse, ViewRow, serde,
+     serde_json, std};
 use action::query_keys::*;
 use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
 use view::ViewResponseJsonable;
@@ -27,6 +28,12 @@ enum GroupLevel {
 ///
 /// <table>
 /// <tr>
+///  <td><code>Error::InvalidOptions</code></td>
+///  <td>The action's options are inconsistent or, if the action validates
+///  against a design, the design has no such view or the view can't be
+///  reduced or grouped as requested.</td>
+/// </tr>
+/// <tr>
 ///  <td><code>Error::NotFound</code></td>
 ///  <td>The database, design document, or view does not exist.</td>
 /// </tr>
@@ -111,12 +118,20 @@ pub struct ExecuteView<'a, T, P, StartKey, EndKey>
     transport: &'a T,
     view_path: Option<P>,
     reduce: Option<bool>,
+    key: Option<serde_json::Value>,
     start_key: Option<StartKey>,
+    start_key_doc_id: Option<DocumentId>,
     end_key: Option<(EndKey, Inclusivity)>,
+    end_key_doc_id: Option<DocumentId>,
+    skip: Option<u64>,
     limit: Option<u64>,
     descending: Option<bool>,
     group_level: Option<GroupLevel>,
     include_docs: Option<bool>,
+    update_seq: Option<bool>,
+    read_preference: ReadPreference,
+    custom_query: Vec<(String, String)>,
+    design_views: Option<std::collections::HashMap<ViewName, bool>>,
 }
 
 impl<'a, P, T> ExecuteView<'a, T, P, (), ()>
@@ -129,12 +144,20 @@ impl<'a, P, T> ExecuteView<'a, T, P, (), ()>
             transport: transport,
             view_path: Some(view_path),
             reduce: None,
+            key: None,
             start_key: None,
+            start_key_doc_id: None,
             end_key: None,
+            end_key_doc_id: None,
+            skip: None,
             limit: None,
             descending: None,
             group_level: None,
             include_docs: None,
+            update_seq: None,
+            read_preference: ReadPreference::Fresh,
+            custom_query: Vec::new(),
+            design_views: None,
         }
     }
 }
@@ -197,6 +220,98 @@ impl<'a, EndKey, P, StartKey, T> ExecuteView<'a, T, P, StartKey, EndKey>
         self.include_docs = Some(yes_or_no);
         self
     }
+
+    /// Modifies the action to request the database's update sequence that the
+    /// view reflects.
+    ///
+    /// The `with_update_seq` method abstracts CouchDB's `update_seq` query
+    /// parameter. The sequence is available via
+    /// `ViewResponse::update_sequence`, which tells the application which
+    /// database state the result reflects—e.g., to start a changes feed from
+    /// there after paginating. By default, the CouchDB server omits the
+    /// sequence, which saves the server some work.
+    ///
+    pub fn with_update_seq(mut self, update_seq: bool) -> Self {
+        self.update_seq = Some(update_seq);
+        self
+    }
+
+    /// Modifies the action to start, among the rows whose key equals the start
+    /// key, at the row of the given document id.
+    ///
+    /// The `with_start_key_doc_id` method abstracts CouchDB's
+    /// `startkey_docid` query parameter. Rows with equal keys are sorted by
+    /// document id, so the document id breaks ties when paginating a view
+    /// whose keys are not unique. The option requires a start key—see
+    /// `with_start_key`—otherwise the action fails with
+    /// `Error::InvalidOptions`.
+    ///
+    pub fn with_start_key_doc_id<D: Into<DocumentId>>(mut self, doc_id: D) -> Self {
+        self.start_key_doc_id = Some(doc_id.into());
+        self
+    }
+
+    /// Modifies the action to end, among the rows whose key equals the end
+    /// key, at the row of the given document id.
+    ///
+    /// The `with_end_key_doc_id` method abstracts CouchDB's `endkey_docid`
+    /// query parameter. The option requires an end key—see
+    /// `with_end_key_inclusive`—otherwise the action fails with
+    /// `Error::InvalidOptions`.
+    ///
+    pub fn with_end_key_doc_id<D: Into<DocumentId>>(mut self, doc_id: D) -> Self {
+        self.end_key_doc_id = Some(doc_id.into());
+        self
+    }
+
+    /// Modifies the action to check its view and options against the given
+    /// design document before sending the request.
+    ///
+    /// The design must be the content of the view's design document—e.g., as
+    /// previously read via `ReadDocument`. When the action runs, it fails
+    /// with `Error::InvalidOptions` if the design has no view of the action's
+    /// view name or if the view has no reduce function but the action
+    /// explicitly reduces or groups the view. These errors occur without a
+    /// round trip to the server.
+    ///
+    /// The check is only as current as the given design. By default, the
+    /// action does no checking, and the server reports these errors.
+    ///
+    pub fn validate_against(mut self, design: &Design) -> Self {
+        self.design_views = Some(design.views
+            .iter()
+            .map(|(name, view_function)| (name.clone(), view_function.reduce.is_some()))
+            .collect());
+        self
+    }
+
+    /// Modifies the action to use the given read preference—see
+    /// `ReadPreference`.
+    ///
+    /// By default, the action uses the client's read preference, which is
+    /// `ReadPreference::Fresh` unless set via `Client::with_read_preference`.
+    ///
+    pub fn with_read_preference(mut self, read_preference: ReadPreference) -> Self {
+        self.read_preference = read_preference;
+        self
+    }
+
+    /// Modifies the action to include a query parameter that Chill does not
+    /// otherwise support—e.g., one added in a newer CouchDB version.
+    ///
+    /// Chill percent-encodes the key and value. If a typed option of the
+    /// action sets a parameter with the same key, then the typed option takes
+    /// precedence and this parameter is ignored. The action fails with an
+    /// error if the key is empty or if the key or value contains a control
+    /// character.
+    ///
+    pub fn with_query_param<K, V>(mut self, key: K, value: V) -> Self
+        where K: Into<String>,
+              V: Into<String>
+    {
+        self.custom_query.push((key.into(), value.into()));
+        self
+    }
 }
 
 impl<'a, EndKey, P, T> ExecuteView<'a, T, P, (), EndKey>
@@ -204,6 +319,18 @@ impl<'a, EndKey, P, T> ExecuteView<'a, T, P, (), EndKey>
           P: IntoViewPath,
           T: Transport + 'a
 {
+    /// Modifies the action to include only records with the given key.
+    ///
+    /// The `with_key` method abstracts CouchDB's `key` query parameter. The
+    /// key may be any serializable value—e.g., a string, a number, or a
+    /// `ComplexKey`—and the action JSON-encodes it, so a string key is sent as
+    /// `key=%22foo%22`. By default, the CouchDB server includes all records.
+    ///
+    pub fn with_key<K: serde::Serialize>(mut self, key: K) -> Self {
+        self.key = Some(serde_json::to_value(&key));
+        self
+    }
+
     /// Modifies the action to include only records with a key greater than or
     /// equal to a given key.
     ///
@@ -217,14 +344,47 @@ impl<'a, EndKey, P, T> ExecuteView<'a, T, P, (), EndKey>
             transport: self.transport,
             view_path: self.view_path,
             reduce: self.reduce,
+            key: self.key,
             start_key: Some(start_key),
+            start_key_doc_id: self.start_key_doc_id,
             end_key: self.end_key,
+            end_key_doc_id: self.end_key_doc_id,
+            skip: self.skip,
             limit: self.limit,
             descending: self.descending,
             group_level: self.group_level,
             include_docs: self.include_docs,
+            update_seq: self.update_seq,
+            read_preference: self.read_preference,
+            custom_query: self.custom_query,
+            design_views: self.design_views,
         }
     }
+
+    /// Modifies the action to start immediately after the given row—e.g., the
+    /// last row of the previous page.
+    ///
+    /// The `with_start_after_row` method sets the `startkey` and
+    /// `startkey_docid` query parameters from the row's key and document id
+    /// and skips the row itself. Unlike paginating by key alone, this neither
+    /// skips nor repeats rows when multiple rows have the same key.
+    ///
+    /// If the row has no key—e.g., the row of a reduced, ungrouped view—then
+    /// the action leaves both query parameters unset and only skips the row.
+    ///
+    pub fn with_start_after_row(self, row: &ViewRow) -> ExecuteView<'a, T, P, serde_json::Value, EndKey> {
+        let action = match row.raw_key() {
+            None => ExecuteView { start_key: None, ..self.with_start_key(serde_json::Value::Null) },
+            Some(key) => {
+                let action = self.with_start_key(key.clone());
+                match row.document_path() {
+                    None => action,
+                    Some(doc_path) => action.with_start_key_doc_id(doc_path.document_id().clone()),
+                }
+            }
+        };
+        ExecuteView { skip: Some(1), ..action }
+    }
 }
 
 impl<'a, P, StartKey, T> ExecuteView<'a, T, P, StartKey, ()>
@@ -245,12 +405,20 @@ impl<'a, P, StartKey, T> ExecuteView<'a, T, P, StartKey, ()>
             transport: self.transport,
             view_path: self.view_path,
             reduce: self.reduce,
+            key: self.key,
             start_key: self.start_key,
+            start_key_doc_id: self.start_key_doc_id,
             end_key: Some((end_key, Inclusivity::Inclusive)),
+            end_key_doc_id: self.end_key_doc_id,
+            skip: self.skip,
             limit: self.limit,
             descending: self.descending,
             group_level: self.group_level,
             include_docs: self.include_docs,
+            update_seq: self.update_seq,
+            read_preference: self.read_preference,
+            custom_query: self.custom_query,
+            design_views: self.design_views,
         }
     }
 
@@ -268,12 +436,20 @@ impl<'a, P, StartKey, T> ExecuteView<'a, T, P, StartKey, ()>
             transport: self.transport,
             view_path: self.view_path,
             reduce: self.reduce,
+            key: self.key,
             start_key: self.start_key,
+            start_key_doc_id: self.start_key_doc_id,
             end_key: Some((end_key, Inclusivity::Exclusive)),
+            end_key_doc_id: self.end_key_doc_id,
+            skip: self.skip,
             limit: self.limit,
             descending: self.descending,
             group_level: self.group_level,
             include_docs: self.include_docs,
+            update_seq: self.update_seq,
+            read_preference: self.read_preference,
+            custom_query: self.custom_query,
+            design_views: self.design_views,
         }
     }
 }
@@ -292,9 +468,34 @@ impl<'a, EndKey, P, StartKey, T> ExecuteView<'a, T, P, StartKey, EndKey>
 
     fn make_request(&mut self) -> Result<(Request, DatabaseName), Error> {
 
+        if self.start_key_doc_id.is_some() && self.start_key.is_none() {
+            return Err(Error::InvalidOptions("The start key document id requires a start key"));
+        }
+
+        if self.end_key_doc_id.is_some() && self.end_key.is_none() {
+            return Err(Error::InvalidOptions("The end key document id requires an end key"));
+        }
+
         let view_path = try!(std::mem::replace(&mut self.view_path, None).unwrap().into_view_path());
         let db_name = view_path.database_name().clone();
 
+        if let Some(ref design_views) = self.design_views {
+            let has_reduce = match design_views.get(view_path.attachment_name()) {
+                Some(x) => *x,
+                None => return Err(Error::InvalidOptions("The design has no such view")),
+            };
+            if !has_reduce && self.reduce == Some(true) {
+                return Err(Error::InvalidOptions("The view has no reduce function and can't be reduced"));
+            }
+            match self.group_level {
+                Some(GroupLevel::Exact(true)) |
+                Some(GroupLevel::Number(_)) if !has_reduce => {
+                    return Err(Error::InvalidOptions("The view has no reduce function and can't be grouped"));
+                }
+                _ => (),
+            }
+        }
+
         let request = self.transport.get(view_path.iter()).with_accept_json();
 
         let request = match self.reduce {
@@ -302,11 +503,21 @@ impl<'a, EndKey, P, StartKey, T> ExecuteView<'a, T, P, StartKey, EndKey>
             Some(ref yes_or_no) => request.with_query(ReduceQueryKey, yes_or_no),
         };
 
+        let request = match self.key {
+            None => request,
+            Some(ref key) => try!(request.with_query_fallible(KeyQueryKey, key)),
+        };
+
         let request = match self.start_key {
             None => request,
             Some(ref key) => try!(request.with_query_fallible(StartKeyQueryKey, key)),
         };
 
+        let request = match self.start_key_doc_id {
+            None => request,
+            Some(ref doc_id) => request.with_query(StartKeyDocIdQueryKey, doc_id),
+        };
+
         let request = match self.end_key {
             None => request,
             Some((ref key, Inclusivity::Inclusive)) => try!(request.with_query_fallible(EndKeyQueryKey, key)),
@@ -315,6 +526,16 @@ impl<'a, EndKey, P, StartKey, T> ExecuteView<'a, T, P, StartKey, EndKey>
             }
         };
 
+        let request = match self.end_key_doc_id {
+            None => request,
+            Some(ref doc_id) => request.with_query(EndKeyDocIdQueryKey, doc_id),
+        };
+
+        let request = match self.skip {
+            None => request,
+            Some(ref skip) => request.with_query(SkipQueryKey, skip),
+        };
+
         let request = match self.limit {
             None => request,
             Some(ref limit) => request.with_query(LimitQueryKey, limit),
@@ -336,6 +557,23 @@ impl<'a, EndKey, P, StartKey, T> ExecuteView<'a, T, P, StartKey, EndKey>
             Some(ref yes_or_no) => request.with_query(IncludeDocsQueryKey, yes_or_no),
         };
 
+        let request = match self.update_seq {
+            None => request,
+            Some(ref yes_or_no) => request.with_query(UpdateSeqQueryKey, yes_or_no),
+        };
+
+        let request = match self.read_preference.stable() {
+            None => request,
+            Some(ref yes_or_no) => request.with_query(StableQueryKey, yes_or_no),
+        };
+
+        let request = match self.read_preference.update() {
+            None => request,
+            Some(ref update) => request.with_query(UpdateQueryKey, update),
+        };
+
+        let request = try!(request.with_custom_query(&self.custom_query));
+
         Ok((request, db_name))
     }
 }
@@ -355,7 +593,7 @@ fn handle_response(response: JsonResponse, db_name: DatabaseName) -> Result<View
 #[cfg(test)]
 mod tests {
 
-    use {DatabaseName, Error};
+    use {ComplexKey, DatabaseName, DesignBuilder, Error, ViewFunction, ViewResponse, serde_json};
     use super::*;
     use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
     use view::ViewResponseBuilder;
@@ -486,6 +724,27 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn make_request_with_query_param() {
+        let transport = MockTransport::new();
+
+        let expected = (transport.get(vec!["foo", "_design", "bar", "_view", "qux"])
+            .with_accept_json()
+            .with_query_literal("limit", "42")
+            .with_query_literal("sorted", "false"),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux")
+                .with_query_param("limit", "17")
+                .with_limit(42)
+                .with_query_param("sorted", "false");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
     #[test]
     fn make_request_with_reduce() {
         let transport = MockTransport::new();
@@ -503,6 +762,59 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn make_request_with_read_preference_stable() {
+        let transport = MockTransport::new();
+
+        let expected = (transport.get(vec!["foo", "_design", "bar", "_view", "qux"])
+            .with_accept_json()
+            .with_query_literal("stable", "true"),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux")
+                .with_read_preference(ReadPreference::Stable);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_read_preference_stale() {
+        let transport = MockTransport::new();
+
+        let expected = (transport.get(vec!["foo", "_design", "bar", "_view", "qux"])
+            .with_accept_json()
+            .with_query_literal("stable", "true")
+            .with_query_literal("update", "false"),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux")
+                .with_read_preference(ReadPreference::Stale);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_read_preference_fresh_is_no_op() {
+        let transport = MockTransport::new();
+
+        let expected = (transport.get(vec!["foo", "_design", "bar", "_view", "qux"]).with_accept_json(),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux")
+                .with_read_preference(ReadPreference::Fresh);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
     #[test]
     fn make_request_with_start_key() {
         let transport = MockTransport::new();
@@ -521,6 +833,200 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn make_request_with_key() {
+        let transport = MockTransport::new();
+        let (request, _) = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux")
+            .with_key("foo")
+            .make_request()
+            .unwrap();
+        assert!(request.to_curl_command().contains("?key=%22foo%22"));
+        let expected = transport.get(vec!["foo", "_design", "bar", "_view", "qux"])
+            .with_accept_json()
+            .with_query_literal("key", r#""foo""#);
+        assert_eq!(expected, request);
+    }
+
+    #[test]
+    fn make_request_with_complex_keys() {
+        let transport = MockTransport::new();
+        let (request, _) = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux")
+            .with_key(ComplexKey::new().with("2024").with(3).with("foo"))
+            .make_request()
+            .unwrap();
+        assert!(request.to_curl_command().contains("?key=%5B%222024%22%2C3%2C%22foo%22%5D"));
+
+        let expected = (transport.get(vec!["foo", "_design", "bar", "_view", "qux"])
+            .with_accept_json()
+            .with_query_literal("startkey", r#"["2024",3]"#)
+            .with_query_literal("endkey", r#"["2024",3,{}]"#),
+                        DatabaseName::from("foo"));
+        let got = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux")
+            .with_start_key(ComplexKey::new().with("2024").with(3))
+            .with_end_key_inclusive(ComplexKey::new().with("2024").with(3).with_max())
+            .make_request()
+            .unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_key_doc_ids() {
+        let transport = MockTransport::new();
+
+        let expected = (transport.get(vec!["foo", "_design", "bar", "_view", "qux"])
+            .with_accept_json()
+            .with_query_literal("startkey", "7")
+            .with_query_literal("startkey_docid", "alpha")
+            .with_query_literal("endkey", "9")
+            .with_query_literal("endkey_docid", "bravo"),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux")
+                .with_start_key(7)
+                .with_end_key_inclusive(9)
+                .with_start_key_doc_id("alpha")
+                .with_end_key_doc_id("bravo");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_nok_start_key_doc_id_without_start_key() {
+        let transport = MockTransport::new();
+        let mut action = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux").with_start_key_doc_id("alpha");
+        match action.make_request() {
+            Err(Error::InvalidOptions(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn make_request_with_start_after_row() {
+        let transport = MockTransport::new();
+
+        let expected = (transport.get(vec!["foo", "_design", "bar", "_view", "qux"])
+            .with_accept_json()
+            .with_query_literal("startkey", "7")
+            .with_query_literal("startkey_docid", "alpha")
+            .with_query_literal("skip", "1")
+            .with_query_literal("limit", "10"),
+                        DatabaseName::from("foo"));
+
+        let page = ViewResponseBuilder::new_unreduced("foo", 2, 0)
+            .with_row("charlie", 6, 1)
+            .with_row("alpha", 7, 1)
+            .unwrap();
+
+        let got = {
+            let mut action = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux")
+                .with_start_after_row(page.rows().last().unwrap())
+                .with_limit(10);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_start_after_row_without_key() {
+        let transport = MockTransport::new();
+
+        let expected = (transport.get(vec!["foo", "_design", "bar", "_view", "qux"])
+            .with_accept_json()
+            .with_query_literal("skip", "1"),
+                        DatabaseName::from("foo"));
+
+        let decoded = serde_json::from_str(r#"{"rows":[{"value":42}]}"#).unwrap();
+        let page = ViewResponse::new_from_decoded(DatabaseName::from("foo"), decoded);
+
+        let got = {
+            let mut action = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux")
+                .with_start_after_row(page.rows().last().unwrap());
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_validate_against_ok() {
+        let transport = MockTransport::new();
+        let design = DesignBuilder::new()
+            .insert_view("qux", ViewFunction::new_with_reduce("function(doc) { emit(doc._id, 1); }", "_sum"))
+            .unwrap();
+
+        let expected = (transport.get(vec!["foo", "_design", "bar", "_view", "qux"])
+            .with_accept_json()
+            .with_query_literal("group_level", "1"),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux")
+                .with_group_level(1)
+                .validate_against(&design);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_validate_against_nok_missing_view() {
+        let transport = MockTransport::new();
+        let design = DesignBuilder::new()
+            .insert_view("other", ViewFunction::new("function(doc) { emit(doc._id, null); }"))
+            .unwrap();
+        let mut action = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux").validate_against(&design);
+        match action.make_request() {
+            Err(Error::InvalidOptions(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn make_request_validate_against_nok_no_reduce_function() {
+        let transport = MockTransport::new();
+        let design = DesignBuilder::new()
+            .insert_view("qux", ViewFunction::new("function(doc) { emit(doc._id, null); }"))
+            .unwrap();
+
+        let mut action = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux")
+            .with_reduce(true)
+            .validate_against(&design);
+        match action.make_request() {
+            Err(Error::InvalidOptions(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+
+        let mut action = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux")
+            .with_exact_groups(true)
+            .validate_against(&design);
+        match action.make_request() {
+            Err(Error::InvalidOptions(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn make_request_with_update_seq() {
+        let transport = MockTransport::new();
+
+        let expected = (transport.get(vec!["foo", "_design", "bar", "_view", "qux"])
+            .with_accept_json()
+            .with_query_literal("update_seq", "true"),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux").with_update_seq(true);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
     #[test]
     fn handle_response_ok_reduced() {
 
@@ -0,0 +1,286 @@
+//! Defines an action for exporting all documents in a database as
+//! newline-delimited JSON.
+
+use {DatabasePath, Error, IntoDatabasePath, serde_json, std};
+use action::query_keys::*;
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Exports all documents in a database as newline-delimited JSON and returns
+/// the number of documents exported.
+///
+/// Chill reads the documents one page at a time by sending HTTP requests to
+/// `GET /db/_all_docs?include_docs=true&attachments=true`, and it writes each
+/// document as one line of JSON, so the database may be larger than memory.
+///
+/// Each exported document keeps its `_rev` field and carries its attachments'
+/// content inline, as base64. To restore the output into an empty database,
+/// use the `ImportNdjson` action with new edits disabled—see
+/// `ImportNdjson::with_new_edits`. Otherwise, the server rejects every
+/// document that has a `_rev` field as a conflict.
+///
+/// By default, the export includes design documents and reads 1000 documents
+/// per request.
+///
+pub struct ExportNdjson<'a, T: Transport + 'a, P: IntoDatabasePath, W: std::io::Write> {
+    transport: &'a T,
+    db_path: Option<P>,
+    writer: W,
+    include_design: bool,
+    page_size: u64,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath, W: std::io::Write> ExportNdjson<'a, T, P, W> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P, writer: W) -> Self {
+        ExportNdjson {
+            transport: transport,
+            db_path: Some(db_path),
+            writer: writer,
+            include_design: true,
+            page_size: DEFAULT_PAGE_SIZE,
+        }
+    }
+
+    /// Modifies the action to include (or exclude) design documents.
+    pub fn with_design_documents(mut self, include_design: bool) -> Self {
+        self.include_design = include_design;
+        self
+    }
+
+    /// Modifies the action to read the given number of documents per request.
+    pub fn with_page_size(mut self, page_size: u64) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<u64, Error> {
+
+        if self.page_size == 0 {
+            return Err(Error::InvalidOptions("The page size must be nonzero"));
+        }
+
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+        let mut num_exported = 0;
+        let mut start_key = None;
+
+        loop {
+            let request = try!(self.make_request(&db_path, start_key.as_ref()));
+            let rows = try!(self.transport.send(request, JsonResponseDecoder::new(handle_response)));
+            let (n, next_start_key) = try!(self.write_page(rows));
+            num_exported += n;
+            start_key = match next_start_key {
+                None => return Ok(num_exported),
+                x @ Some(..) => x,
+            };
+        }
+    }
+
+    fn make_request(&self, db_path: &DatabasePath, start_key: Option<&String>) -> Result<Request, Error> {
+        // Read one extra row to learn where the next page starts.
+        let request = self.transport
+            .get(db_path.iter().chain(std::iter::once("_all_docs")))
+            .with_accept_json()
+            .with_query(IncludeDocsQueryKey, &true)
+            .with_query(AttachmentsQueryKey, &true)
+            .with_query(LimitQueryKey, &(self.page_size + 1));
+
+        match start_key {
+            None => Ok(request),
+            Some(key) => request.with_query_fallible(StartKeyQueryKey, key),
+        }
+    }
+
+    // Returns the number of documents written and the id of the first document
+    // of the next page, if any.
+    fn write_page(&mut self, mut rows: Vec<serde_json::Value>) -> Result<(u64, Option<String>), Error> {
+
+        let next_start_key = if rows.len() as u64 > self.page_size {
+            let next_row = rows.split_off(self.page_size as usize).into_iter().next().unwrap();
+            match next_row.find("id").and_then(|x| x.as_str()) {
+                Some(x) => Some(String::from(x)),
+                None => return Err(Error::UnexpectedResponse("The row has no document id")),
+            }
+        } else {
+            None
+        };
+
+        let mut num_written = 0;
+
+        for mut row in rows {
+
+            let doc = match row.as_object_mut().and_then(|x| x.remove("doc")) {
+                None |
+                Some(serde_json::Value::Null) => continue,
+                Some(doc) => doc,
+            };
+
+            let is_design = doc.find("_id").and_then(|x| x.as_str()).map_or(false, |x| x.starts_with("_design/"));
+            if is_design && !self.include_design {
+                continue;
+            }
+
+            try!(serde_json::to_writer(&mut self.writer, &doc).map_err(|e| Error::JsonEncode { cause: Box::new(e) }));
+            try!(self.writer.write_all(b"\n").map_err(|e| {
+                Error::Io {
+                    cause: e,
+                    description: "Failed to write the NDJSON output",
+                }
+            }));
+
+            num_written += 1;
+        }
+
+        Ok((num_written, next_start_key))
+    }
+}
+
+const DEFAULT_PAGE_SIZE: u64 = 1000;
+
+fn handle_response(response: JsonResponse) -> Result<Vec<serde_json::Value>, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let mut body: serde_json::Value = try!(response.decode_content());
+            match body.as_object_mut().and_then(|x| x.remove("rows")) {
+                Some(serde_json::Value::Array(rows)) => Ok(rows),
+                _ => Err(Error::UnexpectedResponse("The response has no rows")),
+            }
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {Error, IntoDatabasePath, serde_json, std};
+    use action::ImportNdjson;
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    fn make_rows(raw: &str) -> Vec<serde_json::Value> {
+        serde_json::from_str(raw).unwrap()
+    }
+
+    #[test]
+    fn make_request_first_page() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_all_docs"])
+            .with_accept_json()
+            .with_query_literal("include_docs", "true")
+            .with_query_literal("attachments", "true")
+            .with_query_literal("limit", "3");
+        let action = ExportNdjson::new(&transport, "/foo", Vec::new()).with_page_size(2);
+        let got = action.make_request(&"/foo".into_database_path().unwrap(), None).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_next_page() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_all_docs"])
+            .with_accept_json()
+            .with_query_literal("include_docs", "true")
+            .with_query_literal("attachments", "true")
+            .with_query_literal("limit", "3")
+            .with_query_literal("startkey", r#""bar""#);
+        let action = ExportNdjson::new(&transport, "/foo", Vec::new()).with_page_size(2);
+        let got = action.make_request(&"/foo".into_database_path().unwrap(), Some(&String::from("bar"))).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn write_page_full_page() {
+
+        let transport = MockTransport::new();
+        let mut output = Vec::new();
+
+        let got = {
+            let mut action = ExportNdjson::new(&transport, "/foo", &mut output).with_page_size(2);
+            action.write_page(make_rows(r#"[{"id": "a", "doc": {"_id": "a", "x": 1}},
+                                            {"id": "b", "doc": {"_id": "b", "x": 2}},
+                                            {"id": "c", "doc": {"_id": "c", "x": 3}}]"#))
+                .unwrap()
+        };
+
+        assert_eq!((2, Some(String::from("c"))), got);
+        assert_eq!(b"{\"_id\":\"a\",\"x\":1}\n{\"_id\":\"b\",\"x\":2}\n".to_vec(), output);
+    }
+
+    #[test]
+    fn write_page_last_page_without_design_documents() {
+
+        let transport = MockTransport::new();
+        let mut output = Vec::new();
+
+        let got = {
+            let mut action = ExportNdjson::new(&transport, "/foo", &mut output)
+                .with_page_size(2)
+                .with_design_documents(false);
+            action.write_page(make_rows(r#"[{"id": "_design/a", "doc": {"_id": "_design/a"}},
+                                            {"id": "b", "doc": {"_id": "b"}}]"#))
+                .unwrap()
+        };
+
+        assert_eq!((1, None), got);
+        assert_eq!(b"{\"_id\":\"b\"}\n".to_vec(), output);
+    }
+
+    #[test]
+    fn run_output_round_trips_through_import_without_new_edits() {
+
+        let docs = r#"[{"_id": "a", "_rev": "2-1234567890abcdef1234567890abcdef", "x": 1},
+                       {"_id": "b", "_rev": "1-abcdef1234567890abcdef1234567890",
+                        "_attachments": {"c": {"content_type": "text/plain", "revpos": 1,
+                                               "digest": "md5-uMyUdaBX6dHKJ7HrEjBwRQ==", "data": "YWJj"}}}]"#;
+        let docs: Vec<serde_json::Value> = serde_json::from_str(docs).unwrap();
+
+        let rows = serde_json::builder::ArrayBuilder::new()
+            .push_object(|x| x.insert("id", "a").insert("doc", &docs[0]))
+            .push_object(|x| x.insert("id", "b").insert("doc", &docs[1]))
+            .build();
+        let page = serde_json::builder::ObjectBuilder::new().insert("rows", rows).build();
+        let export_transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(serde_json::to_string(&page).unwrap())
+            .unwrap());
+
+        let mut output = Vec::new();
+        let num_exported = ExportNdjson::new(&export_transport, "/foo", &mut output).run().unwrap();
+        assert_eq!(2, num_exported);
+
+        let import_transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Created)
+            .with_json_content_raw("[]")
+            .unwrap());
+        let report = ImportNdjson::new(&import_transport, "/bar", std::io::Cursor::new(output), 10)
+            .with_new_edits(false)
+            .run()
+            .unwrap();
+        assert_eq!(2, report.documents_written());
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert("docs", &docs)
+            .insert("new_edits", false)
+            .build();
+        let expected = import_transport.post(vec!["bar", "_bulk_docs"])
+            .with_accept_json()
+            .with_json_content(&body)
+            .unwrap();
+        assert_eq!(vec![expected], import_transport.take_requests());
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"no_db_file"}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" &&
+                                                        error_response.reason() == "no_db_file" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
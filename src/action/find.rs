@@ -0,0 +1,803 @@
+//! Defines an action for querying documents with a Mango selector.
+
+use {DatabaseName, DatabasePath, Error, FindResponse, IntoDatabasePath, serde_json, std};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Queries documents with a Mango selector and returns the result.
+///
+/// Chill queries the documents by sending an HTTP request to `POST /db/_find`
+/// with the selector and other options in the request body. For more details
+/// about Mango queries, please see the CouchDB documentation.
+///
+/// The CouchDB server returns at most `limit` documents per request—25 by
+/// default. To page through a large result set, either resume a query via
+/// `with_bookmark` or use `into_pages` to follow bookmarks automatically.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when querying documents.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::FullScan</code></td>
+///  <td>The client fails on full scans—see
+///  <code>Client::fail_on_full_scan</code>—and no index matches the
+///  query.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read the database.</td>
+/// </tr>
+/// </table>
+///
+pub struct Find<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    query: Query<'a>,
+}
+
+struct Query<'a> {
+    selector: &'a serde_json::Value,
+    partition: Option<String>,
+    limit: Option<u64>,
+    skip: Option<u64>,
+    bookmark: Option<String>,
+    sort: Vec<SortSpec>,
+    use_index: Option<UseIndex>,
+    read_quorum: Option<u8>,
+    execution_stats: bool,
+    conflicts: bool,
+    custom_query: Vec<(String, String)>,
+}
+
+impl<'a> Query<'a> {
+    fn make_request<T: Transport>(&self, transport: &T, db_path: &DatabasePath) -> Result<Request, Error> {
+
+        if let Some(ref partition) = self.partition {
+            if partition.is_empty() || partition.contains(':') {
+                return Err(Error::InvalidOptions("The partition must be nonempty and contain no colon"));
+            }
+        }
+
+        if let Some(UseIndex { fields: Some(ref fields), .. }) = self.use_index {
+            if self.sort.iter().any(|x| !fields.contains(&x.field)) {
+                return Err(Error::InvalidOptions("The sort includes a field not covered by the index"));
+            }
+        }
+
+        let mut body = serde_json::builder::ObjectBuilder::new().insert("selector", self.selector);
+
+        if let Some(limit) = self.limit {
+            body = body.insert("limit", limit);
+        }
+
+        if let Some(skip) = self.skip {
+            body = body.insert("skip", skip);
+        }
+
+        if let Some(ref bookmark) = self.bookmark {
+            body = body.insert("bookmark", bookmark);
+        }
+
+        if !self.sort.is_empty() {
+            body = body.insert_array("sort", |mut x| {
+                for spec in self.sort.iter() {
+                    x = x.push_object(|y| y.insert(spec.field.clone(), spec.direction.as_str()));
+                }
+                x
+            });
+        }
+
+        if let Some(ref use_index) = self.use_index {
+            body = match use_index.name {
+                None => body.insert("use_index", &use_index.design_doc),
+                Some(ref name) => body.insert_array("use_index", |x| x.push(&use_index.design_doc).push(name)),
+            };
+        }
+
+        if let Some(read_quorum) = self.read_quorum {
+            body = body.insert("r", read_quorum);
+        }
+
+        if self.execution_stats {
+            body = body.insert("execution_stats", true);
+        }
+
+        if self.conflicts {
+            body = body.insert("conflicts", true);
+        }
+
+        let mut path = db_path.iter().collect::<Vec<_>>();
+        if let Some(ref partition) = self.partition {
+            path.push("_partition");
+            path.push(partition);
+        }
+        path.push("_find");
+
+        transport.post(path)
+            .with_accept_json()
+            .with_custom_query(&self.custom_query)
+            .and_then(|x| x.with_json_content(&body.build()))
+    }
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> Find<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P, selector: &'a serde_json::Value) -> Self {
+        Find {
+            transport: transport,
+            db_path: Some(db_path),
+            query: Query {
+                selector: selector,
+                partition: None,
+                limit: None,
+                skip: None,
+                bookmark: None,
+                sort: Vec::new(),
+                use_index: None,
+                read_quorum: None,
+                execution_stats: false,
+                conflicts: false,
+                custom_query: Vec::new(),
+            },
+        }
+    }
+
+    /// Modifies the action to query only the documents in the given
+    /// partition of a partitioned database.
+    ///
+    /// The `with_partition` method changes the request path to `POST
+    /// /db/_partition/{partition}/_find`, which lets the server query a
+    /// single shard instead of all of them. The action fails with an
+    /// `Error::InvalidOptions` error if the partition is empty or contains a
+    /// colon.
+    ///
+    pub fn with_partition<S: Into<String>>(mut self, partition: S) -> Self {
+        self.query.partition = Some(partition.into());
+        self
+    }
+
+    /// Modifies the action to retrieve at most a given number of documents.
+    ///
+    /// The `with_limit` method abstracts the `limit` field of the `_find`
+    /// request body. By default, the CouchDB server sends at most 25
+    /// documents.
+    ///
+    pub fn with_limit(mut self, limit: u64) -> Self {
+        self.query.limit = Some(limit);
+        self
+    }
+
+    /// Modifies the action to skip a given number of documents.
+    ///
+    /// The `with_skip` method abstracts the `skip` field of the `_find`
+    /// request body. Prefer `with_bookmark` for paging through large result
+    /// sets.
+    ///
+    pub fn with_skip(mut self, skip: u64) -> Self {
+        self.query.skip = Some(skip);
+        self
+    }
+
+    /// Modifies the action to resume a previous query after the last document
+    /// that query returned.
+    ///
+    /// The `with_bookmark` method abstracts the `bookmark` field of the
+    /// `_find` request body. The bookmark is available via
+    /// `FindResponse::bookmark`.
+    ///
+    pub fn with_bookmark(mut self, bookmark: &str) -> Self {
+        self.query.bookmark = Some(String::from(bookmark));
+        self
+    }
+
+    /// Modifies the action to sort the documents by the given fields.
+    ///
+    /// The `with_sort` method abstracts the `sort` field of the `_find` request
+    /// body. The CouchDB server requires an index covering the sort fields.
+    ///
+    pub fn with_sort(mut self, sort: Vec<SortSpec>) -> Self {
+        self.query.sort = sort;
+        self
+    }
+
+    /// Modifies the action to use a specific index.
+    ///
+    /// The `with_use_index` method abstracts the `use_index` field of the
+    /// `_find` request body. By default, the CouchDB server chooses the index.
+    ///
+    /// If the `UseIndex` specifies the fields the index covers, then the action
+    /// fails with an `Error::InvalidOptions` error if the sort includes a field
+    /// the index does not cover.
+    ///
+    pub fn with_use_index(mut self, use_index: UseIndex) -> Self {
+        self.query.use_index = Some(use_index);
+        self
+    }
+
+    /// Modifies the action to read each document from the given number of
+    /// replicas.
+    ///
+    /// The `with_read_quorum` method abstracts the `r` field of the `_find`
+    /// request body. By default, the CouchDB server reads from one replica.
+    ///
+    pub fn with_read_quorum(mut self, read_quorum: u8) -> Self {
+        self.query.read_quorum = Some(read_quorum);
+        self
+    }
+
+    /// Modifies the action to include statistics about how the server executed
+    /// the query.
+    ///
+    /// The `with_execution_stats` method abstracts the `execution_stats` field
+    /// of the `_find` request body. The statistics are available via
+    /// `FindResponse::execution_stats`. By default, the statistics are
+    /// disabled.
+    ///
+    pub fn with_execution_stats(mut self, execution_stats: bool) -> Self {
+        self.query.execution_stats = execution_stats;
+        self
+    }
+
+    /// Modifies the action to retrieve (or not retrieve) each document's
+    /// conflicting revisions.
+    ///
+    /// The `with_conflicts` method abstracts the `conflicts` field of the
+    /// `_find` request body. If enabled, the conflicting revisions are
+    /// available via the `Document::conflicts` method of each document. By
+    /// default, the conflicts are not retrieved.
+    ///
+    pub fn with_conflicts(mut self, conflicts: bool) -> Self {
+        self.query.conflicts = conflicts;
+        self
+    }
+
+    /// Modifies the action to include a query parameter that Chill does not
+    /// otherwise support.
+    ///
+    /// The parameter goes in the query string, whereas all typed options of
+    /// this action go in the request body, so the two never conflict. See
+    /// `ExecuteView::with_query_param` for how Chill encodes the parameter
+    /// and when the action fails.
+    ///
+    pub fn with_query_param<K, V>(mut self, key: K, value: V) -> Self
+        where K: Into<String>,
+              V: Into<String>
+    {
+        self.query.custom_query.push((key.into(), value.into()));
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<FindResponse, Error> {
+        let (request, db_name) = try!(self.make_request());
+        let fail_on_full_scan = self.transport.fail_on_full_scan();
+        self.transport.send(request,
+                            JsonResponseDecoder::new(move |response| {
+                                handle_response(response, db_name, fail_on_full_scan)
+                            }))
+    }
+
+    /// Converts the action into an iterator that executes the query one page
+    /// at a time, following bookmarks until the result set is exhausted.
+    ///
+    /// The iterator stops after a page containing fewer documents than the
+    /// limit, after an empty page, or after the first error.
+    ///
+    pub fn into_pages(mut self) -> FindPages<'a, T> {
+        let (db_path, error) = match std::mem::replace(&mut self.db_path, None).unwrap().into_database_path() {
+            Ok(x) => (Some(x), None),
+            Err(e) => (None, Some(e)),
+        };
+        FindPages {
+            transport: self.transport,
+            db_path: db_path,
+            error: error,
+            query: self.query,
+            done: false,
+        }
+    }
+
+    fn make_request(&mut self) -> Result<(Request, DatabaseName), Error> {
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+        let request = try!(self.query.make_request(self.transport, &db_path));
+        Ok((request, db_path.database_name().clone()))
+    }
+}
+
+/// Iterates through the pages of a Mango query—see `Find::into_pages`.
+pub struct FindPages<'a, T: Transport + 'a> {
+    transport: &'a T,
+    db_path: Option<DatabasePath>,
+    error: Option<Error>,
+    query: Query<'a>,
+    done: bool,
+}
+
+impl<'a, T: Transport + 'a> FindPages<'a, T> {
+    fn run_page(&self) -> Result<FindResponse, Error> {
+        let db_path = self.db_path.as_ref().unwrap();
+        let request = try!(self.query.make_request(self.transport, db_path));
+        let db_name = db_path.database_name().clone();
+        let fail_on_full_scan = self.transport.fail_on_full_scan();
+        self.transport.send(request,
+                            JsonResponseDecoder::new(move |response| {
+                                handle_response(response, db_name, fail_on_full_scan)
+                            }))
+    }
+
+    // Prepares the query for the next page and returns whether there may be a
+    // next page.
+    fn advance(&mut self, page: &FindResponse) -> bool {
+
+        let num_documents = page.documents().len() as u64;
+
+        if num_documents == 0 || num_documents < self.query.limit.unwrap_or(DEFAULT_LIMIT) {
+            return false;
+        }
+
+        match page.bookmark() {
+            None => false,
+            Some(bookmark) if self.query.bookmark.as_ref().map(|x| x.as_str()) == Some(bookmark) => false,
+            Some(bookmark) => {
+                self.query.bookmark = Some(String::from(bookmark));
+                self.query.skip = None;
+                true
+            }
+        }
+    }
+}
+
+impl<'a, T: Transport + 'a> Iterator for FindPages<'a, T> {
+    type Item = Result<FindResponse, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+
+        if self.done {
+            return None;
+        }
+
+        if let Some(e) = self.error.take() {
+            self.done = true;
+            return Some(Err(e));
+        }
+
+        let page = match self.run_page() {
+            Ok(x) => x,
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e));
+            }
+        };
+
+        self.done = !self.advance(&page);
+        Some(Ok(page))
+    }
+}
+
+const DEFAULT_LIMIT: u64 = 25;
+
+/// Specifies a field to sort a Mango query's documents by, and in which
+/// direction.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SortSpec {
+    field: String,
+    direction: SortDirection,
+}
+
+impl SortSpec {
+    /// Constructs a specification to sort by the given field in ascending
+    /// order.
+    pub fn ascending<S: Into<String>>(field: S) -> Self {
+        SortSpec {
+            field: field.into(),
+            direction: SortDirection::Ascending,
+        }
+    }
+
+    /// Constructs a specification to sort by the given field in descending
+    /// order.
+    pub fn descending<S: Into<String>>(field: S) -> Self {
+        SortSpec {
+            field: field.into(),
+            direction: SortDirection::Descending,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+impl SortDirection {
+    fn as_str(&self) -> &'static str {
+        match self {
+            &SortDirection::Ascending => "asc",
+            &SortDirection::Descending => "desc",
+        }
+    }
+}
+
+/// Specifies the index a Mango query should use.
+///
+/// `UseIndex` abstracts the `use_index` field of the `_find` request body. The
+/// index is either any index within a design document or a specific, named
+/// index within a design document.
+///
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UseIndex {
+    design_doc: String,
+    name: Option<String>,
+    fields: Option<Vec<String>>,
+}
+
+impl UseIndex {
+    /// Constructs a specification to use any index within the given design
+    /// document.
+    pub fn design_document<S: Into<String>>(design_doc: S) -> Self {
+        UseIndex {
+            design_doc: design_doc.into(),
+            name: None,
+            fields: None,
+        }
+    }
+
+    /// Constructs a specification to use the named index within the given
+    /// design document.
+    pub fn index<S1: Into<String>, S2: Into<String>>(design_doc: S1, name: S2) -> Self {
+        UseIndex {
+            design_doc: design_doc.into(),
+            name: Some(name.into()),
+            fields: None,
+        }
+    }
+
+    /// Declares the fields the index covers, so that the `Find` action can
+    /// reject a sort on other fields before sending the request.
+    pub fn with_fields<I, S>(mut self, fields: I) -> Self
+        where I: IntoIterator<Item = S>,
+              S: Into<String>
+    {
+        self.fields = Some(fields.into_iter().map(|x| x.into()).collect());
+        self
+    }
+}
+
+fn handle_response(response: JsonResponse,
+                   db_name: DatabaseName,
+                   fail_on_full_scan: bool)
+                   -> Result<FindResponse, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let find_response = try!(FindResponse::new_from_json(db_name, try!(response.decode_content())));
+            if fail_on_full_scan && find_response.is_full_scan() {
+                return Err(Error::FullScan { warning: String::from(find_response.warning().unwrap()) });
+            }
+            Ok(find_response)
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {DatabaseName, Error, Revision, serde_json};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    fn make_selector() -> serde_json::Value {
+        serde_json::builder::ObjectBuilder::new()
+            .insert_object("year", |x| x.insert("$gt", 2010))
+            .build()
+    }
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let selector = make_selector();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert("selector", &selector)
+            .build();
+
+        let expected = (transport.post(vec!["foo", "_find"]).with_accept_json().with_json_content(&body).unwrap(),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = Find::new(&transport, "/foo", &selector);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_limit_and_bookmark() {
+
+        let transport = MockTransport::new();
+        let selector = make_selector();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert("selector", &selector)
+            .insert("limit", 10)
+            .insert("bookmark", "g1AAAA")
+            .build();
+
+        let expected = (transport.post(vec!["foo", "_find"]).with_accept_json().with_json_content(&body).unwrap(),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = Find::new(&transport, "/foo", &selector).with_limit(10).with_bookmark("g1AAAA");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_sort_use_index_and_read_quorum() {
+
+        let transport = MockTransport::new();
+        let selector = make_selector();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert("selector", &selector)
+            .insert_array("sort", |x| {
+                x.push_object(|x| x.insert("year", "asc"))
+                    .push_object(|x| x.insert("title", "desc"))
+            })
+            .insert_array("use_index", |x| x.push("_design/movies").push("by-year"))
+            .insert("r", 2)
+            .build();
+
+        let expected = (transport.post(vec!["foo", "_find"]).with_accept_json().with_json_content(&body).unwrap(),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = Find::new(&transport, "/foo", &selector)
+                .with_sort(vec![SortSpec::ascending("year"), SortSpec::descending("title")])
+                .with_use_index(UseIndex::index("_design/movies", "by-year"))
+                .with_read_quorum(2);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_execution_stats() {
+
+        let transport = MockTransport::new();
+        let selector = make_selector();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert("selector", &selector)
+            .insert("execution_stats", true)
+            .build();
+
+        let expected = (transport.post(vec!["foo", "_find"]).with_accept_json().with_json_content(&body).unwrap(),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = Find::new(&transport, "/foo", &selector).with_execution_stats(true);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_conflicts() {
+
+        let transport = MockTransport::new();
+        let selector = make_selector();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert("selector", &selector)
+            .insert("conflicts", true)
+            .build();
+
+        let expected = (transport.post(vec!["foo", "_find"]).with_accept_json().with_json_content(&body).unwrap(),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = Find::new(&transport, "/foo", &selector).with_conflicts(true);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_use_index_design_document() {
+
+        let transport = MockTransport::new();
+        let selector = make_selector();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert("selector", &selector)
+            .insert("use_index", "_design/movies")
+            .build();
+
+        let expected = (transport.post(vec!["foo", "_find"]).with_accept_json().with_json_content(&body).unwrap(),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = Find::new(&transport, "/foo", &selector)
+                .with_use_index(UseIndex::design_document("_design/movies"));
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_partition() {
+
+        let transport = MockTransport::new();
+        let selector = make_selector();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert("selector", &selector)
+            .build();
+
+        let expected = (transport.post(vec!["foo", "_partition", "sensor-1", "_find"])
+            .with_accept_json()
+            .with_json_content(&body)
+            .unwrap(),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = Find::new(&transport, "/foo", &selector).with_partition("sensor-1");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_nok_bad_partition() {
+
+        let transport = MockTransport::new();
+        let selector = make_selector();
+
+        for partition in vec!["", "sensor:1"] {
+            let mut action = Find::new(&transport, "/foo", &selector).with_partition(partition);
+            match action.make_request() {
+                Err(Error::InvalidOptions(..)) => (),
+                x @ _ => unexpected_result!(x),
+            }
+        }
+    }
+
+    #[test]
+    fn make_request_nok_sort_not_covered_by_index() {
+
+        let transport = MockTransport::new();
+        let selector = make_selector();
+
+        let mut action = Find::new(&transport, "/foo", &selector)
+            .with_sort(vec![SortSpec::ascending("title")])
+            .with_use_index(UseIndex::index("_design/movies", "by-year").with_fields(vec!["year"]));
+
+        match action.make_request() {
+            Err(Error::InvalidOptions(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn find_pages_advance() {
+
+        let transport = MockTransport::new();
+        let selector = make_selector();
+        let mut pages = Find::new(&transport, "/foo", &selector).with_limit(1).into_pages();
+
+        let full_page = FindResponse::new_from_json(DatabaseName::from("foo"),
+                                                    serde_json::from_str(r#"{"docs": [{"_id": "bar",
+                                                    "_rev": "1-1234567890abcdef1234567890abcdef"}],
+                                                    "bookmark": "g1AAAA"}"#)
+                                                        .unwrap())
+            .unwrap();
+        assert!(pages.advance(&full_page));
+        assert_eq!(Some(String::from("g1AAAA")), pages.query.bookmark);
+
+        let short_page = FindResponse::new_from_json(DatabaseName::from("foo"),
+                                                     serde_json::from_str(r#"{"docs": [], "bookmark": "g1AAAB"}"#)
+                                                         .unwrap())
+            .unwrap();
+        assert!(!pages.advance(&short_page));
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"docs": [{"_id": "bar", "_rev": "1-1234567890abcdef1234567890abcdef"}],
+                                       "bookmark": "g1AAAA"}"#)
+            .unwrap();
+
+        let got = super::handle_response(response, DatabaseName::from("foo"), false).unwrap();
+        assert_eq!(1, got.documents().len());
+        assert_eq!(Some("g1AAAA"), got.bookmark());
+    }
+
+    #[test]
+    fn handle_response_ok_with_conflicts() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"docs": [{"_id": "bar", "_rev": "2-1234567890abcdef1234567890abcdef",
+                                                 "_conflicts": ["2-fedcba0987654321fedcba0987654321"]}]}"#)
+            .unwrap();
+
+        let got = super::handle_response(response, DatabaseName::from("foo"), false).unwrap();
+        let expected = vec![Revision::parse("2-fedcba0987654321fedcba0987654321").unwrap()];
+        assert_eq!(&expected, got.documents()[0].conflicts());
+    }
+
+    #[test]
+    fn handle_response_ok_full_scan_permitted() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"docs": [],
+                                       "warning": "no matching index found, create an index to optimize query time"}"#)
+            .unwrap();
+
+        let got = super::handle_response(response, DatabaseName::from("foo"), false).unwrap();
+        assert!(got.is_full_scan());
+    }
+
+    #[test]
+    fn handle_response_nok_full_scan() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"docs": [],
+                                       "warning": "no matching index found, create an index to optimize query time"}"#)
+            .unwrap();
+
+        match super::handle_response(response, DatabaseName::from("foo"), true) {
+            Err(Error::FullScan { ref warning }) if warning ==
+                                                    "no matching index found, create an index to optimize query \
+                                                     time" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"no_db_file"}"#)
+            .unwrap();
+
+        match super::handle_response(response, DatabaseName::from("foo"), false) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" &&
+                                                        error_response.reason() == "no_db_file" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(r#"{"error":"unauthorized","reason":"Authentication required."}"#)
+            .unwrap();
+
+        match super::handle_response(response, DatabaseName::from("foo"), false) {
+            Err(Error::Unauthorized(ref error_response)) if error_response.error() == "unauthorized" &&
+                                                            error_response.reason() == "Authentication required." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
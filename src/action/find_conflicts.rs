@@ -0,0 +1,135 @@
+//! Defines an action for finding documents that have conflicting revisions.
+
+use {ConflictedDoc, Error, IntoDatabasePath, serde_json, std};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Finds documents that have conflicting revisions and returns the result.
+///
+/// Chill finds the documents by sending an HTTP request to `POST /db/_find`
+/// with a selector matching documents that have a `_conflicts` field. Such
+/// documents typically result from replication or from a bulk write without
+/// new edits—see `WriteDocuments::with_new_edits`.
+///
+/// The result contains at most `limit` documents, each with its id, its
+/// current revision, and its conflicting revisions.
+///
+/// Without an index on `_conflicts`, the CouchDB server scans the whole
+/// database to answer the query, which is slow for large databases. Create a
+/// Mango index on `_conflicts`, or use a view in a design document, if the
+/// query runs often.
+///
+pub struct FindConflicts<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    limit: u64,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> FindConflicts<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P, limit: u64) -> Self {
+        FindConflicts {
+            transport: transport,
+            db_path: Some(db_path),
+            limit: limit,
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Vec<ConflictedDoc>, Error> {
+        let request = try!(self.make_request());
+        self.transport.send(request, JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_object("selector",
+                           |x| x.insert_object("_conflicts", |x| x.insert("$exists", true)))
+            .insert("conflicts", true)
+            .insert_array("fields", |x| x.push("_id").push("_rev").push("_conflicts"))
+            .insert("limit", self.limit)
+            .build();
+
+        self.transport
+            .post(db_path.iter().chain(std::iter::once("_find")))
+            .with_accept_json()
+            .with_json_content(&body)
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<Vec<ConflictedDoc>, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let mut body: serde_json::Value = try!(response.decode_content());
+            let docs = match body.as_object_mut().and_then(|x| x.remove("docs")) {
+                Some(serde_json::Value::Array(docs)) => docs,
+                _ => return Err(Error::UnexpectedResponse("The find response has no documents")),
+            };
+            docs.into_iter()
+                .map(|doc| serde_json::from_value(doc).map_err(|e| Error::JsonDecode { cause: Box::new(e) }))
+                .collect()
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {ConflictedDoc, DocumentId, Error, Revision, serde_json};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_object("selector",
+                           |x| x.insert_object("_conflicts", |x| x.insert("$exists", true)))
+            .insert("conflicts", true)
+            .insert_array("fields", |x| x.push("_id").push("_rev").push("_conflicts"))
+            .insert("limit", 10)
+            .build();
+
+        let expected = transport.post(vec!["foo", "_find"]).with_accept_json().with_json_content(&body).unwrap();
+        let got = FindConflicts::new(&transport, "/foo", 10).make_request().unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"docs": [{"_id": "bar", "_rev": "2-1234567890abcdef1234567890abcdef",
+                                                 "_conflicts": ["2-abcdef1234567890abcdef1234567890"]}],
+                                       "bookmark": "g1AAAA"}"#)
+            .unwrap();
+
+        let expected = vec![ConflictedDoc::new(DocumentId::from("bar"),
+                                               Revision::parse("2-1234567890abcdef1234567890abcdef").unwrap(),
+                                               vec![Revision::parse("2-abcdef1234567890abcdef1234567890").unwrap()])];
+
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"no_db_file"}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" &&
+                                                        error_response.reason() == "no_db_file" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Defines an action for importing documents from a newline-delimited JSON
+//! source.
+
+use {Error, ImportReport, IntoDatabasePath, serde_json, std};
+use action::WriteDocuments;
+use std::io::BufRead;
+use transport::Transport;
+
+/// Imports documents from a newline-delimited JSON source and returns a report
+/// of the outcome.
+///
+/// Each non-blank line of the source is one document, as a JSON object. Chill
+/// reads the source one batch at a time and writes each batch by sending an
+/// HTTP request to `POST /db/_bulk_docs`, so the source may be larger than
+/// memory.
+///
+/// A line that is not a JSON object does not abort the import. Instead, the
+/// line's number is recorded in the `ImportReport`, as are documents the
+/// server fails to write. The action fails only if reading the source fails, if
+/// a request fails as a whole, or if the batch size is zero.
+///
+/// By default, the server generates a new revision for each document, so a
+/// document that has a `_rev` field must match the document's current
+/// revision. To restore a backup into an empty database—e.g., the output of
+/// the `ExportNdjson` action—disable new edits via `with_new_edits`.
+///
+pub struct ImportNdjson<'a, T: Transport + 'a, P: IntoDatabasePath, R: std::io::Read> {
+    transport: &'a T,
+    db_path: Option<P>,
+    reader: std::io::BufReader<R>,
+    batch_size: usize,
+    new_edits: Option<bool>,
+    line_number: u64,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath, R: std::io::Read> ImportNdjson<'a, T, P, R> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P, reader: R, batch_size: usize) -> Self {
+        ImportNdjson {
+            transport: transport,
+            db_path: Some(db_path),
+            reader: std::io::BufReader::new(reader),
+            batch_size: batch_size,
+            new_edits: None,
+            line_number: 0,
+        }
+    }
+
+    /// Modifies the action to store each document's revision as is instead of
+    /// generating a new revision—see `WriteDocuments::with_new_edits`.
+    ///
+    /// With new edits disabled, the server reports only the documents it fails
+    /// to write, so the action counts every other document as written.
+    ///
+    pub fn with_new_edits(mut self, new_edits: bool) -> Self {
+        self.new_edits = Some(new_edits);
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<ImportReport, Error> {
+
+        if self.batch_size == 0 {
+            return Err(Error::InvalidOptions("The batch size must be nonzero"));
+        }
+
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+        let mut report = ImportReport::default();
+
+        loop {
+            let batch = try!(self.read_batch(&mut report));
+            if batch.is_empty() {
+                return Ok(report);
+            }
+            let action = WriteDocuments::new(self.transport, db_path.clone(), &batch);
+            let action = match self.new_edits {
+                None => action,
+                Some(new_edits) => action.with_new_edits(new_edits),
+            };
+            let results = try!(action.run());
+            if self.new_edits == Some(false) {
+                let failures = results.into_iter().filter(|x| !x.is_ok()).collect::<Vec<_>>();
+                report.add_documents_written(batch.len().saturating_sub(failures.len()) as u64);
+                for result in failures {
+                    report.add_result(result);
+                }
+            } else {
+                for result in results {
+                    report.add_result(result);
+                }
+            }
+        }
+    }
+
+    // Returns an empty batch if and only if the source is exhausted.
+    fn read_batch(&mut self, report: &mut ImportReport) -> Result<Vec<serde_json::Value>, Error> {
+
+        let mut batch = Vec::with_capacity(self.batch_size);
+        let mut line = Vec::new();
+
+        while batch.len() < self.batch_size {
+
+            line.clear();
+            let n = try!(self.reader.read_until(b'\n', &mut line).map_err(|e| {
+                Error::Io {
+                    cause: e,
+                    description: "Failed to read the NDJSON source",
+                }
+            }));
+            if n == 0 {
+                break;
+            }
+
+            self.line_number += 1;
+
+            if line.iter().all(|&b| (b as char).is_whitespace()) {
+                continue;
+            }
+
+            match serde_json::from_slice::<serde_json::Value>(&line) {
+                Ok(doc @ serde_json::Value::Object(..)) => batch.push(doc),
+                _ => report.add_malformed_line(self.line_number),
+            }
+        }
+
+        Ok(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {Error, ImportReport, serde_json, std};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn read_batch_splits_source_into_batches() {
+
+        let transport = MockTransport::new();
+        let source = b"{\"_id\": \"a\"}\n{\"_id\": \"b\"}\n\n{\"_id\": \"c\"}";
+        let mut action = ImportNdjson::new(&transport, "/foo", std::io::Cursor::new(&source[..]), 2);
+        let mut report = ImportReport::default();
+
+        let got = action.read_batch(&mut report).unwrap();
+        assert_eq!(vec![serde_json::builder::ObjectBuilder::new().insert("_id", "a").build(),
+                        serde_json::builder::ObjectBuilder::new().insert("_id", "b").build()],
+                   got);
+
+        let got = action.read_batch(&mut report).unwrap();
+        assert_eq!(vec![serde_json::builder::ObjectBuilder::new().insert("_id", "c").build()], got);
+
+        let got = action.read_batch(&mut report).unwrap();
+        assert!(got.is_empty());
+        assert!(report.malformed_lines().is_empty());
+    }
+
+    #[test]
+    fn read_batch_reports_malformed_lines() {
+
+        let transport = MockTransport::new();
+        let source = b"{\"_id\": \"a\"}\nnot json\n42\n{\"_id\": \"b\"}\n";
+        let mut action = ImportNdjson::new(&transport, "/foo", std::io::Cursor::new(&source[..]), 10);
+        let mut report = ImportReport::default();
+
+        let got = action.read_batch(&mut report).unwrap();
+        assert_eq!(2, got.len());
+        assert_eq!(&vec![2, 3], report.malformed_lines());
+    }
+
+    #[test]
+    fn run_ok_without_new_edits() {
+
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Created)
+            .with_json_content_raw("[]")
+            .unwrap());
+
+        let source = b"{\"_id\": \"a\", \"_rev\": \"2-1234567890abcdef1234567890abcdef\"}\n\
+                       {\"_id\": \"b\", \"_rev\": \"1-abcdef1234567890abcdef1234567890\"}\n";
+        let report = ImportNdjson::new(&transport, "/foo", std::io::Cursor::new(&source[..]), 10)
+            .with_new_edits(false)
+            .run()
+            .unwrap();
+        assert_eq!(2, report.documents_written());
+        assert!(report.failures().is_empty());
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_array("docs", |x| {
+                x.push_object(|x| x.insert("_id", "a").insert("_rev", "2-1234567890abcdef1234567890abcdef"))
+                    .push_object(|x| x.insert("_id", "b").insert("_rev", "1-abcdef1234567890abcdef1234567890"))
+            })
+            .insert("new_edits", false)
+            .build();
+        let expected = transport.post(vec!["foo", "_bulk_docs"]).with_accept_json().with_json_content(&body).unwrap();
+        assert_eq!(vec![expected], transport.take_requests());
+    }
+
+    #[test]
+    fn run_nok_zero_batch_size() {
+        let transport = MockTransport::new();
+        let action = ImportNdjson::new(&transport, "/foo", std::io::Cursor::new(&b""[..]), 0);
+        match action.run() {
+            Err(Error::InvalidOptions(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
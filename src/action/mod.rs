@@ -1,20 +1,94 @@
 pub mod create_database;
 pub mod create_document;
+pub mod create_index;
+pub mod delete_attachment;
+pub mod delete_database;
 pub mod delete_document;
+pub mod delete_documents;
+pub mod delete_index;
 pub mod execute_view;
+pub mod export_ndjson;
+pub mod find;
+pub mod find_conflicts;
+pub mod import_ndjson;
+pub mod ping;
+pub mod ping_database;
+pub mod pull_replication;
+pub mod put_attachment;
+pub mod raw_request;
+pub mod read_all_documents;
+pub mod read_attachment_range;
+pub mod read_capabilities;
+pub mod read_changes;
+pub mod read_config;
+pub mod read_database_info;
+pub mod read_databases_info;
+pub mod read_design_documents;
 pub mod read_document;
+pub mod read_document_revision;
+pub mod read_documents;
+pub mod read_indexes;
+pub mod read_local_document;
+pub mod read_local_documents;
+pub mod read_membership;
+pub mod read_purged_infos;
+pub mod read_revisions;
+pub mod read_revs_limit;
+pub mod read_scheduler_doc;
+pub mod read_session;
+pub mod read_stats;
+pub mod update_config;
 pub mod update_document;
+pub mod write_documents;
+pub mod write_local_document;
 
 pub use self::create_database::CreateDatabase;
 pub use self::create_document::CreateDocument;
+pub use self::create_index::CreateIndex;
+pub use self::delete_attachment::DeleteAttachment;
+pub use self::delete_database::DeleteDatabase;
 pub use self::delete_document::DeleteDocument;
+pub use self::delete_documents::DeleteDocuments;
+pub use self::delete_index::DeleteIndex;
 pub use self::execute_view::ExecuteView;
+pub use self::export_ndjson::ExportNdjson;
+pub use self::find::{Find, FindPages};
+pub use self::find_conflicts::FindConflicts;
+pub use self::import_ndjson::ImportNdjson;
+pub use self::ping::Ping;
+pub use self::ping_database::PingDatabase;
+pub use self::pull_replication::PullReplication;
+pub use self::put_attachment::{PutAttachment, PutAttachmentStream};
+pub use self::raw_request::RawRequest;
+pub use self::read_all_documents::ReadAllDocuments;
+pub use self::read_attachment_range::ReadAttachmentRange;
+pub use self::read_capabilities::ReadCapabilities;
+pub use self::read_changes::{ChangeEvent, ChangesFilter, CheckpointedChanges, ContinuousChanges, ReadChanges};
+pub use self::read_config::ReadConfig;
+pub use self::read_database_info::ReadDatabaseInfo;
+pub use self::read_databases_info::ReadDatabasesInfo;
+pub use self::read_design_documents::ReadDesignDocuments;
 pub use self::read_document::ReadDocument;
+pub use self::read_document_revision::ReadDocumentRevision;
+pub use self::read_documents::ReadDocuments;
+pub use self::read_indexes::ReadIndexes;
+pub use self::read_local_document::ReadLocalDocument;
+pub use self::read_local_documents::ReadLocalDocuments;
+pub use self::read_membership::ReadMembership;
+pub use self::read_purged_infos::ReadPurgedInfos;
+pub use self::read_revisions::ReadRevisions;
+pub use self::read_revs_limit::ReadRevsLimit;
+pub use self::read_scheduler_doc::ReadSchedulerDoc;
+pub use self::read_session::ReadSession;
+pub use self::read_stats::ReadStats;
+pub use self::update_config::UpdateConfig;
 pub use self::update_document::UpdateDocument;
+pub use self::write_documents::WriteDocuments;
+pub use self::write_local_document::WriteLocalDocument;
 
 pub mod query_keys {
 
-    use {Error, Revision, serde, transport};
+    use {DocumentId, Error, LocalRevision, Revision, Since, serde, serde_json, transport};
 
     macro_rules! define_query_key {
         ($key_name:ident, $key_str:expr) => {
@@ -58,6 +132,9 @@ pub mod query_keys {
     define_query_key!(AttachmentsQueryKey, "attachments");
     define_query_value_bool!(AttachmentsQueryKey);
 
+    define_query_key!(ConflictsQueryKey, "conflicts");
+    define_query_value_bool!(ConflictsQueryKey);
+
     define_query_key!(DescendingQueryKey, "descending");
     define_query_value_bool!(DescendingQueryKey);
 
@@ -68,7 +145,26 @@ pub mod query_keys {
         type Value = String;
         fn as_query_value_fallible(&self) -> Result<Self::Value, Error> {
             use serde_json;
-            serde_json::to_string(self).map_err(|e| Error::JsonEncode { cause: e })
+            serde_json::to_string(self).map_err(|e| Error::JsonEncode { cause: Box::new(e) })
+        }
+    }
+
+    define_query_key!(EndKeyDocIdQueryKey, "endkey_docid");
+    define_query_value_simple!(EndKeyDocIdQueryKey, DocumentId);
+
+    define_query_key!(FeedQueryKey, "feed");
+    impl transport::AsQueryValue<FeedQueryKey> for &'static str {
+        type Value = &'static str;
+        fn as_query_value(&self) -> Self::Value {
+            *self
+        }
+    }
+
+    define_query_key!(FilterQueryKey, "filter");
+    impl transport::AsQueryValue<FilterQueryKey> for &'static str {
+        type Value = &'static str;
+        fn as_query_value(&self) -> Self::Value {
+            *self
         }
     }
 
@@ -84,6 +180,17 @@ pub mod query_keys {
     define_query_key!(InclusiveEndQueryKey, "inclusive_end");
     define_query_value_bool!(InclusiveEndQueryKey);
 
+    define_query_key!(KeyQueryKey, "key");
+    impl transport::AsQueryValueFallible<KeyQueryKey> for serde_json::Value {
+        type Value = String;
+        fn as_query_value_fallible(&self) -> Result<Self::Value, Error> {
+            serde_json::to_string(self).map_err(|e| Error::JsonEncode { cause: Box::new(e) })
+        }
+    }
+
+    define_query_key!(LatestQueryKey, "latest");
+    define_query_value_bool!(LatestQueryKey);
+
     define_query_key!(LimitQueryKey, "limit");
     define_query_value_simple!(LimitQueryKey, u64);
 
@@ -97,6 +204,32 @@ pub mod query_keys {
             self.to_string()
         }
     }
+    impl transport::AsQueryValue<RevisionQueryKey> for LocalRevision {
+        type Value = String;
+        fn as_query_value(&self) -> Self::Value {
+            self.to_string()
+        }
+    }
+
+    define_query_key!(RevsQueryKey, "revs");
+    define_query_value_bool!(RevsQueryKey);
+
+    define_query_key!(RevsInfoQueryKey, "revs_info");
+    define_query_value_bool!(RevsInfoQueryKey);
+
+    define_query_key!(SinceQueryKey, "since");
+    impl transport::AsQueryValue<SinceQueryKey> for Since {
+        type Value = String;
+        fn as_query_value(&self) -> Self::Value {
+            String::from(self.as_str())
+        }
+    }
+
+    define_query_key!(SkipQueryKey, "skip");
+    define_query_value_simple!(SkipQueryKey, u64);
+
+    define_query_key!(StableQueryKey, "stable");
+    define_query_value_bool!(StableQueryKey);
 
     define_query_key!(StartKeyQueryKey, "startkey");
     impl<T> transport::AsQueryValueFallible<StartKeyQueryKey> for T
@@ -105,7 +238,37 @@ pub mod query_keys {
         type Value = String;
         fn as_query_value_fallible(&self) -> Result<Self::Value, Error> {
             use serde_json;
-            serde_json::to_string(self).map_err(|e| Error::JsonEncode { cause: e })
+            serde_json::to_string(self).map_err(|e| Error::JsonEncode { cause: Box::new(e) })
+        }
+    }
+
+    define_query_key!(StartKeyDocIdQueryKey, "startkey_docid");
+    define_query_value_simple!(StartKeyDocIdQueryKey, DocumentId);
+
+    define_query_key!(StyleQueryKey, "style");
+    impl transport::AsQueryValue<StyleQueryKey> for &'static str {
+        type Value = &'static str;
+        fn as_query_value(&self) -> Self::Value {
+            *self
+        }
+    }
+
+    define_query_key!(UpdateSeqQueryKey, "update_seq");
+    define_query_value_bool!(UpdateSeqQueryKey);
+
+    define_query_key!(UpdateQueryKey, "update");
+    impl transport::AsQueryValue<UpdateQueryKey> for &'static str {
+        type Value = &'static str;
+        fn as_query_value(&self) -> Self::Value {
+            *self
+        }
+    }
+
+    define_query_key!(ViewQueryKey, "view");
+    impl transport::AsQueryValue<ViewQueryKey> for String {
+        type Value = String;
+        fn as_query_value(&self) -> Self::Value {
+            self.clone()
         }
     }
 }
@@ -0,0 +1,236 @@
+//! Defines an action for checking whether the CouchDB server is up.
+
+use {Error, serde_json};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Checks whether the CouchDB server is up.
+///
+/// Chill pings the server by sending an HTTP request to `GET /_up`. If the
+/// server lacks the `/_up` endpoint—e.g., CouchDB 1.x—then Chill
+/// instead sends an HTTP request to `GET /`. The action succeeds if and only if
+/// the server reports it is healthy.
+///
+/// The client remembers whether the server has the `/_up` endpoint, so
+/// subsequent pings go straight to the supported endpoint. If the client has
+/// already detected the server's capabilities—see `ReadCapabilities`—then
+/// even the first ping goes straight to the supported endpoint.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when pinging the server.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::ServerResponse</code></td>
+///  <td>The server is not healthy—e.g., the server is in maintenance
+///  mode.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Transport</code></td>
+///  <td>The server is unreachable.</td>
+/// </tr>
+/// </table>
+///
+pub struct Ping<'a, T: Transport + 'a> {
+    transport: &'a T,
+}
+
+impl<'a, T: Transport + 'a> Ping<'a, T> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T) -> Self {
+        Ping { transport: transport }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<(), Error> {
+
+        let up_endpoint = self.transport.server_capabilities().and_then(|x| {
+            x.up_endpoint().or_else(|| x.probed().map(|x| x.has_up()))
+        });
+
+        if up_endpoint != Some(false) {
+            let status = match self.transport.send(self.make_up_request(),
+                                                   JsonResponseDecoder::new(handle_up_response)) {
+                // A proxy may reject the unknown endpoint with non-JSON
+                // content.
+                Err(Error::ServerResponse { status_code: StatusCode::NotImplemented, .. }) => UpStatus::Unsupported,
+                x @ _ => try!(x),
+            };
+            if let Some(capabilities) = self.transport.server_capabilities() {
+                capabilities.set_up_endpoint(status == UpStatus::Healthy);
+            }
+            if status == UpStatus::Healthy {
+                return Ok(());
+            }
+        }
+
+        self.transport.send(self.make_root_request(),
+                            JsonResponseDecoder::new(handle_root_response))
+    }
+
+    fn make_up_request(&mut self) -> Request {
+        self.transport.get(vec!["_up"]).with_accept_json()
+    }
+
+    fn make_root_request(&mut self) -> Request {
+        self.transport.get(Vec::<&str>::new()).with_accept_json()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum UpStatus {
+    Healthy,
+    Unsupported,
+}
+
+fn handle_up_response(response: JsonResponse) -> Result<UpStatus, Error> {
+    match response.status_code() {
+        StatusCode::Ok => Ok(UpStatus::Healthy),
+        StatusCode::NotImplemented => Ok(UpStatus::Unsupported),
+        StatusCode::BadRequest | StatusCode::NotFound => {
+            // A server that supports the `/_up` endpoint always sends a
+            // `status` field, even when unhealthy.
+            let content: serde_json::Value = try!(response.decode_content());
+            match content.find("status") {
+                None => Ok(UpStatus::Unsupported),
+                Some(_) => Err(Error::server_response(&response)),
+            }
+        }
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+fn handle_root_response(response: JsonResponse) -> Result<(), Error> {
+    match response.status_code() {
+        StatusCode::Ok => Ok(()),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {Capabilities, Error, serde_json};
+    use super::*;
+    use super::UpStatus;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_up_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["_up"]).with_accept_json();
+        let got = Ping::new(&transport).make_up_request();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_root_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.get(Vec::<&str>::new()).with_accept_json();
+        let got = Ping::new(&transport).make_root_request();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_up_response_ok() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"status":"ok"}"#)
+            .unwrap();
+        assert_eq!(UpStatus::Healthy, super::handle_up_response(response).unwrap());
+    }
+
+    #[test]
+    fn handle_up_response_unsupported() {
+        let response = JsonResponseBuilder::new(StatusCode::BadRequest)
+            .with_json_content_raw(r#"{"error":"illegal_database_name","reason":"Name: '_up'."}"#)
+            .unwrap();
+        assert_eq!(UpStatus::Unsupported, super::handle_up_response(response).unwrap());
+    }
+
+    #[test]
+    fn handle_up_response_not_implemented() {
+        let response = JsonResponseBuilder::new(StatusCode::NotImplemented)
+            .with_json_content_raw(r#"{"error":"not_implemented","reason":"Not implemented."}"#)
+            .unwrap();
+        assert_eq!(UpStatus::Unsupported, super::handle_up_response(response).unwrap());
+    }
+
+    #[test]
+    fn handle_up_response_maintenance_mode() {
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"status":"maintenance_mode"}"#)
+            .unwrap();
+        match super::handle_up_response(response) {
+            Err(Error::ServerResponse { status_code: StatusCode::NotFound, .. }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn run_ok_with_up_endpoint() {
+
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"status":"ok"}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"status":"ok"}"#)
+                .unwrap());
+
+        Ping::new(&transport).run().unwrap();
+        Ping::new(&transport).run().unwrap();
+
+        let expected = vec![transport.get(vec!["_up"]).with_accept_json(),
+                            transport.get(vec!["_up"]).with_accept_json()];
+        assert_eq!(expected, transport.take_requests());
+    }
+
+    #[test]
+    fn run_ok_without_up_endpoint() {
+
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::NotFound)
+                .with_json_content_raw(r#"{"error":"not_found","reason":"no_db_file"}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"couchdb":"Welcome","version":"1.6.1"}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"couchdb":"Welcome","version":"1.6.1"}"#)
+                .unwrap());
+
+        Ping::new(&transport).run().unwrap();
+        Ping::new(&transport).run().unwrap();
+
+        // The second ping skips the `/_up` endpoint.
+        let expected = vec![transport.get(vec!["_up"]).with_accept_json(),
+                            transport.get(Vec::<&str>::new()).with_accept_json(),
+                            transport.get(Vec::<&str>::new()).with_accept_json()];
+        assert_eq!(expected, transport.take_requests());
+    }
+
+    #[test]
+    fn run_ok_with_probed_capabilities() {
+
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"couchdb":"Welcome","version":"1.6.1"}"#)
+            .unwrap());
+
+        let info = serde_json::from_str(r#"{"couchdb":"Welcome","version":"1.6.1"}"#).unwrap();
+        let capabilities = Capabilities::from_server_info(&info).unwrap();
+        transport.server_capabilities().unwrap().set_probed(capabilities);
+
+        Ping::new(&transport).run().unwrap();
+
+        let expected = vec![transport.get(Vec::<&str>::new()).with_accept_json()];
+        assert_eq!(expected, transport.take_requests());
+    }
+
+    #[test]
+    fn handle_root_response_ok() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"couchdb":"Welcome","version":"1.6.1"}"#)
+            .unwrap();
+        super::handle_root_response(response).unwrap();
+    }
+}
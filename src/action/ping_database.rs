@@ -0,0 +1,80 @@
+//! Defines an action for checking whether a database is available.
+
+use {Error, IntoDatabasePath, std};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Checks whether a database is available.
+///
+/// Chill pings the database by sending an HTTP request to `HEAD /db`. The
+/// action succeeds if and only if the database exists and the client may
+/// access it. Because the response has no content, an unsuccessful result is
+/// always an `Error::ServerResponse` error.
+///
+pub struct PingDatabase<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+}
+
+impl<'a, P: IntoDatabasePath, T: Transport + 'a> PingDatabase<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P) -> Self {
+        PingDatabase {
+            transport: transport,
+            db_path: Some(db_path),
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<(), Error> {
+        self.transport.send(try!(self.make_request()),
+                            JsonResponseDecoder::new(handle_response).without_content_type_check())
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+        Ok(self.transport.head(db_path.iter()))
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<(), Error> {
+    match response.status_code() {
+        StatusCode::Ok => Ok(()),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use Error;
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.head(vec!["foo"]);
+
+        let got = {
+            let mut action = PingDatabase::new(&transport, "/foo");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok).unwrap();
+        super::handle_response(response).unwrap();
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+        let response = JsonResponseBuilder::new(StatusCode::NotFound).unwrap();
+        match super::handle_response(response) {
+            Err(Error::ServerResponse { status_code: StatusCode::NotFound, error_response: None, .. }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
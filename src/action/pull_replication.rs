@@ -0,0 +1,429 @@
+//! Defines an action for pulling one batch of changes from a source database
+//! into a target database.
+
+use {BulkDocsResult, DatabasePath, DocumentId, Error, ErrorResponse, IntoDatabasePath, ReplicationReport, Revision,
+     Since, serde_json, std};
+use action::{ReadChanges, WriteDocuments};
+use action::query_keys::*;
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Pulls one batch of changes from a source database into a target database
+/// and returns a report of the outcome.
+///
+/// The action is a minimal replicator, built from the same endpoints the
+/// CouchDB replicator uses:
+///
+/// 1. Chill reads at most `batch_size` changes from the source, including all
+///    leaf revisions, by sending an HTTP request to `GET
+///    /source/_changes?style=all_docs`.
+///
+/// 2. Chill asks the target which of those revisions it lacks by sending an
+///    HTTP request to `POST /target/_revs_diff`.
+///
+/// 3. Chill reads the missing revisions, with their revision histories and
+///    attachments, by sending an HTTP request to `POST
+///    /source/_bulk_get?revs=true&attachments=true`. This requires CouchDB
+///    v2.0 or later—see `Capabilities::has_bulk_get`.
+///
+/// 4. Chill writes the revisions to the target, as is, by sending an HTTP
+///    request to `POST /target/_bulk_docs` with new edits disabled.
+///
+/// Each step is skipped if the previous step yields nothing to do. A revision
+/// that fails to be read or written does not abort the action. Instead, it is
+/// recorded in the `ReplicationReport`.
+///
+/// The action does not store a checkpoint. To pull the next batch, pass the
+/// report's `last_seq` to `with_since`.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when pulling changes.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::InvalidOptions</code></td>
+///  <td>The batch size is zero.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The source or target database does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read the source or write the
+///  target.</td>
+/// </tr>
+/// </table>
+///
+pub struct PullReplication<'a, T: Transport + 'a, P: IntoDatabasePath, Q: IntoDatabasePath> {
+    source_transport: &'a T,
+    source_path: Option<P>,
+    target_transport: &'a T,
+    target_path: Option<Q>,
+    batch_size: u64,
+    since: Since,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath, Q: IntoDatabasePath> PullReplication<'a, T, P, Q> {
+    #[doc(hidden)]
+    pub fn new(source_transport: &'a T,
+               source_path: P,
+               target_transport: &'a T,
+               target_path: Q,
+               batch_size: u64)
+               -> Self {
+        PullReplication {
+            source_transport: source_transport,
+            source_path: Some(source_path),
+            target_transport: target_transport,
+            target_path: Some(target_path),
+            batch_size: batch_size,
+            since: Since::Zero,
+        }
+    }
+
+    /// Modifies the action to pull only the changes after the given update
+    /// sequence of the source.
+    ///
+    /// By default, the action starts at the beginning of the source's changes
+    /// feed.
+    ///
+    pub fn with_since<S: Into<Since>>(mut self, since: S) -> Self {
+        self.since = since.into();
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<ReplicationReport, Error> {
+
+        if self.batch_size == 0 {
+            return Err(Error::InvalidOptions("The batch size must be nonzero"));
+        }
+
+        let source_path = try!(std::mem::replace(&mut self.source_path, None).unwrap().into_database_path());
+        let target_path = try!(std::mem::replace(&mut self.target_path, None).unwrap().into_database_path());
+
+        let changes = try!(ReadChanges::new(self.source_transport, source_path.clone())
+            .with_since(self.since.clone())
+            .with_limit(self.batch_size)
+            .with_all_revisions(true)
+            .run());
+
+        let mut report = ReplicationReport::new(changes.last_seq().clone());
+        let mut leaves = Vec::new();
+        for row in changes.into_results() {
+            report.add_read(row.doc_id(), row.revisions().len());
+            leaves.push((row.doc_id().clone(), row.revisions().clone()));
+        }
+
+        if leaves.is_empty() {
+            return Ok(report);
+        }
+
+        let missing = try!(self.target_transport.send(try!(make_revs_diff_request(self.target_transport,
+                                                                                  &target_path,
+                                                                                  &leaves)),
+                                                      JsonResponseDecoder::new(handle_revs_diff_response)));
+
+        // Keep the changes-feed order so that the requests are deterministic.
+        let mut wanted = Vec::new();
+        for (doc_id, revisions) in leaves {
+            if let Some(missing_revisions) = missing.get(&doc_id) {
+                for rev in revisions.into_iter().filter(|x| missing_revisions.contains(x)) {
+                    wanted.push((doc_id.clone(), rev));
+                }
+            }
+        }
+
+        if wanted.is_empty() {
+            return Ok(report);
+        }
+
+        let fetched = try!(self.source_transport.send(try!(make_bulk_get_request(self.source_transport,
+                                                                                 &source_path,
+                                                                                 &wanted)),
+                                                      JsonResponseDecoder::new(handle_bulk_get_response)));
+
+        let mut docs = Vec::with_capacity(fetched.len());
+        for item in fetched {
+            match item {
+                Ok(doc) => docs.push(doc),
+                Err(failure) => report.add_failure(failure),
+            }
+        }
+
+        if docs.is_empty() {
+            return Ok(report);
+        }
+
+        let results = try!(WriteDocuments::new(self.target_transport, target_path, &docs).with_new_edits(false).run());
+
+        // With new edits disabled, the server sends only the failures.
+        let mut num_failures = 0;
+        for result in results {
+            if !result.is_ok() {
+                num_failures += 1;
+                report.add_failure(result);
+            }
+        }
+
+        report.add_written((docs.len() as u64).saturating_sub(num_failures));
+        Ok(report)
+    }
+}
+
+fn make_revs_diff_request<T: Transport>(transport: &T,
+                                        db_path: &DatabasePath,
+                                        leaves: &[(DocumentId, Vec<Revision>)])
+                                        -> Result<Request, Error> {
+
+    let body = leaves.iter()
+        .fold(serde_json::builder::ObjectBuilder::new(),
+              |body, &(ref doc_id, ref revisions)| body.insert(doc_id.to_string(), revisions))
+        .build();
+
+    transport.post(db_path.iter().chain(std::iter::once("_revs_diff")))
+        .with_accept_json()
+        .with_json_content(&body)
+}
+
+fn handle_revs_diff_response(response: JsonResponse)
+                             -> Result<std::collections::HashMap<DocumentId, Vec<Revision>>, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let body: serde_json::Value = try!(response.decode_content());
+            let body = match body {
+                serde_json::Value::Object(x) => x,
+                _ => return Err(Error::UnexpectedResponse("The revisions diff is not a JSON object")),
+            };
+            body.into_iter()
+                .map(|(doc_id, diff)| {
+                    let missing = match diff.find("missing").and_then(|x| x.as_array()) {
+                        Some(x) => x,
+                        None => return Err(Error::UnexpectedResponse("The revisions diff has no missing revisions")),
+                    };
+                    let missing = try!(missing.iter()
+                        .map(|x| match x.as_str() {
+                            Some(x) => Revision::parse(x),
+                            None => Err(Error::UnexpectedResponse("The revisions diff has a non-string revision")),
+                        })
+                        .collect::<Result<Vec<_>, _>>());
+                    Ok((DocumentId::from(doc_id), missing))
+                })
+                .collect()
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+fn make_bulk_get_request<T: Transport>(transport: &T,
+                                       db_path: &DatabasePath,
+                                       wanted: &[(DocumentId, Revision)])
+                                       -> Result<Request, Error> {
+
+    let body = serde_json::builder::ObjectBuilder::new()
+        .insert_array("docs", |x| {
+            wanted.iter().fold(x, |x, &(ref doc_id, ref rev)| {
+                x.push_object(|x| x.insert("id", doc_id).insert("rev", rev))
+            })
+        })
+        .build();
+
+    transport.post(db_path.iter().chain(std::iter::once("_bulk_get")))
+        .with_accept_json()
+        .with_query(RevsQueryKey, &true)
+        .with_query(AttachmentsQueryKey, &true)
+        .with_json_content(&body)
+}
+
+// Returns one item per requested revision: the document, with its revision
+// history, or the failure to read it.
+fn handle_bulk_get_response(response: JsonResponse)
+                            -> Result<Vec<Result<serde_json::Value, BulkDocsResult>>, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let mut body: serde_json::Value = try!(response.decode_content());
+            let results = match body.as_object_mut().and_then(|x| x.remove("results")) {
+                Some(serde_json::Value::Array(results)) => results,
+                _ => return Err(Error::UnexpectedResponse("The response has no results")),
+            };
+            let mut items = Vec::new();
+            for mut result in results {
+                let doc_id = match result.find("id").and_then(|x| x.as_str()) {
+                    Some(x) => DocumentId::from(x),
+                    None => return Err(Error::UnexpectedResponse("The result has no document id")),
+                };
+                let docs = match result.as_object_mut().and_then(|x| x.remove("docs")) {
+                    Some(serde_json::Value::Array(docs)) => docs,
+                    _ => return Err(Error::UnexpectedResponse("The result has no documents")),
+                };
+                for mut doc in docs {
+                    if let Some(x) = doc.as_object_mut().and_then(|x| x.remove("ok")) {
+                        items.push(Ok(x));
+                        continue;
+                    }
+                    let error_response = match doc.find("error") {
+                        Some(x) => {
+                            ErrorResponse::new(x.find("error").and_then(|x| x.as_str()).unwrap_or("unknown_error"),
+                                               x.find("reason").and_then(|x| x.as_str()).unwrap_or(""))
+                        }
+                        None => return Err(Error::UnexpectedResponse("The result has neither a document nor an error")),
+                    };
+                    items.push(Err(BulkDocsResult::new_error(Some(doc_id.clone()), error_response)));
+                }
+            }
+            Ok(items)
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {DocumentId, Error, IntoDatabasePath, Revision, Seq, serde_json};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    const REV_A: &'static str = "1-1234567890abcdef1234567890abcdef";
+    const REV_B1: &'static str = "2-abcdef1234567890abcdef1234567890";
+    const REV_B2: &'static str = "2-fedcba0987654321fedcba0987654321";
+
+    #[test]
+    fn make_revs_diff_request_ok() {
+        let transport = MockTransport::new();
+        let leaves = vec![(DocumentId::from("a"), vec![Revision::parse(REV_A).unwrap()]),
+                          (DocumentId::from("b"),
+                           vec![Revision::parse(REV_B1).unwrap(), Revision::parse(REV_B2).unwrap()])];
+        let expected = transport.post(vec!["foo", "_revs_diff"])
+            .with_accept_json()
+            .with_json_content(&serde_json::builder::ObjectBuilder::new()
+                .insert_array("a", |x| x.push(REV_A))
+                .insert_array("b", |x| x.push(REV_B1).push(REV_B2))
+                .build())
+            .unwrap();
+        let got = super::make_revs_diff_request(&transport, &"/foo".into_database_path().unwrap(), &leaves).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_bulk_get_request_ok() {
+        let transport = MockTransport::new();
+        let wanted = vec![(DocumentId::from("a"), Revision::parse(REV_A).unwrap())];
+        let expected = transport.post(vec!["foo", "_bulk_get"])
+            .with_accept_json()
+            .with_query_literal("revs", "true")
+            .with_query_literal("attachments", "true")
+            .with_json_content(&serde_json::builder::ObjectBuilder::new()
+                .insert_array("docs", |x| x.push_object(|x| x.insert("id", "a").insert("rev", REV_A)))
+                .build())
+            .unwrap();
+        let got = super::make_bulk_get_request(&transport, &"/foo".into_database_path().unwrap(), &wanted).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_revs_diff_response_ok() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(format!(r#"{{"b": {{"missing": ["{}"], "possible_ancestors": ["{}"]}}}}"#,
+                                           REV_B2,
+                                           REV_A))
+            .unwrap();
+        let got = super::handle_revs_diff_response(response).unwrap();
+        assert_eq!(1, got.len());
+        assert_eq!(Some(&vec![Revision::parse(REV_B2).unwrap()]), got.get(&DocumentId::from("b")));
+    }
+
+    #[test]
+    fn handle_bulk_get_response_ok_and_error() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(format!(r#"{{"results": [
+                {{"id": "a", "docs": [{{"ok": {{"_id": "a", "_rev": "{}", "x": 1}}}}]}},
+                {{"id": "b", "docs": [{{"error": {{"id": "b", "rev": "{}", "error": "not_found",
+                                                  "reason": "missing"}}}}]}}]}}"#,
+                                           REV_A,
+                                           REV_B1))
+            .unwrap();
+        let got = super::handle_bulk_get_response(response).unwrap();
+        assert_eq!(2, got.len());
+        assert_eq!(Some("a"), got[0].as_ref().ok().and_then(|x| x.find("_id")).and_then(|x| x.as_str()));
+        let failure = got[1].as_ref().err().unwrap();
+        assert_eq!(Some(&DocumentId::from("b")), failure.doc_id());
+        assert_eq!("not_found", failure.error_response().unwrap().error());
+    }
+
+    #[test]
+    fn run_ok_writes_missing_revisions() {
+
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(format!(r#"{{"last_seq": "7-g1AAAA", "pending": 0, "results": [
+                    {{"seq": "6-g1AAAA", "id": "a", "changes": [{{"rev": "{}"}}]}},
+                    {{"seq": "7-g1AAAA", "id": "b", "changes": [{{"rev": "{}"}}, {{"rev": "{}"}}]}}]}}"#,
+                                               REV_A,
+                                               REV_B1,
+                                               REV_B2))
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(format!(r#"{{"b": {{"missing": ["{}", "{}"]}}}}"#, REV_B1, REV_B2))
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(format!(r#"{{"results": [{{"id": "b", "docs": [
+                    {{"ok": {{"_id": "b", "_rev": "{}"}}}},
+                    {{"ok": {{"_id": "b", "_rev": "{}"}}}}]}}]}}"#,
+                                               REV_B1,
+                                               REV_B2))
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Created)
+                .with_json_content_raw("[]")
+                .unwrap());
+
+        let report = PullReplication::new(&transport, "/source", &transport, "/target", 10).run().unwrap();
+        assert_eq!(&Seq::from("7-g1AAAA"), report.last_seq());
+        assert_eq!(2, report.documents_read());
+        assert_eq!(2, report.documents_written());
+        assert_eq!(&vec![DocumentId::from("b")], report.conflicts());
+        assert!(report.failures().is_empty());
+
+        let requests = transport.take_requests();
+        assert_eq!(4, requests.len());
+        assert_eq!(transport.get(vec!["source", "_changes"])
+                       .with_accept_json()
+                       .with_query_literal("limit", "10")
+                       .with_query_literal("style", "all_docs"),
+                   requests[0]);
+    }
+
+    #[test]
+    fn run_ok_nothing_missing() {
+
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(format!(r#"{{"last_seq": "6-g1AAAA", "results": [
+                    {{"seq": "6-g1AAAA", "id": "a", "changes": [{{"rev": "{}"}}]}}]}}"#,
+                                               REV_A))
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok).with_json_content_raw("{}").unwrap());
+
+        let report = PullReplication::new(&transport, "/source", &transport, "/target", 10)
+            .with_since(5)
+            .run()
+            .unwrap();
+        assert_eq!(1, report.documents_read());
+        assert_eq!(0, report.documents_written());
+        assert_eq!(2, transport.take_requests().len());
+    }
+
+    #[test]
+    fn run_nok_zero_batch_size() {
+        let transport = MockTransport::new();
+        match PullReplication::new(&transport, "/source", &transport, "/target", 0).run() {
+            Err(Error::InvalidOptions(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,389 @@
+//! Defines an action for uploading an attachment.
+
+use {AttachmentPath, Error, IntoAttachmentPath, Revision, base64, md5, mime, std};
+use action::query_keys::*;
+use document::WriteDocumentResponse;
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Uploads an attachment, creating or replacing it.
+///
+/// Chill uploads the attachment by sending an HTTP request to `PUT
+/// /{db}/{docid}/{attname}`. The request content is the attachment's content,
+/// as is. If the document does not exist, then the CouchDB server creates it.
+///
+/// # Digest verification
+///
+/// With `with_content_md5`, Chill sends the MD5 digest of the content in the
+/// `Content-MD5` header, and the server rejects the upload if the content it
+/// receives does not match the digest. The rejection is an
+/// `Error::DigestMismatch`.
+///
+/// Chill doesn't compare the digest the server stores for the attachment,
+/// because for content types the server compresses—e.g., `text/*`—the stored
+/// digest covers the compressed content, not the uploaded content.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when uploading an
+/// attachment.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::DigestMismatch</code></td>
+///  <td>Digest verification is enabled and the attachment was corrupted in
+///  transit.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::DocumentConflict</code></td>
+///  <td>The revision is not the document's current revision.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to upload the attachment.</td>
+/// </tr>
+/// </table>
+///
+pub struct PutAttachment<'a, T: Transport + 'a, P: IntoAttachmentPath> {
+    transport: &'a T,
+    att_path: Option<P>,
+    content_type: mime::Mime,
+    content: Vec<u8>,
+    revision: Option<&'a Revision>,
+    content_md5: bool,
+}
+
+impl<'a, P: IntoAttachmentPath, T: Transport + 'a> PutAttachment<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, att_path: P, content_type: mime::Mime, content: Vec<u8>) -> Self {
+        PutAttachment {
+            transport: transport,
+            att_path: Some(att_path),
+            content_type: content_type,
+            content: content,
+            revision: None,
+            content_md5: false,
+        }
+    }
+
+    /// Sets the document's current revision, which is required if the
+    /// document exists.
+    pub fn with_revision(mut self, revision: &'a Revision) -> Self {
+        self.revision = Some(revision);
+        self
+    }
+
+    /// Sets whether to verify the content's MD5 digest.
+    ///
+    /// By default, Chill does not verify the digest.
+    ///
+    pub fn with_content_md5(mut self, content_md5: bool) -> Self {
+        self.content_md5 = content_md5;
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    ///
+    /// On success, the result is the document's new revision.
+    ///
+    pub fn run(mut self) -> Result<Revision, Error> {
+
+        let att_path = try!(std::mem::replace(&mut self.att_path, None).unwrap().into_attachment_path());
+        let digest = if self.content_md5 {
+            Some(content_digest(&self.content))
+        } else {
+            None
+        };
+
+        let request = self.make_request(&att_path, digest.as_ref().map(|x| x.as_str()));
+
+        match self.transport.send(request, JsonResponseDecoder::new(handle_response)) {
+            // The server verifies the `Content-MD5` header, if any.
+            Err(Error::ServerResponse { status_code: StatusCode::BadRequest, error_response: Some(ref x), .. })
+                if digest.is_some() && x.error() == "content_md5_mismatch" => {
+                Err(Error::DigestMismatch { expected: digest.unwrap() })
+            }
+            x @ _ => x,
+        }
+    }
+
+    fn make_request(&mut self, att_path: &AttachmentPath, digest: Option<&str>) -> Request {
+
+        let content = std::mem::replace(&mut self.content, Vec::new());
+        let request = self.transport
+            .put(att_path.iter())
+            .with_accept_json()
+            .with_content(self.content_type.clone(), content);
+
+        let request = match self.revision {
+            None => request,
+            Some(rev) => request.with_query(RevisionQueryKey, rev),
+        };
+
+        match digest {
+            None => request,
+            Some(digest) => request.with_raw_header("Content-MD5", digest),
+        }
+    }
+}
+
+/// Uploads an attachment whose content is read from a source, creating or
+/// replacing it.
+///
+/// Unlike `PutAttachment`, which holds the whole content in memory,
+/// `PutAttachmentStream` reads the content from the source while sending the
+/// request, so the application may upload a large attachment—e.g., a file—
+/// without buffering it. Chill sends a `Content-Length` header if the
+/// content's length is given, and uses chunked transfer encoding otherwise.
+/// The given length must be exact.
+///
+/// The content is read only once, so the upload isn't retried after a `401
+/// Unauthorized` response, and redirects aren't followed. Digest verification
+/// isn't supported.
+///
+/// # Errors
+///
+/// The errors are the same as for `PutAttachment`, plus the following.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::Io</code></td>
+///  <td>Reading the content from the source failed. The error's cause is the
+///  source's error.</td>
+/// </tr>
+/// </table>
+///
+pub struct PutAttachmentStream<'a, T: Transport + 'a, P: IntoAttachmentPath, R: std::io::Read> {
+    transport: &'a T,
+    att_path: Option<P>,
+    content_type: mime::Mime,
+    source: R,
+    length: Option<u64>,
+    revision: Option<&'a Revision>,
+}
+
+impl<'a, P: IntoAttachmentPath, R: std::io::Read, T: Transport + 'a> PutAttachmentStream<'a, T, P, R> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, att_path: P, content_type: mime::Mime, source: R, length: Option<u64>) -> Self {
+        PutAttachmentStream {
+            transport: transport,
+            att_path: Some(att_path),
+            content_type: content_type,
+            source: source,
+            length: length,
+            revision: None,
+        }
+    }
+
+    /// Sets the document's current revision, which is required if the
+    /// document exists.
+    pub fn with_revision(mut self, revision: &'a Revision) -> Self {
+        self.revision = Some(revision);
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    ///
+    /// On success, the result is the document's new revision.
+    ///
+    pub fn run(mut self) -> Result<Revision, Error> {
+        let att_path = try!(std::mem::replace(&mut self.att_path, None).unwrap().into_attachment_path());
+        let request = self.make_request(&att_path);
+        self.transport.send_streaming(request,
+                                      &mut self.source,
+                                      self.length,
+                                      JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&self, att_path: &AttachmentPath) -> Request {
+
+        let request = self.transport
+            .put(att_path.iter())
+            .with_accept_json()
+            .with_content(self.content_type.clone(), Vec::new());
+
+        match self.revision {
+            None => request,
+            Some(rev) => request.with_query(RevisionQueryKey, rev),
+        }
+    }
+}
+
+// Returns the base64-encoded MD5 digest of the content, as sent in the
+// `Content-MD5` header.
+fn content_digest(content: &[u8]) -> String {
+    base64::encode(&md5::compute(content)[..])
+}
+
+fn handle_response(response: JsonResponse) -> Result<Revision, Error> {
+    match response.status_code() {
+        StatusCode::Created | StatusCode::Accepted => {
+            let body: WriteDocumentResponse = try!(response.decode_content());
+            Ok(body.revision)
+        }
+        StatusCode::Conflict => Err(Error::document_conflict(&response)),
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {Error, IntoAttachmentPath, Revision, std};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn content_digest_ok() {
+        assert_eq!("XUFAKrxLKna5cZ2REBfFkg==", super::content_digest(b"hello"));
+    }
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let expected = transport.put(vec!["foo", "bar", "baz"])
+            .with_accept_json()
+            .with_content(mime!(Text / Plain), b"hello".to_vec());
+
+        let got = {
+            let att_path = "/foo/bar/baz".into_attachment_path().unwrap();
+            let mut action = PutAttachment::new(&transport, "/foo/bar/baz", mime!(Text / Plain), b"hello".to_vec());
+            action.make_request(&att_path, "XUFAKrxLKna5cZ2REBfFkg==")
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_revision_and_content_md5() {
+
+        let transport = MockTransport::new();
+        let expected = transport.put(vec!["foo", "bar", "baz"])
+            .with_accept_json()
+            .with_content(mime!(Text / Plain), b"hello".to_vec())
+            .with_query_literal("rev", "1-1234567890abcdef1234567890abcdef")
+            .with_raw_header("Content-MD5", "XUFAKrxLKna5cZ2REBfFkg==");
+
+        let got = {
+            let rev = Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap();
+            let att_path = "/foo/bar/baz".into_attachment_path().unwrap();
+            let mut action = PutAttachment::new(&transport, "/foo/bar/baz", mime!(Text / Plain), b"hello".to_vec())
+                .with_revision(&rev)
+                .with_content_md5(true);
+            action.make_request(&att_path, "XUFAKrxLKna5cZ2REBfFkg==")
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_created() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Created)
+            .with_json_content_raw(r#"{"ok":true,"id":"bar","rev":"2-1234567890abcdef1234567890abcdef"}"#)
+            .unwrap();
+
+        let expected = Revision::parse("2-1234567890abcdef1234567890abcdef").unwrap();
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_conflict() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Conflict)
+            .with_json_content_raw(r#"{"error":"conflict","reason":"Document update conflict."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::DocumentConflict(ref error_response)) if error_response.error() == "conflict" &&
+                                                                error_response.reason() ==
+                                                                "Document update conflict." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn run_ok_content_md5() {
+
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Created)
+            .with_json_content_raw(r#"{"ok":true,"id":"bar","rev":"2-1234567890abcdef1234567890abcdef"}"#)
+            .unwrap());
+
+        let expected = Revision::parse("2-1234567890abcdef1234567890abcdef").unwrap();
+        let got = PutAttachment::new(&transport, "/foo/bar/baz", mime!(Text / Plain), b"hello".to_vec())
+            .with_content_md5(true)
+            .run()
+            .unwrap();
+        assert_eq!(expected, got);
+
+        // The server verifies the digest, so there is no second request to
+        // read back the stored digest.
+        assert_eq!(1, transport.take_requests().len());
+    }
+
+    #[test]
+    fn run_nok_content_md5_rejected_by_server() {
+
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::BadRequest)
+            .with_json_content_raw(r#"{"error":"content_md5_mismatch","reason":"Possible message corruption."}"#)
+            .unwrap());
+
+        match PutAttachment::new(&transport, "/foo/bar/baz", mime!(Text / Plain), b"hello".to_vec())
+            .with_content_md5(true)
+            .run() {
+            Err(Error::DigestMismatch { ref expected }) if expected == "XUFAKrxLKna5cZ2REBfFkg==" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn stream_run_ok() {
+
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Created)
+            .with_json_content_raw(r#"{"ok":true,"id":"bar","rev":"2-1234567890abcdef1234567890abcdef"}"#)
+            .unwrap());
+
+        let revision = Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap();
+        let expected = Revision::parse("2-1234567890abcdef1234567890abcdef").unwrap();
+        let got = PutAttachmentStream::new(&transport,
+                                           "/foo/bar/baz",
+                                           mime!(Text / Plain),
+                                           &b"hello"[..],
+                                           Some(5))
+            .with_revision(&revision)
+            .run()
+            .unwrap();
+        assert_eq!(expected, got);
+
+        let expected = transport.put(vec!["foo", "bar", "baz"])
+            .with_accept_json()
+            .with_content(mime!(Text / Plain), b"hello".to_vec())
+            .with_query_literal("rev", "1-1234567890abcdef1234567890abcdef");
+        assert_eq!(vec![expected], transport.take_requests());
+    }
+
+    #[test]
+    fn stream_run_nok_source_error() {
+
+        struct FailingSource;
+
+        impl std::io::Read for FailingSource {
+            fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::new(std::io::ErrorKind::Other, "disk on fire"))
+            }
+        }
+
+        let transport = MockTransport::new();
+        match PutAttachmentStream::new(&transport, "/foo/bar/baz", mime!(Text / Plain), FailingSource, None).run() {
+            Err(Error::Io { ref cause, .. }) if cause.to_string() == "disk on fire" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
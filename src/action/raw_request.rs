@@ -0,0 +1,178 @@
+//! Defines an action for sending an arbitrary HTTP request to the CouchDB
+//! server.
+
+use {Error, RawResponse, std};
+use path::split_raw_path;
+use transport::{Method, RawResponseReader, Request, Transport};
+
+/// Sends an arbitrary HTTP request to the CouchDB server and returns the raw
+/// response.
+///
+/// `RawRequest` is an escape hatch for CouchDB endpoints that Chill does not
+/// otherwise support. The request goes through the client's transport, so it
+/// uses the same server URL and credentials as every other action.
+///
+/// The path must begin with a slash and must not contain empty segments or end
+/// with a slash—the same rules as for Chill's typed paths. Each segment is
+/// percent-encoded as is, so the path must not already be percent-encoded. The
+/// path may end with a query string, e.g., `/_scheduler/jobs?limit=10`, in
+/// which case the query string must already be percent-encoded.
+///
+/// The action does not interpret the response. In particular, an error status
+/// code is _not_ an error result—the application must check the status code
+/// itself.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when sending a raw request.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::InvalidOptions</code></td>
+///  <td>The HTTP method is invalid.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::PathParse</code></td>
+///  <td>The path is invalid.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Transport</code></td>
+///  <td>The server is unreachable.</td>
+/// </tr>
+/// </table>
+///
+pub struct RawRequest<'a, T: Transport + 'a> {
+    transport: &'a T,
+    method: &'a str,
+    path: &'a str,
+    headers: Vec<(&'a str, &'a str)>,
+    body: Option<Vec<u8>>,
+}
+
+impl<'a, T: Transport + 'a> RawRequest<'a, T> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, method: &'a str, path: &'a str) -> Self {
+        RawRequest {
+            transport: transport,
+            method: method,
+            path: path,
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    /// Adds a header to the request.
+    ///
+    /// By default, the request has no headers other than those the transport
+    /// adds, e.g., for authentication.
+    ///
+    pub fn with_header(mut self, name: &'a str, value: &'a str) -> Self {
+        self.headers.push((name, value));
+        self
+    }
+
+    /// Sets the request's body.
+    ///
+    /// By default, the request has no body.
+    ///
+    pub fn with_body(mut self, body: Vec<u8>) -> Self {
+        self.body = Some(body);
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<RawResponse, Error> {
+        let request = try!(self.make_request());
+        self.transport.send(request, RawResponseReader::new())
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+
+        use std::str::FromStr;
+
+        let method = try!(Method::from_str(self.method)
+            .map_err(|_| Error::InvalidOptions("The HTTP method is invalid")));
+
+        let (path, query) = match self.path.find('?') {
+            Some(index) => (&self.path[..index], Some(&self.path[index + 1..])),
+            None => (self.path, None),
+        };
+
+        let mut request = self.transport.request(method, try!(split_raw_path(path)));
+
+        if let Some(query) = query {
+            request = request.with_raw_query(query);
+        }
+
+        for &(name, value) in self.headers.iter() {
+            request = request.with_raw_header(name, value);
+        }
+
+        if let Some(body) = std::mem::replace(&mut self.body, None) {
+            request = request.with_raw_body(body);
+        }
+
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use Error;
+    use error::PathParseErrorKind;
+    use super::*;
+    use transport::{JsonResponseBuilder, Method, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["_node", "_local", "_versions"]);
+        let got = RawRequest::new(&transport, "GET", "/_node/_local/_versions").make_request().unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_query_headers_and_body() {
+        let transport = MockTransport::new();
+        let expected = transport.request(Method::Extension(String::from("COPY")), vec!["db", "doc"])
+            .with_raw_query("batch=ok")
+            .with_raw_header("Destination", "other_doc")
+            .with_raw_body(b"alpha".to_vec());
+        let got = RawRequest::new(&transport, "COPY", "/db/doc?batch=ok")
+            .with_header("Destination", "other_doc")
+            .with_body(b"alpha".to_vec())
+            .make_request()
+            .unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_nok_bad_path() {
+        let transport = MockTransport::new();
+        match RawRequest::new(&transport, "GET", "/db/").make_request() {
+            Err(Error::PathParse(PathParseErrorKind::TrailingSlash)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn make_request_nok_bad_method() {
+        let transport = MockTransport::new();
+        match RawRequest::new(&transport, "", "/").make_request() {
+            Err(Error::InvalidOptions(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn run_returns_error_status_as_is() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"missing"}"#)
+            .unwrap());
+        let got = RawRequest::new(&transport, "GET", "/db/doc").run().unwrap();
+        assert_eq!(404, got.status_code());
+        assert_eq!(Some("application/json"), got.header("content-type"));
+        assert_eq!(Some(r#"{"error":"not_found","reason":"missing"}"#), got.body_str());
+    }
+}
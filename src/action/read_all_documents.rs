@@ -0,0 +1,167 @@
+//! Defines an action for reading all the documents in a database, optionally
+//! only those whose ids start with a given prefix.
+
+use {DatabaseName, DatabasePath, Error, IntoDatabasePath, ViewResponse, std};
+use action::query_keys::*;
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+use view::ViewResponseJsonable;
+
+// The code point CouchDB's prefix-scan idiom appends to the prefix for the end
+// key. It sorts after every character that appears in practical document ids.
+const PREFIX_END_MARKER: char = '\u{fff0}';
+
+/// Reads all the documents in a database, or only those whose ids start with
+/// a given prefix.
+///
+/// Chill reads the documents by sending an HTTP request to `GET
+/// /db/_all_docs`. With `with_prefix`, the request has the key range
+/// `startkey="prefix"` and `endkey="prefix\ufff0"`—CouchDB's idiom for a
+/// prefix scan. The result is a `ViewResponse` with one row per document,
+/// whose key is the document id.
+///
+/// By default, the result contains each document's id and revision but not
+/// its content—see `with_documents`.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when reading documents.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read the documents.</td>
+/// </tr>
+/// </table>
+///
+pub struct ReadAllDocuments<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    prefix: Option<String>,
+    include_docs: bool,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> ReadAllDocuments<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P) -> Self {
+        ReadAllDocuments {
+            transport: transport,
+            db_path: Some(db_path),
+            prefix: None,
+            include_docs: false,
+        }
+    }
+
+    /// Modifies the action to read only the documents whose ids start with
+    /// the given prefix—e.g., `"user:"`.
+    pub fn with_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Modifies the action to retrieve (or not retrieve) each document's
+    /// content.
+    ///
+    /// The `with_documents` method abstracts the `include_docs` query
+    /// parameter. If enabled, the content is available via the
+    /// `ViewRow::document` method.
+    ///
+    pub fn with_documents(mut self, include_docs: bool) -> Self {
+        self.include_docs = include_docs;
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<ViewResponse, Error> {
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+        let db_name = db_path.database_name().clone();
+        let request = try!(self.make_request(&db_path));
+        self.transport.send(request,
+                            JsonResponseDecoder::new(move |response| handle_response(response, db_name)))
+    }
+
+    fn make_request(&self, db_path: &DatabasePath) -> Result<Request, Error> {
+        let request = self.transport.get(db_path.iter().chain(std::iter::once("_all_docs"))).with_accept_json();
+        let request = match self.prefix {
+            None => request,
+            Some(ref prefix) => {
+                let end_key = format!("{}{}", prefix, PREFIX_END_MARKER);
+                let request = try!(request.with_query_fallible(StartKeyQueryKey, prefix));
+                try!(request.with_query_fallible(EndKeyQueryKey, &end_key))
+            }
+        };
+        Ok(if self.include_docs {
+            request.with_query(IncludeDocsQueryKey, &true)
+        } else {
+            request
+        })
+    }
+}
+
+fn handle_response(response: JsonResponse, db_name: DatabaseName) -> Result<ViewResponse, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let body: ViewResponseJsonable = try!(response.decode_content());
+            Ok(ViewResponse::new_from_decoded(db_name, body))
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {DatabaseName, Error, IntoDatabasePath};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_all_docs"]).with_accept_json();
+        let action = ReadAllDocuments::new(&transport, "/foo");
+        let got = action.make_request(&"/foo".into_database_path().unwrap()).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_prefix() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_all_docs"])
+            .with_accept_json()
+            .with_query_literal("startkey", r#""user:""#)
+            .with_query_literal("endkey", "\"user:\u{fff0}\"")
+            .with_query_literal("include_docs", "true");
+        let action = ReadAllDocuments::new(&transport, "/foo").with_prefix("user:").with_documents(true);
+        let got = action.make_request(&"/foo".into_database_path().unwrap()).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"total_rows": 3, "offset": 1, "rows": [
+                {"id": "user:alice", "key": "user:alice", "value": {"rev": "1-1234567890abcdef1234567890abcdef"}}]}"#)
+            .unwrap();
+        let got = super::handle_response(response, DatabaseName::from("foo")).unwrap();
+        assert_eq!(Some(3), got.total_rows());
+        assert_eq!(1, got.rows().len());
+        assert_eq!(Some(String::from("user:alice")), got.rows()[0].key().unwrap());
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Database does not exist."}"#)
+            .unwrap();
+        match super::handle_response(response, DatabaseName::from("foo")) {
+            Err(Error::NotFound(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Defines an action for reading a byte range of an attachment's content.
+
+use {AttachmentRange, Error, IntoAttachmentPath, mime, std};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads a byte range of an attachment's content.
+///
+/// Chill reads the range by sending an HTTP request to `GET
+/// /{db}/{docid}/{attname}` with a `Range` header. The range starts at the
+/// given byte offset and ends at the given byte offset, inclusive, or at the
+/// end of the content if no end is given. This is useful for resuming the
+/// download of a large attachment or for reading a slice of it.
+///
+/// The server responds with `206 Partial Content` if it honors the range. A
+/// server that ignores the range responds with the full content instead, in
+/// which case the result is the full content—see
+/// `AttachmentRange::is_partial`.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when reading an attachment
+/// range.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::InvalidOptions</code></td>
+///  <td>The range ends before it starts.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database, document, or attachment does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::ServerResponse</code></td>
+///  <td>The range starts past the end of the content—i.e., the server
+///  responded with <code>416 Range Not Satisfiable</code>.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read the attachment.</td>
+/// </tr>
+/// </table>
+///
+pub struct ReadAttachmentRange<'a, T: Transport + 'a, P: IntoAttachmentPath> {
+    transport: &'a T,
+    att_path: Option<P>,
+    start: u64,
+    end: Option<u64>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoAttachmentPath> ReadAttachmentRange<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, att_path: P, start: u64, end: Option<u64>) -> Self {
+        ReadAttachmentRange {
+            transport: transport,
+            att_path: Some(att_path),
+            start: start,
+            end: end,
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<AttachmentRange, Error> {
+        self.transport.send(try!(self.make_request()),
+                            JsonResponseDecoder::new(handle_response).without_content_type_check())
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+
+        let range = match self.end {
+            None => format!("bytes={}-", self.start),
+            Some(end) if self.start <= end => format!("bytes={}-{}", self.start, end),
+            Some(_) => return Err(Error::InvalidOptions("The attachment range ends before it starts")),
+        };
+
+        let att_path = try!(std::mem::replace(&mut self.att_path, None).unwrap().into_attachment_path());
+        Ok(self.transport.get(att_path.iter()).with_raw_header("Range", &range))
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<AttachmentRange, Error> {
+
+    let content_type = response.headers()
+        .get_raw_str("Content-Type")
+        .and_then(|x| x.parse::<mime::Mime>().ok())
+        .unwrap_or(mime!(Application / OctetStream));
+
+    match response.status_code() {
+        StatusCode::PartialContent => {
+            let content_range = match response.headers().get_raw_str("Content-Range") {
+                Some(x) => String::from(x),
+                None => return Err(Error::UnexpectedResponse("The partial response has no Content-Range header")),
+            };
+            AttachmentRange::new_partial(content_type, response.into_content(), &content_range)
+        }
+        // The server ignored the range, though it may still advertise range
+        // support—e.g., because the range starts at zero.
+        StatusCode::Ok => {
+            let accepts_ranges = response.headers().get_raw_str("Accept-Ranges") == Some("bytes");
+            Ok(AttachmentRange::new_full(content_type, response.into_content()).with_accepts_ranges(accepts_ranges))
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use Error;
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_with_end() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "bar", "baz"]).with_raw_header("Range", "bytes=100-199");
+        let got = ReadAttachmentRange::new(&transport, "/foo/bar/baz", 100, Some(199)).make_request().unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_without_end() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "bar", "baz"]).with_raw_header("Range", "bytes=100-");
+        let got = ReadAttachmentRange::new(&transport, "/foo/bar/baz", 100, None).make_request().unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_nok_end_before_start() {
+        let transport = MockTransport::new();
+        match ReadAttachmentRange::new(&transport, "/foo/bar/baz", 100, Some(99)).make_request() {
+            Err(Error::InvalidOptions(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_partial_content() {
+        let response = JsonResponseBuilder::new(StatusCode::PartialContent)
+            .with_raw_header("Content-Type", "text/plain")
+            .with_raw_header("Content-Range", "bytes 6-10/11")
+            .with_raw_content(b"world".to_vec())
+            .unwrap();
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(&mime!(Text / Plain), got.content_type());
+        assert_eq!(&b"world"[..], got.content());
+        assert!(got.is_partial());
+        assert_eq!(Some("bytes 6-10/11"), got.content_range());
+        assert_eq!(6, got.offset());
+        assert_eq!(Some(11), got.total_length());
+        assert_eq!(11, got.end_offset());
+        assert!(got.is_complete());
+        assert!(got.accepts_ranges());
+    }
+
+    #[test]
+    fn handle_response_full_content() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_raw_header("Content-Type", "text/plain")
+            .with_raw_content(b"hello world".to_vec())
+            .unwrap();
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(&b"hello world"[..], got.content());
+        assert!(!got.is_partial());
+        assert_eq!(None, got.content_range());
+        assert_eq!(0, got.offset());
+        assert_eq!(Some(11), got.total_length());
+        assert!(!got.accepts_ranges());
+    }
+
+    #[test]
+    fn handle_response_full_content_with_accept_ranges() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_raw_header("Accept-Ranges", "bytes")
+            .with_raw_content(b"hello world".to_vec())
+            .unwrap();
+        let got = super::handle_response(response).unwrap();
+        assert!(!got.is_partial());
+        assert!(got.accepts_ranges());
+    }
+
+    #[test]
+    fn handle_response_nok_partial_content_without_content_range() {
+        let response = JsonResponseBuilder::new(StatusCode::PartialContent)
+            .with_raw_content(b"world".to_vec())
+            .unwrap();
+        match super::handle_response(response) {
+            Err(Error::UnexpectedResponse(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Document is missing attachment"}"#)
+            .unwrap();
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Defines an action for detecting the CouchDB server's capabilities.
+
+use {Capabilities, Error, serde_json};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Detects which optional features the CouchDB server supports.
+///
+/// Chill detects the capabilities by sending an HTTP request to `GET /` and
+/// examining the server's version and feature list.
+///
+/// The client caches the result, shared between its clones, so the action
+/// sends at most one request per server. Other actions—e.g., `Ping` and
+/// `ReadDesignDocuments`—consult the cached result, if any, to skip requests
+/// the server would reject.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when detecting the
+/// capabilities.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::UnexpectedResponse</code></td>
+///  <td>The server info lacks a valid version.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Transport</code></td>
+///  <td>The server is unreachable.</td>
+/// </tr>
+/// </table>
+///
+pub struct ReadCapabilities<'a, T: Transport + 'a> {
+    transport: &'a T,
+}
+
+impl<'a, T: Transport + 'a> ReadCapabilities<'a, T> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T) -> Self {
+        ReadCapabilities { transport: transport }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Capabilities, Error> {
+
+        if let Some(capabilities) = self.transport.server_capabilities().and_then(|x| x.probed()) {
+            return Ok(capabilities);
+        }
+
+        let capabilities = try!(self.transport.send(self.make_request(), JsonResponseDecoder::new(handle_response)));
+
+        if let Some(cache) = self.transport.server_capabilities() {
+            cache.set_probed(capabilities.clone());
+        }
+
+        Ok(capabilities)
+    }
+
+    fn make_request(&mut self) -> Request {
+        self.transport.get(Vec::<&str>::new()).with_accept_json()
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<Capabilities, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let content: serde_json::Value = try!(response.decode_content());
+            Capabilities::from_server_info(&content)
+        }
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use Error;
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.get(Vec::<&str>::new()).with_accept_json();
+        let got = ReadCapabilities::new(&transport).make_request();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn run_ok_caches_result() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"couchdb": "Welcome", "version": "2.3.1"}"#)
+            .unwrap());
+        let first = ReadCapabilities::new(&transport).run().unwrap();
+        assert_eq!(first.version(), "2.3.1");
+        assert!(first.has_design_docs());
+        let second = ReadCapabilities::new(&transport).run().unwrap();
+        assert_eq!(first, second);
+        assert_eq!(transport.take_requests().len(), 1);
+    }
+
+    #[test]
+    fn run_nok_unauthorized() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(r#"{"error":"unauthorized","reason":"Authentication required."}"#)
+            .unwrap());
+        match ReadCapabilities::new(&transport).run() {
+            Err(Error::Unauthorized(ref error_response)) => {
+                assert_eq!(error_response.error(), "unauthorized");
+            }
+            x => unexpected_result!(x),
+        }
+        assert!(transport.server_capabilities().unwrap().probed().is_none());
+    }
+}
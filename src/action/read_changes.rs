@@ -0,0 +1,878 @@
+//! Defines an action for reading a database's changes feed.
+
+use {ChangeRow, Changes, DatabaseName, DatabasePath, DesignDocumentName, Error, IntoDatabasePath, Since, ViewName, std};
+use action::ReadDatabaseInfo;
+use action::query_keys::*;
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads a batch of changes from a database's changes feed and returns the
+/// result.
+///
+/// Chill reads the changes by sending an HTTP request to `GET /db/_changes`.
+/// By default, the feed starts at the beginning of the database's history. To
+/// resume a previous read, pass that read's `last_seq` to `with_since`.
+///
+/// The `run` method reads one batch of changes. Alternatively, the
+/// `into_continuous` method converts the action into an iterator that waits
+/// for new changes indefinitely.
+///
+pub struct ReadChanges<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    since: Since,
+    limit: Option<u64>,
+    include_docs: bool,
+    all_revisions: bool,
+    resilient: bool,
+    reconnect_delay: std::time::Duration,
+    restart_detection: bool,
+    filter: Option<ChangesFilter>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> ReadChanges<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P) -> Self {
+        ReadChanges {
+            transport: transport,
+            db_path: Some(db_path),
+            since: Since::Zero,
+            limit: None,
+            include_docs: false,
+            all_revisions: false,
+            resilient: false,
+            reconnect_delay: std::time::Duration::from_millis(DEFAULT_RECONNECT_DELAY_MS),
+            restart_detection: false,
+            filter: None,
+        }
+    }
+
+    /// Modifies the action to read only changes after the given update
+    /// sequence.
+    ///
+    /// The `with_since` method abstracts the `since` query parameter. The
+    /// argument may be a `Since`, a `Seq`, or a checkpoint the application
+    /// persisted as a string or integer—see `Since` for details.
+    ///
+    pub fn with_since<S: Into<Since>>(mut self, since: S) -> Self {
+        self.since = since.into();
+        self
+    }
+
+    /// Modifies the action to read at most the given number of changes.
+    ///
+    /// The `with_limit` method abstracts the `limit` query parameter. By
+    /// default, the server sends all changes.
+    ///
+    pub fn with_limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Modifies the action to include each changed document in the result.
+    ///
+    /// The `with_include_docs` method abstracts the `include_docs` query
+    /// parameter. By default, documents are not included.
+    ///
+    pub fn with_include_docs(mut self, include_docs: bool) -> Self {
+        self.include_docs = include_docs;
+        self
+    }
+
+    /// Modifies the action to include every leaf revision of each changed
+    /// document, not only the winning revision.
+    ///
+    /// The `with_all_revisions` method abstracts the `style=all_docs` query
+    /// parameter. A replicator needs all leaf revisions so that conflicting
+    /// revisions reach the target, too. By default, each change contains only
+    /// the winning revision.
+    ///
+    pub fn with_all_revisions(mut self, all_revisions: bool) -> Self {
+        self.all_revisions = all_revisions;
+        self
+    }
+
+    /// Modifies the action to read only the changes that pass the given
+    /// filter.
+    ///
+    /// The `with_filter` method abstracts the `filter` query parameter and
+    /// its companion parameters—see `ChangesFilter`. By default, the server
+    /// sends all changes.
+    ///
+    pub fn with_filter(mut self, filter: ChangesFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Modifies the continuous iterator to reconnect after a recoverable
+    /// error instead of ending.
+    ///
+    /// A recoverable error is a transport or I/O error—e.g., the connection
+    /// dropped because of a proxy timeout—or an error for which
+    /// `Error::is_retryable` is true. When resilient, the iterator resumes
+    /// from the last update sequence it received, so no change is lost or
+    /// repeated, and it yields a `ChangeEvent::Reconnected` item to notify the
+    /// application of the reconnection. The iterator waits before each
+    /// reconnection, and the wait doubles with each consecutive failure—see
+    /// `with_reconnect_delay`. After `MAX_CONSECUTIVE_RECONNECTS` consecutive
+    /// failures, the iterator yields the error and ends.
+    ///
+    /// By default, the iterator is not resilient. The option has no effect on
+    /// the `run` method.
+    ///
+    pub fn with_resilient(mut self, resilient: bool) -> Self {
+        self.resilient = resilient;
+        self
+    }
+
+    /// Sets how long a resilient continuous iterator waits before its first
+    /// reconnection after a failure.
+    ///
+    /// The wait doubles with each further consecutive failure and resets after
+    /// a successful request. By default, the first wait is 100 milliseconds.
+    ///
+    pub fn with_reconnect_delay(mut self, delay: std::time::Duration) -> Self {
+        self.reconnect_delay = delay;
+        self
+    }
+
+    /// Modifies the continuous iterator to detect whether the server
+    /// restarted while the iterator was reconnecting.
+    ///
+    /// When enabled, the iterator reads the database's info, via `GET /db`,
+    /// before its first request and after each reconnection, and it compares
+    /// the database's `instance_start_time`. If the start time changed, then
+    /// update sequences the application cached may be invalid, so the
+    /// iterator yields a `ChangeEvent::Restarted` item and resumes from the
+    /// beginning of the feed. The application should discard its cached
+    /// state and resync.
+    ///
+    /// CouchDB 2.x and later always report an `instance_start_time` of `"0"`,
+    /// so the iterator never detects a restart on those servers. By default,
+    /// restart detection is disabled. The option has no effect on the `run`
+    /// method.
+    ///
+    pub fn with_restart_detection(mut self, restart_detection: bool) -> Self {
+        self.restart_detection = restart_detection;
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Changes, Error> {
+        let (request, db_name) = try!(self.make_request());
+        self.transport.send(request,
+                            JsonResponseDecoder::new(move |response| handle_response(response, db_name)))
+    }
+
+    /// Converts the action into an iterator that yields changes as they
+    /// happen.
+    ///
+    /// The iterator reads the changes feed in long-polling mode, via `GET
+    /// /db/_changes?feed=longpoll`, and resumes each request from the previous
+    /// request's `last_seq`. It blocks while waiting for new changes and never
+    /// ends unless an error occurs—see `with_resilient` for retrying after
+    /// errors. If a limit is set, then it limits the number of changes per
+    /// request.
+    ///
+    pub fn into_continuous(mut self) -> ContinuousChanges<'a, T> {
+        let db_path = self.validate_filter()
+            .and_then(|()| std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+        let (db_path, error) = match db_path {
+            Ok(x) => (Some(x), None),
+            Err(e) => (None, Some(e)),
+        };
+        let mut query = self.make_query();
+        query.feed = Some("longpoll");
+        ContinuousChanges {
+            transport: self.transport,
+            db_path: db_path,
+            error: error,
+            query: query,
+            resilient: self.resilient,
+            reconnect_delay: self.reconnect_delay,
+            pending_delay: None,
+            restart_detection: self.restart_detection,
+            restart_check_pending: self.restart_detection,
+            instance_start_time: None,
+            buffer: std::collections::VecDeque::new(),
+            reconnects: 0,
+            done: false,
+        }
+    }
+
+    fn make_query(&self) -> Query {
+        Query {
+            since: self.since.clone(),
+            limit: self.limit,
+            include_docs: self.include_docs,
+            all_revisions: self.all_revisions,
+            filter: self.filter.clone(),
+            feed: None,
+        }
+    }
+
+    fn validate_filter(&self) -> Result<(), Error> {
+        match self.filter {
+            None => Ok(()),
+            Some(ref filter) => filter.validate(),
+        }
+    }
+
+    fn make_request(&mut self) -> Result<(Request, DatabaseName), Error> {
+        try!(self.validate_filter());
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+        Ok((self.make_query().make_request(self.transport, &db_path), db_path.database_name().clone()))
+    }
+}
+
+/// Specifies which changes the server sends in a changes feed—see
+/// `ReadChanges::with_filter`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ChangesFilter {
+    /// Sends only the changes of documents that the given view's map function
+    /// emits at least one row for.
+    ///
+    /// This filter abstracts the `filter=_view` and `view=ddoc/view` query
+    /// parameters. It is more efficient than a filter function if a suitable
+    /// view already exists. Neither name may be empty or contain a slash.
+    ///
+    View {
+        ddoc: DesignDocumentName,
+        view: ViewName,
+    },
+}
+
+impl ChangesFilter {
+    /// Constructs a filter that uses the given view's map function.
+    pub fn view<D, V>(ddoc: D, view: V) -> Self
+        where D: Into<DesignDocumentName>,
+              V: Into<ViewName>
+    {
+        ChangesFilter::View {
+            ddoc: ddoc.into(),
+            view: view.into(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        match self {
+            &ChangesFilter::View { ref ddoc, ref view } => {
+                let is_bad = |x: &str| x.is_empty() || x.contains('/');
+                if is_bad(ddoc.as_ref()) || is_bad(view.as_ref()) {
+                    return Err(Error::InvalidOptions("The view filter's names must be nonempty and contain no slash"));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn add_to_request(&self, request: Request) -> Request {
+        match self {
+            &ChangesFilter::View { ref ddoc, ref view } => {
+                request.with_query(FilterQueryKey, &"_view")
+                    .with_query(ViewQueryKey, &format!("{}/{}", ddoc, view))
+            }
+        }
+    }
+}
+
+/// The maximum number of consecutive times a resilient `ContinuousChanges`
+/// iterator reconnects before giving up.
+pub const MAX_CONSECUTIVE_RECONNECTS: u32 = 5;
+
+// The default wait before a resilient iterator's first reconnection.
+const DEFAULT_RECONNECT_DELAY_MS: u64 = 100;
+
+// Returns the wait before the given consecutive reconnection, counting from
+// one. The wait doubles with each attempt.
+fn reconnect_delay(base: std::time::Duration, attempt: u32) -> std::time::Duration {
+    base * (1 << (std::cmp::min(attempt, MAX_CONSECUTIVE_RECONNECTS) - 1))
+}
+
+/// An item of a `ContinuousChanges` iterator.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChangeEvent {
+    /// A change to a document.
+    Change(ChangeRow),
+
+    /// The iterator reconnected after a recoverable error. The `attempt` is
+    /// the number of consecutive reconnections, starting at one.
+    Reconnected {
+        attempt: u32,
+    },
+
+    /// The server restarted since the iterator's previous request, as
+    /// detected via the database's `instance_start_time`—see
+    /// `ReadChanges::with_restart_detection`. The iterator resumes from the
+    /// beginning of the feed.
+    Restarted,
+}
+
+/// Iterates through a database's changes as they happen—see
+/// `ReadChanges::into_continuous`.
+pub struct ContinuousChanges<'a, T: Transport + 'a> {
+    transport: &'a T,
+    db_path: Option<DatabasePath>,
+    error: Option<Error>,
+    query: Query,
+    resilient: bool,
+    reconnect_delay: std::time::Duration,
+    pending_delay: Option<std::time::Duration>,
+    restart_detection: bool,
+    restart_check_pending: bool,
+    instance_start_time: Option<String>,
+    buffer: std::collections::VecDeque<ChangeRow>,
+    reconnects: u32,
+    done: bool,
+}
+
+impl<'a, T: Transport + 'a> ContinuousChanges<'a, T> {
+    /// Returns the update sequence the iterator resumes from on its next
+    /// request.
+    pub fn since(&self) -> &Since {
+        &self.query.since
+    }
+
+    /// Converts the iterator into one that yields each change together with
+    /// the checkpoint to persist after processing the change—see
+    /// `CheckpointedChanges`.
+    pub fn into_checkpointed(self) -> CheckpointedChanges<'a, T> {
+        CheckpointedChanges { inner: self }
+    }
+
+    fn is_recoverable(error: &Error) -> bool {
+        match error {
+            &Error::Io { .. } |
+            &Error::Transport { .. } => true,
+            _ => error.is_retryable(),
+        }
+    }
+
+    fn handle_error(&mut self, error: Error) -> Result<ChangeEvent, Error> {
+        if self.resilient && Self::is_recoverable(&error) && self.reconnects < MAX_CONSECUTIVE_RECONNECTS {
+            self.reconnects += 1;
+            self.pending_delay = Some(reconnect_delay(self.reconnect_delay, self.reconnects));
+            self.restart_check_pending = self.restart_detection;
+            return Ok(ChangeEvent::Reconnected { attempt: self.reconnects });
+        }
+        self.done = true;
+        Err(error)
+    }
+
+    // Returns whether the server restarted since the previous check. An
+    // unknown or placeholder start time never counts as a restart.
+    fn check_restart(&mut self) -> Result<bool, Error> {
+        let db_info = try!(ReadDatabaseInfo::new(self.transport, self.db_path.clone().unwrap()).run());
+        let instance_start_time = db_info.instance_start_time().map(|x| String::from(x));
+        let restarted = match (&self.instance_start_time, &instance_start_time) {
+            (&Some(ref before), &Some(ref after)) => before != "0" && before != after,
+            _ => false,
+        };
+        self.instance_start_time = instance_start_time;
+        Ok(restarted)
+    }
+}
+
+impl<'a, T: Transport + 'a> Iterator for ContinuousChanges<'a, T> {
+    type Item = Result<ChangeEvent, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(e) = self.error.take() {
+                self.done = true;
+                return Some(Err(e));
+            }
+
+            if let Some(row) = self.buffer.pop_front() {
+                return Some(Ok(ChangeEvent::Change(row)));
+            }
+
+            if self.done {
+                return None;
+            }
+
+            // Back off before reconnecting, so that a server that is down
+            // isn't hit in a busy loop.
+            if let Some(delay) = self.pending_delay.take() {
+                std::thread::sleep(delay);
+            }
+
+            if self.restart_check_pending {
+                match self.check_restart() {
+                    Ok(restarted) => {
+                        self.restart_check_pending = false;
+                        if restarted {
+                            self.query.since = Since::Zero;
+                            return Some(Ok(ChangeEvent::Restarted));
+                        }
+                    }
+                    Err(e) => return Some(self.handle_error(e)),
+                }
+            }
+
+            match self.query.send(self.transport, self.db_path.as_ref().unwrap()) {
+                Ok(changes) => {
+                    self.reconnects = 0;
+                    self.query.since = Since::Seq(changes.last_seq().clone());
+                    self.buffer.extend(changes.into_results());
+                }
+                Err(e) => return Some(self.handle_error(e)),
+            }
+        }
+    }
+}
+
+/// Iterates through a database's changes as they happen, pairing each change
+/// with the checkpoint to persist after processing it.
+///
+/// The checkpoint is the change's update sequence in string form. Resuming
+/// the feed from a persisted checkpoint yields only changes after the one the
+/// checkpoint was persisted for. Reconnections are handled silently.
+///
+pub struct CheckpointedChanges<'a, T: Transport + 'a> {
+    inner: ContinuousChanges<'a, T>,
+}
+
+impl<'a, T: Transport + 'a> Iterator for CheckpointedChanges<'a, T> {
+    type Item = Result<(ChangeRow, String), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.inner.next() {
+                None => return None,
+                Some(Err(e)) => return Some(Err(e)),
+                Some(Ok(ChangeEvent::Reconnected { .. })) => (),
+                Some(Ok(ChangeEvent::Restarted)) => (),
+                Some(Ok(ChangeEvent::Change(row))) => {
+                    let checkpoint = String::from(row.seq().as_str());
+                    return Some(Ok((row, checkpoint)));
+                }
+            }
+        }
+    }
+}
+
+// Query holds the changes feed's parameters so that one-shot reads and
+// continuous iteration build requests the same way.
+struct Query {
+    since: Since,
+    limit: Option<u64>,
+    include_docs: bool,
+    all_revisions: bool,
+    filter: Option<ChangesFilter>,
+    feed: Option<&'static str>,
+}
+
+impl Query {
+    fn make_request<T: Transport>(&self, transport: &T, db_path: &DatabasePath) -> Request {
+
+        let mut request = transport.get(db_path.iter().chain(std::iter::once("_changes")))
+            .with_accept_json();
+
+        if let Some(feed) = self.feed {
+            request = request.with_query(FeedQueryKey, &feed);
+        }
+
+        if self.since != Since::Zero {
+            request = request.with_query(SinceQueryKey, &self.since);
+        }
+
+        if let Some(limit) = self.limit {
+            request = request.with_query(LimitQueryKey, &limit);
+        }
+
+        if self.include_docs {
+            request = request.with_query(IncludeDocsQueryKey, &true);
+        }
+
+        if self.all_revisions {
+            request = request.with_query(StyleQueryKey, &"all_docs");
+        }
+
+        if let Some(ref filter) = self.filter {
+            request = filter.add_to_request(request);
+        }
+
+        request
+    }
+
+    fn send<T: Transport>(&self, transport: &T, db_path: &DatabasePath) -> Result<Changes, Error> {
+        let db_name = db_path.database_name().clone();
+        transport.send(self.make_request(transport, db_path),
+                       JsonResponseDecoder::new(move |response| handle_response(response, db_name)))
+    }
+}
+
+fn handle_response(response: JsonResponse, db_name: DatabaseName) -> Result<Changes, Error> {
+    match response.status_code() {
+        StatusCode::Ok => Changes::new_from_json(db_name, try!(response.decode_content())),
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {DatabaseName, DocumentId, Error, Seq, Since, std};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = (transport.get(vec!["foo", "_changes"]).with_accept_json(), DatabaseName::from("foo"));
+        let got = ReadChanges::new(&transport, "/foo").make_request().unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_options() {
+        let transport = MockTransport::new();
+        let expected = (transport.get(vec!["foo", "_changes"])
+            .with_accept_json()
+            .with_query_literal("since", "42-g1AAAA")
+            .with_query_literal("limit", "10")
+            .with_query_literal("include_docs", "true")
+            .with_query_literal("style", "all_docs"),
+                        DatabaseName::from("foo"));
+        let got = ReadChanges::new(&transport, "/foo")
+            .with_since("42-g1AAAA")
+            .with_limit(10)
+            .with_include_docs(true)
+            .with_all_revisions(true)
+            .make_request()
+            .unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_since_now() {
+        let transport = MockTransport::new();
+        let expected = (transport.get(vec!["foo", "_changes"]).with_accept_json().with_query_literal("since", "now"),
+                        DatabaseName::from("foo"));
+        let got = ReadChanges::new(&transport, "/foo").with_since(Since::Now).make_request().unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_view_filter() {
+        let transport = MockTransport::new();
+        let expected = (transport.get(vec!["foo", "_changes"])
+            .with_accept_json()
+            .with_query_literal("filter", "_view")
+            .with_query_literal("view", "bar/qux"),
+                        DatabaseName::from("foo"));
+        let got = ReadChanges::new(&transport, "/foo")
+            .with_filter(ChangesFilter::view("bar", "qux"))
+            .make_request()
+            .unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_view_filter_nok_bad_name() {
+        let transport = MockTransport::new();
+        for &(ddoc, view) in &[("", "qux"), ("bar", ""), ("bar/baz", "qux"), ("bar", "qux/baz")] {
+            match ReadChanges::new(&transport, "/foo")
+                .with_filter(ChangesFilter::view(ddoc, view))
+                .make_request() {
+                Err(Error::InvalidOptions(..)) => {}
+                x => unexpected_result!(x),
+            }
+        }
+    }
+
+    #[test]
+    fn continuous_nok_bad_view_filter() {
+        let transport = MockTransport::new();
+        let mut changes = ReadChanges::new(&transport, "/foo")
+            .with_filter(ChangesFilter::view("", "qux"))
+            .into_continuous();
+        match changes.next() {
+            Some(Err(Error::InvalidOptions(..))) => {}
+            x => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn continuous_make_request() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_changes"])
+            .with_accept_json()
+            .with_query_literal("feed", "longpoll")
+            .with_query_literal("since", "42");
+        let changes = ReadChanges::new(&transport, "/foo").with_since(42).into_continuous();
+        let got = changes.query.make_request(&transport, changes.db_path.as_ref().unwrap());
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn continuous_resumes_from_last_seq() {
+
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"results": [{"seq": 1, "id": "bar",
+                                           "changes": [{"rev": "1-1234567890abcdef1234567890abcdef"}]}],
+                                           "last_seq": 1}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"results": [{"seq": 2, "id": "qux",
+                                           "changes": [{"rev": "1-1234567890abcdef1234567890abcdef"}]}],
+                                           "last_seq": 2}"#)
+                .unwrap());
+
+        let mut changes = ReadChanges::new(&transport, "/foo").into_continuous();
+
+        match changes.next().unwrap() {
+            Ok(ChangeEvent::Change(ref row)) if row.seq() == &Seq::from(1) => (),
+            x @ _ => unexpected_result!(x),
+        }
+        assert_eq!(&Since::from(1), changes.since());
+
+        match changes.next().unwrap() {
+            Ok(ChangeEvent::Change(ref row)) if row.seq() == &Seq::from(2) => (),
+            x @ _ => unexpected_result!(x),
+        }
+        assert_eq!(&Since::from(2), changes.since());
+    }
+
+    #[test]
+    fn continuous_resilient_reconnects() {
+
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::ServiceUnavailable).unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"results": [{"seq": 1, "id": "bar",
+                                           "changes": [{"rev": "1-1234567890abcdef1234567890abcdef"}]}],
+                                           "last_seq": 1}"#)
+                .unwrap());
+
+        let mut changes = ReadChanges::new(&transport, "/foo")
+            .with_resilient(true)
+            .with_reconnect_delay(std::time::Duration::from_millis(0))
+            .into_continuous();
+
+        match changes.next().unwrap() {
+            Ok(ChangeEvent::Reconnected { attempt: 1 }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+
+        match changes.next().unwrap() {
+            Ok(ChangeEvent::Change(ref row)) if row.seq() == &Seq::from(1) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn continuous_restart_detection_signals_restart() {
+
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"db_name": "foo", "doc_count": 1, "doc_del_count": 0, "update_seq": 5,
+                                           "instance_start_time": "1458167485345987"}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::ServiceUnavailable).unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"db_name": "foo", "doc_count": 1, "doc_del_count": 0, "update_seq": 5,
+                                           "instance_start_time": "1458167999999999"}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"results": [{"seq": 1, "id": "bar",
+                                           "changes": [{"rev": "1-1234567890abcdef1234567890abcdef"}]}],
+                                           "last_seq": 1}"#)
+                .unwrap());
+
+        let mut changes = ReadChanges::new(&transport, "/foo")
+            .with_since(5)
+            .with_resilient(true)
+            .with_reconnect_delay(std::time::Duration::from_millis(0))
+            .with_restart_detection(true)
+            .into_continuous();
+
+        match changes.next().unwrap() {
+            Ok(ChangeEvent::Reconnected { attempt: 1 }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+
+        match changes.next().unwrap() {
+            Ok(ChangeEvent::Restarted) => (),
+            x @ _ => unexpected_result!(x),
+        }
+        assert_eq!(&Since::Zero, changes.since());
+
+        match changes.next().unwrap() {
+            Ok(ChangeEvent::Change(ref row)) if row.seq() == &Seq::from(1) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn continuous_restart_detection_ignores_placeholder_start_time() {
+
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"db_name": "foo", "doc_count": 1, "doc_del_count": 0,
+                                           "update_seq": "5-g1AAAA", "instance_start_time": "0"}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::ServiceUnavailable).unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"db_name": "foo", "doc_count": 1, "doc_del_count": 0,
+                                           "update_seq": "5-g1AAAA", "instance_start_time": "0"}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"results": [{"seq": "6-g1AAAA", "id": "bar",
+                                           "changes": [{"rev": "1-1234567890abcdef1234567890abcdef"}]}],
+                                           "last_seq": "6-g1AAAA"}"#)
+                .unwrap());
+
+        let mut changes = ReadChanges::new(&transport, "/foo")
+            .with_resilient(true)
+            .with_reconnect_delay(std::time::Duration::from_millis(0))
+            .with_restart_detection(true)
+            .into_continuous();
+
+        match changes.next().unwrap() {
+            Ok(ChangeEvent::Reconnected { attempt: 1 }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+
+        match changes.next().unwrap() {
+            Ok(ChangeEvent::Change(ref row)) if row.seq() == &Seq::from("6-g1AAAA") => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn continuous_resilient_gives_up_after_max_reconnects() {
+
+        let mut transport = MockTransport::new();
+        for _ in 0..MAX_CONSECUTIVE_RECONNECTS + 1 {
+            transport = transport.with_response(JsonResponseBuilder::new(StatusCode::ServiceUnavailable).unwrap());
+        }
+
+        let changes = ReadChanges::new(&transport, "/foo")
+            .with_resilient(true)
+            .with_reconnect_delay(std::time::Duration::from_millis(0))
+            .into_continuous();
+        let mut items = changes.collect::<Vec<_>>();
+
+        assert_eq!(MAX_CONSECUTIVE_RECONNECTS as usize + 1, items.len());
+        match items.pop().unwrap() {
+            Err(Error::ServerResponse { .. }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn reconnect_delay_doubles_per_attempt() {
+        let base = std::time::Duration::from_millis(100);
+        assert_eq!(std::time::Duration::from_millis(100), super::reconnect_delay(base, 1));
+        assert_eq!(std::time::Duration::from_millis(200), super::reconnect_delay(base, 2));
+        assert_eq!(std::time::Duration::from_millis(1600), super::reconnect_delay(base, 5));
+    }
+
+    #[test]
+    fn continuous_resilient_backoff_resets_after_change() {
+
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::ServiceUnavailable).unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::ServiceUnavailable).unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"results": [{"seq": 1, "id": "bar",
+                                           "changes": [{"rev": "1-1234567890abcdef1234567890abcdef"}]}],
+                                           "last_seq": 1}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::ServiceUnavailable).unwrap());
+
+        let base = std::time::Duration::from_millis(1);
+        let mut changes = ReadChanges::new(&transport, "/foo")
+            .with_resilient(true)
+            .with_reconnect_delay(base)
+            .into_continuous();
+
+        match changes.next().unwrap() {
+            Ok(ChangeEvent::Reconnected { attempt: 1 }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+        assert_eq!(Some(base), changes.pending_delay);
+
+        match changes.next().unwrap() {
+            Ok(ChangeEvent::Reconnected { attempt: 2 }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+        assert_eq!(Some(base * 2), changes.pending_delay);
+
+        match changes.next().unwrap() {
+            Ok(ChangeEvent::Change(ref row)) if row.seq() == &Seq::from(1) => (),
+            x @ _ => unexpected_result!(x),
+        }
+        assert_eq!(None, changes.pending_delay);
+
+        match changes.next().unwrap() {
+            Ok(ChangeEvent::Reconnected { attempt: 1 }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+        assert_eq!(Some(base), changes.pending_delay);
+    }
+
+    #[test]
+    fn continuous_not_resilient_ends_on_error() {
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::ServiceUnavailable).unwrap());
+        let changes = ReadChanges::new(&transport, "/foo").into_continuous();
+        let items = changes.collect::<Vec<_>>();
+        assert_eq!(1, items.len());
+        assert!(items[0].is_err());
+    }
+
+    #[test]
+    fn checkpointed_skips_reconnects() {
+
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::ServiceUnavailable).unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"results": [{"seq": "7-g1AAAA", "id": "bar",
+                                           "changes": [{"rev": "1-1234567890abcdef1234567890abcdef"}]}],
+                                           "last_seq": "7-g1AAAA"}"#)
+                .unwrap());
+
+        let mut changes = ReadChanges::new(&transport, "/foo")
+            .with_resilient(true)
+            .with_reconnect_delay(std::time::Duration::from_millis(0))
+            .into_continuous()
+            .into_checkpointed();
+
+        match changes.next().unwrap() {
+            Ok((ref row, ref checkpoint)) if row.doc_id() == &DocumentId::from("bar") &&
+                                             checkpoint == "7-g1AAAA" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_ok() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"results": [{"seq": 1, "id": "bar",
+                                                    "changes": [{"rev": "1-1234567890abcdef1234567890abcdef"}]}],
+                                       "last_seq": 1}"#)
+            .unwrap();
+        let got = super::handle_response(response, DatabaseName::from("foo")).unwrap();
+        assert_eq!(1, got.results().len());
+        assert_eq!(&Seq::from(1), got.last_seq());
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Database does not exist."}"#)
+            .unwrap();
+
+        match super::handle_response(response, DatabaseName::from("foo")) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" &&
+                                                        error_response.reason() == "Database does not exist." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Defines an action for reading a configuration value from the CouchDB
+//! server.
+
+use Error;
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads a configuration value from the CouchDB server and returns the result.
+///
+/// Chill reads the configuration value by sending an HTTP request to `GET
+/// /_node/{node}/_config/{section}/{key}`. The node may be `_local` to
+/// specify the node that handles the request.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when reading a
+/// configuration value.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::Forbidden</code></td>
+///  <td>The client is not a server admin.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The configuration value does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read the configuration value.</td>
+/// </tr>
+/// </table>
+///
+pub struct ReadConfig<'a, T: Transport + 'a> {
+    transport: &'a T,
+    node: &'a str,
+    section: &'a str,
+    key: &'a str,
+}
+
+impl<'a, T: Transport + 'a> ReadConfig<'a, T> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, node: &'a str, section: &'a str, key: &'a str) -> Self {
+        ReadConfig {
+            transport: transport,
+            node: node,
+            section: section,
+            key: key,
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<String, Error> {
+        self.transport.send(self.make_request(), JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&mut self) -> Request {
+        self.transport.get(vec!["_node", self.node, "_config", self.section, self.key]).with_accept_json()
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<String, Error> {
+    match response.status_code() {
+        StatusCode::Ok => Ok(try!(response.decode_content())),
+        StatusCode::Forbidden => Err(Error::forbidden(&response)),
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use Error;
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["_node", "_local", "_config", "httpd", "max_http_request_size"])
+            .with_accept_json();
+
+        let got = ReadConfig::new(&transport, "_local", "httpd", "max_http_request_size").make_request();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#""4294967296""#)
+            .unwrap();
+
+        let expected = String::from("4294967296");
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_forbidden() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Forbidden)
+            .with_json_content_raw(r#"{"error":"forbidden","reason":"You are not a server admin."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Forbidden(ref error_response)) if error_response.error() == "forbidden" &&
+                                                         error_response.reason() == "You are not a server admin." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"unknown_config_value"}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" &&
+                                                        error_response.reason() == "unknown_config_value" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(r#"{"error":"unauthorized","reason":"Authentication required."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(ref error_response)) if error_response.error() == "unauthorized" &&
+                                                            error_response.reason() == "Authentication required." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Defines an action for reading meta-information about a database.
+
+use {DatabaseInfo, Error, IntoDatabasePath, std};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads meta-information about a database.
+///
+/// Chill reads the database info by sending an HTTP request to `GET /db`.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when reading database info.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read the database.</td>
+/// </tr>
+/// </table>
+///
+pub struct ReadDatabaseInfo<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+}
+
+impl<'a, P: IntoDatabasePath, T: Transport + 'a> ReadDatabaseInfo<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P) -> Self {
+        ReadDatabaseInfo {
+            transport: transport,
+            db_path: Some(db_path),
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<DatabaseInfo, Error> {
+        self.transport.send(try!(self.make_request()), JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+        Ok(self.transport.get(db_path.iter()).with_accept_json())
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<DatabaseInfo, Error> {
+    match response.status_code() {
+        StatusCode::Ok => response.decode_content(),
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {DatabaseName, Error};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo"]).with_accept_json();
+
+        let got = {
+            let mut action = ReadDatabaseInfo::new(&transport, "/foo");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"db_name": "foo", "doc_count": 17, "doc_del_count": 3, "update_seq": 42,
+                                       "instance_start_time": "1458167485345987"}"#)
+            .unwrap();
+
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(&DatabaseName::from("foo"), got.database_name());
+        assert_eq!(17, got.document_count());
+        assert_eq!(Some("1458167485345987"), got.instance_start_time());
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Database does not exist."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" &&
+                                                        error_response.reason() == "Database does not exist." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
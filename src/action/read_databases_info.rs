@@ -0,0 +1,137 @@
+//! Defines an action for reading info about multiple databases from the
+//! CouchDB server.
+
+use {DatabaseInfo, DatabaseName, Error, serde_json};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads info about multiple databases from the CouchDB server and returns the
+/// result.
+///
+/// Chill reads the database info by sending an HTTP request to `POST
+/// /_dbs_info`, which requires CouchDB 2.2 or later. The result contains one
+/// entry per requested database, in the same order as requested. An entry is
+/// `None` if the database does not exist.
+///
+pub struct ReadDatabasesInfo<'a, T: Transport + 'a> {
+    transport: &'a T,
+    db_names: Vec<DatabaseName>,
+}
+
+impl<'a, T: Transport + 'a> ReadDatabasesInfo<'a, T> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_names: Vec<DatabaseName>) -> Self {
+        ReadDatabasesInfo {
+            transport: transport,
+            db_names: db_names,
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Vec<Option<DatabaseInfo>>, Error> {
+        self.transport.send(try!(self.make_request()),
+                            JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert("keys", &self.db_names)
+            .build();
+        self.transport.post(vec!["_dbs_info"]).with_accept_json().with_json_content(&body)
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<Vec<Option<DatabaseInfo>>, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let entries: Vec<serde_json::Value> = try!(response.decode_content());
+            entries.into_iter()
+                .map(|entry| match entry.find("info") {
+                    None |
+                    Some(&serde_json::Value::Null) => Ok(None),
+                    Some(info) => {
+                        serde_json::from_value(info.clone())
+                            .map(|x| Some(x))
+                            .map_err(|e| Error::JsonDecode { cause: Box::new(e) })
+                    }
+                })
+                .collect()
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {DatabaseName, Error, serde_json};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_array("keys", |x| x.push("foo").push("bar"))
+            .build();
+        let expected = transport.post(vec!["_dbs_info"]).with_accept_json().with_json_content(&body).unwrap();
+
+        let got = {
+            let mut action = ReadDatabasesInfo::new(&transport,
+                                                    vec![DatabaseName::from("foo"), DatabaseName::from("bar")]);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"[{"key":"foo","info":{"db_name":"foo","doc_count":17,"doc_del_count":3,
+                                                               "update_seq":"42-abc"}},
+                                       {"key":"bar","info":null},
+                                       {"key":"qux","error":"not_found"}]"#)
+            .unwrap();
+
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(3, got.len());
+        assert_eq!(Some(&DatabaseName::from("foo")),
+                   got[0].as_ref().map(|x| x.database_name()));
+        assert_eq!(Some(17), got[0].as_ref().map(|x| x.document_count()));
+        assert_eq!(None, got[1]);
+        assert_eq!(None, got[2]);
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Database does not exist."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" &&
+                                                        error_response.reason() == "Database does not exist." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(r#"{"error":"unauthorized","reason":"Authentication required."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(ref error_response)) if error_response.error() == "unauthorized" &&
+                                                            error_response.reason() == "Authentication required." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
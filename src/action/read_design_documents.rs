@@ -0,0 +1,268 @@
+//! Defines an action for listing the design documents in a database.
+
+use {DatabaseName, DatabasePath, Design, DesignDocumentName, DesignDocumentPath, DesignDocumentRow, Error,
+     IntoDatabasePath, Revision, serde_json, std};
+use action::query_keys::*;
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Lists the design documents in a database and returns the result.
+///
+/// Chill lists the design documents by sending an HTTP request to `GET
+/// /db/_design_docs`, which CouchDB v2.2 and later support. If the server
+/// rejects the request as a bad document id, as older servers do, then Chill
+/// falls back to `GET /db/_all_docs` with a key range spanning the design
+/// documents. If the client has already detected that the server lacks the
+/// `_design_docs` endpoint—see `ReadCapabilities`—then Chill sends only the
+/// fallback request.
+///
+/// By default, the result contains each design document's path and revision
+/// but not its content—see `with_documents`.
+///
+pub struct ReadDesignDocuments<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    include_docs: bool,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> ReadDesignDocuments<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P) -> Self {
+        ReadDesignDocuments {
+            transport: transport,
+            db_path: Some(db_path),
+            include_docs: false,
+        }
+    }
+
+    /// Modifies the action to retrieve (or not retrieve) each design document's
+    /// content.
+    ///
+    /// The `with_documents` method abstracts the `include_docs` query
+    /// parameter. If enabled, the content is available via the
+    /// `DesignDocumentRow::design` method.
+    ///
+    pub fn with_documents(mut self, include_docs: bool) -> Self {
+        self.include_docs = include_docs;
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Vec<DesignDocumentRow>, Error> {
+
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+
+        let include_docs = self.include_docs;
+        let has_design_docs = self.transport
+            .server_capabilities()
+            .and_then(|x| x.probed())
+            .map(|x| x.has_design_docs())
+            .unwrap_or(true);
+
+        let result = if has_design_docs {
+            let db_name = db_path.database_name().clone();
+            Some(self.transport.send(self.make_request(&db_path),
+                                     JsonResponseDecoder::new(move |response| {
+                                         handle_response(response, db_name, include_docs)
+                                     })))
+        } else {
+            None
+        };
+
+        match result {
+            Some(Err(Error::ServerResponse { status_code: StatusCode::BadRequest, .. })) |
+            Some(Err(Error::NotFound(..))) |
+            None => {
+                let db_name = db_path.database_name().clone();
+                let request = try!(self.make_fallback_request(&db_path));
+                self.transport.send(request,
+                                    JsonResponseDecoder::new(move |response| {
+                                        handle_response(response, db_name, include_docs)
+                                    }))
+            }
+            Some(x) => x,
+        }
+    }
+
+    fn make_request(&self, db_path: &DatabasePath) -> Request {
+        let request = self.transport.get(db_path.iter().chain(std::iter::once("_design_docs"))).with_accept_json();
+        if self.include_docs {
+            request.with_query(IncludeDocsQueryKey, &true)
+        } else {
+            request
+        }
+    }
+
+    fn make_fallback_request(&self, db_path: &DatabasePath) -> Result<Request, Error> {
+        let request = self.transport.get(db_path.iter().chain(std::iter::once("_all_docs"))).with_accept_json();
+        let request = try!(request.with_query_fallible(StartKeyQueryKey, &"_design/"));
+        let request = try!(request.with_query_fallible(EndKeyQueryKey, &"_design0"));
+        Ok(if self.include_docs {
+            request.with_query(IncludeDocsQueryKey, &true)
+        } else {
+            request
+        })
+    }
+}
+
+fn handle_response(response: JsonResponse,
+                   db_name: DatabaseName,
+                   include_docs: bool)
+                   -> Result<Vec<DesignDocumentRow>, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let mut body: serde_json::Value = try!(response.decode_content());
+            let rows = match body.as_object_mut().and_then(|x| x.remove("rows")) {
+                Some(serde_json::Value::Array(rows)) => rows,
+                _ => return Err(Error::UnexpectedResponse("The response has no rows")),
+            };
+            rows.into_iter().map(|row| decode_row(row, &db_name, include_docs)).collect()
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+fn decode_row(mut row: serde_json::Value,
+              db_name: &DatabaseName,
+              include_docs: bool)
+              -> Result<DesignDocumentRow, Error> {
+
+    let ddoc_name = match row.find("id").and_then(|x| x.as_str()) {
+        Some(x) if x.starts_with("_design/") => DesignDocumentName::from(&x["_design/".len()..]),
+        _ => return Err(Error::UnexpectedResponse("The row's id is not a design document id")),
+    };
+
+    let revision = match row.find_path(&["value", "rev"]).and_then(|x| x.as_str()) {
+        Some(x) => try!(Revision::parse(x)),
+        None => return Err(Error::UnexpectedResponse("The row has no revision")),
+    };
+
+    let doc = if include_docs {
+        row.as_object_mut().and_then(|x| x.remove("doc"))
+    } else {
+        None
+    };
+
+    let design = match doc {
+        None => None,
+        Some(doc) => {
+            Some(try!(serde_json::from_value::<Design>(doc).map_err(|e| Error::JsonDecode { cause: Box::new(e) })))
+        }
+    };
+
+    Ok(DesignDocumentRow::new(DesignDocumentPath::from((db_name.clone(), ddoc_name)), revision, design))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {Capabilities, DatabaseName, DesignBuilder, DesignDocumentPath, DesignDocumentRow, Error, IntoDatabasePath,
+         Revision, serde_json};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_design_docs"]).with_accept_json();
+        let action = ReadDesignDocuments::new(&transport, "/foo");
+        let got = action.make_request(&"/foo".into_database_path().unwrap());
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_documents() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_design_docs"])
+            .with_accept_json()
+            .with_query_literal("include_docs", "true");
+        let action = ReadDesignDocuments::new(&transport, "/foo").with_documents(true);
+        let got = action.make_request(&"/foo".into_database_path().unwrap());
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_fallback_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_all_docs"])
+            .with_accept_json()
+            .with_query_literal("startkey", r#""_design/""#)
+            .with_query_literal("endkey", r#""_design0""#);
+        let action = ReadDesignDocuments::new(&transport, "/foo");
+        let got = action.make_fallback_request(&"/foo".into_database_path().unwrap()).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn run_falls_back_on_bad_request() {
+
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::BadRequest)
+                .with_json_content_raw(r#"{"error":"bad_request",
+                                           "reason":"Only reserved document ids may start with underscore."}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"total_rows": 1, "offset": 0, "rows": [
+                    {"id": "_design/bar", "key": "_design/bar",
+                     "value": {"rev": "1-1234567890abcdef1234567890abcdef"}}]}"#)
+                .unwrap());
+
+        let expected = vec![DesignDocumentRow::new(DesignDocumentPath::from((DatabaseName::from("foo"), "bar")),
+                                                   Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
+                                                   None)];
+
+        let got = ReadDesignDocuments::new(&transport, "/foo").run().unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn run_skips_design_docs_with_probed_capabilities() {
+
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"total_rows": 0, "offset": 0, "rows": []}"#)
+            .unwrap());
+
+        let info = serde_json::from_str(r#"{"couchdb":"Welcome","version":"2.1.1"}"#).unwrap();
+        transport.server_capabilities().unwrap().set_probed(Capabilities::from_server_info(&info).unwrap());
+
+        let got = ReadDesignDocuments::new(&transport, "/foo").run().unwrap();
+        assert_eq!(Vec::<DesignDocumentRow>::new(), got);
+
+        let action = ReadDesignDocuments::new(&transport, "/foo");
+        let expected = vec![action.make_fallback_request(&"/foo".into_database_path().unwrap()).unwrap()];
+        assert_eq!(expected, transport.take_requests());
+    }
+
+    #[test]
+    fn handle_response_ok_with_documents() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"total_rows": 1, "offset": 0, "rows": [
+                {"id": "_design/bar", "key": "_design/bar", "value": {"rev": "1-1234567890abcdef1234567890abcdef"},
+                 "doc": {"_id": "_design/bar", "_rev": "1-1234567890abcdef1234567890abcdef",
+                         "language": "javascript", "views": {}}}]}"#)
+            .unwrap();
+
+        let expected = vec![DesignDocumentRow::new(DesignDocumentPath::from((DatabaseName::from("foo"), "bar")),
+                                                   Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
+                                                   Some(DesignBuilder::new().unwrap()))];
+
+        let got = super::handle_response(response, DatabaseName::from("foo"), true).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(r#"{"error":"unauthorized","reason":"Authentication required."}"#)
+            .unwrap();
+
+        match super::handle_response(response, DatabaseName::from("foo"), false) {
+            Err(Error::Unauthorized(ref error_response)) if error_response.error() == "unauthorized" &&
+                                                            error_response.reason() == "Authentication required." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! Defines an action for reading a document from the CouchDB server.
 
-use {DatabaseName, Document, Error, IntoDocumentPath, Revision, std};
+use {Attachment, AttachmentName, DatabaseName, Document, Error, IntoDocumentPath, Revision, serde_json, std};
 use action::query_keys::*;
 use document::JsonDecodableDocument;
 use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
@@ -21,6 +21,11 @@ use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transpor
 ///  <td>The database or document does not exist.</td>
 /// </tr>
 /// <tr>
+///  <td><code>Error::PreconditionFailed</code></td>
+///  <td>The document's current revision does not match the revision given
+///  via <code>with_if_match</code>.</td>
+/// </tr>
+/// <tr>
 ///  <td><code>Error::Unauthorized</code></td>
 ///  <td>The client lacks permission to read the document.</td>
 /// </tr>
@@ -61,6 +66,12 @@ pub struct ReadDocument<'a, T: Transport + 'a, P: IntoDocumentPath> {
     doc_path: Option<P>,
     revision: Option<&'a Revision>,
     attachment_content: Option<AttachmentContent>,
+    multipart_attachments: bool,
+    conflicts: Option<bool>,
+    revisions_info: Option<bool>,
+    latest: Option<bool>,
+    if_match: Option<&'a Revision>,
+    custom_query: Vec<(String, String)>,
 }
 
 impl<'a, T: Transport + 'a, P: IntoDocumentPath> ReadDocument<'a, T, P> {
@@ -71,6 +82,12 @@ impl<'a, T: Transport + 'a, P: IntoDocumentPath> ReadDocument<'a, T, P> {
             doc_path: Some(doc_path),
             revision: None,
             attachment_content: None,
+            multipart_attachments: false,
+            conflicts: None,
+            revisions_info: None,
+            latest: None,
+            if_match: None,
+            custom_query: Vec::new(),
         }
     }
 
@@ -95,17 +112,130 @@ impl<'a, T: Transport + 'a, P: IntoDocumentPath> ReadDocument<'a, T, P> {
         self
     }
 
+    /// Modifies the action to receive attachment content as binary parts of a
+    /// multipart response instead of as base64-encoded JSON.
+    ///
+    /// The `with_multipart_attachments` method abstracts the
+    /// `multipart/related` content type of the HTTP request `GET /db/docid`.
+    /// It applies only when the action also retrieves attachment content—see
+    /// the `with_attachment_content` method. Sending attachment content as
+    /// binary avoids the size and decoding overhead of base64. If the CouchDB
+    /// server responds with JSON anyway, then Chill decodes the base64
+    /// content as usual.
+    ///
+    pub fn with_multipart_attachments(mut self, multipart_attachments: bool) -> Self {
+        self.multipart_attachments = multipart_attachments;
+        self
+    }
+
+    /// Modifies the action to retrieve (or not retrieve) the document's
+    /// conflicting revisions.
+    ///
+    /// The `with_conflicts` method abstracts the `conflicts` query parameter of
+    /// the HTTP request `GET /db/docid`. If enabled, the conflicting revisions
+    /// are available via the `Document::conflicts` method.
+    ///
+    pub fn with_conflicts(mut self, conflicts: bool) -> Self {
+        self.conflicts = Some(conflicts);
+        self
+    }
+
+    /// Modifies the action to retrieve (or not retrieve) the availability of
+    /// the document's past revisions.
+    ///
+    /// The `with_revisions_info` method abstracts the `revs_info` query
+    /// parameter of the HTTP request `GET /db/docid`. If enabled, the revision
+    /// info is available via the `Document::revisions_info` method.
+    ///
+    pub fn with_revisions_info(mut self, revisions_info: bool) -> Self {
+        self.revisions_info = Some(revisions_info);
+        self
+    }
+
+    /// Modifies the action to read the latest leaf revision descending from
+    /// the revision given via `with_revision`.
+    ///
+    /// The `with_latest` method abstracts the `latest` query parameter of the
+    /// HTTP request `GET /db/docid`. If the given revision is stale—i.e., the
+    /// document has since been updated—then the server sends the newest leaf
+    /// revision descending from it instead of the exact revision, so the
+    /// result's revision may differ from the given one. If the given revision
+    /// is itself a leaf, then the server sends that revision. Enabling this
+    /// option without also specifying a revision causes the action to fail
+    /// with an `Error::InvalidOptions` error.
+    ///
+    pub fn with_latest(mut self, latest: bool) -> Self {
+        self.latest = Some(latest);
+        self
+    }
+
+    /// Modifies the action to read the document only if the document's current
+    /// revision is the given revision.
+    ///
+    /// The `with_if_match` method abstracts the `If-Match` header of the HTTP
+    /// request `GET /db/docid`. If the document's current revision differs,
+    /// then the action fails with an `Error::PreconditionFailed` error.
+    ///
+    pub fn with_if_match(mut self, revision: &'a Revision) -> Self {
+        self.if_match = Some(revision);
+        self
+    }
+
+    /// Modifies the action to include a query parameter that Chill does not
+    /// otherwise support—see `ExecuteView::with_query_param` for details.
+    ///
+    pub fn with_query_param<K, V>(mut self, key: K, value: V) -> Self
+        where K: Into<String>,
+              V: Into<String>
+    {
+        self.custom_query.push((key.into(), value.into()));
+        self
+    }
+
     /// Executes the action and waits for the result.
     pub fn run(mut self) -> Result<Document, Error> {
         let (request, db_name) = try!(self.make_request());
-        self.transport.send(request,
-                            JsonResponseDecoder::new(move |response| handle_response(response, db_name)))
+        let decoder = JsonResponseDecoder::new(move |response| handle_response(response, db_name));
+        let decoder = if self.multipart_attachments {
+            decoder.with_multipart()
+        } else {
+            decoder
+        };
+        self.transport.send(request, decoder)
+    }
+
+    /// Executes the action, waits for the result, and also returns the
+    /// document as the server sent it.
+    ///
+    /// The raw value is the decoded response body, including the `_id`,
+    /// `_rev`, and `_attachments` fields and any fields the application's
+    /// content type doesn't know about—e.g., for preserving such fields when
+    /// later writing the document. The cost is that the document is held in
+    /// memory twice—once as the `Document` and once as the raw value.
+    ///
+    pub fn run_with_raw(mut self) -> Result<(Document, serde_json::Value), Error> {
+        let (request, db_name) = try!(self.make_request());
+        let decoder = JsonResponseDecoder::new(move |response| handle_response_with_raw(response, db_name));
+        let decoder = if self.multipart_attachments {
+            decoder.with_multipart()
+        } else {
+            decoder
+        };
+        self.transport.send(request, decoder)
     }
 
     fn make_request(&mut self) -> Result<(Request, DatabaseName), Error> {
+        if self.latest == Some(true) && self.revision.is_none() {
+            return Err(Error::InvalidOptions("The latest option requires a revision"));
+        }
+
         let doc_path = try!(std::mem::replace(&mut self.doc_path, None).unwrap().into_document_path());
         let db_name = doc_path.database_name().clone();
-        let request = self.transport.get(doc_path.iter()).with_accept_json();
+        let request = if self.multipart_attachments {
+            self.transport.get(doc_path.iter()).with_accept_json_or_multipart_related()
+        } else {
+            self.transport.get(doc_path.iter()).with_accept_json()
+        };
 
         let request = match self.attachment_content {
             None => request,
@@ -118,6 +248,28 @@ impl<'a, T: Transport + 'a, P: IntoDocumentPath> ReadDocument<'a, T, P> {
             Some(rev) => request.with_query(RevisionQueryKey, rev),
         };
 
+        let request = match self.conflicts {
+            None => request,
+            Some(ref conflicts) => request.with_query(ConflictsQueryKey, conflicts),
+        };
+
+        let request = match self.revisions_info {
+            None => request,
+            Some(ref revisions_info) => request.with_query(RevsInfoQueryKey, revisions_info),
+        };
+
+        let request = match self.latest {
+            None => request,
+            Some(ref latest) => request.with_query(LatestQueryKey, latest),
+        };
+
+        let request = match self.if_match {
+            None => request,
+            Some(rev) => request.with_if_match(rev),
+        };
+
+        let request = try!(request.with_custom_query(&self.custom_query));
+
         Ok((request, db_name))
     }
 }
@@ -126,14 +278,65 @@ fn handle_response(response: JsonResponse, db_name: DatabaseName) -> Result<Docu
     match response.status_code() {
         StatusCode::Ok => {
             let decoded_doc: JsonDecodableDocument = try!(response.decode_content());
-            Ok(Document::new_from_decoded(db_name, decoded_doc))
+            make_document(response, db_name, decoded_doc)
         }
         StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::PreconditionFailed => Err(Error::precondition_failed(&response)),
         StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
         _ => Err(Error::server_response(&response)),
     }
 }
 
+fn handle_response_with_raw(response: JsonResponse,
+                            db_name: DatabaseName)
+                            -> Result<(Document, serde_json::Value), Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let raw: serde_json::Value = try!(response.decode_content());
+            let decoded_doc: JsonDecodableDocument = try!(serde_json::from_value(raw.clone())
+                .map_err(|e| Error::JsonDecode { cause: Box::new(e) }));
+            let doc = try!(make_document(response, db_name, decoded_doc));
+            Ok((doc, raw))
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::PreconditionFailed => Err(Error::precondition_failed(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+// Fills in the content of any attachments sent as parts of a multipart
+// response and constructs the document.
+fn make_document(response: JsonResponse,
+                 db_name: DatabaseName,
+                 mut decoded_doc: JsonDecodableDocument)
+                 -> Result<Document, Error> {
+    for part in response.into_parts() {
+        let att_name = match part.filename() {
+            Some(x) => AttachmentName::from(x),
+            None => continue,
+        };
+        match decoded_doc.attachments.get_mut(&att_name) {
+            Some(&mut Attachment::Saved(ref mut attachment)) => {
+                attachment.set_content_bytes(part.into_content());
+            }
+            _ => {
+                return Err(Error::UnexpectedResponse("The multipart response has content for an unknown \
+                                                      attachment"));
+            }
+        }
+    }
+    for attachment in decoded_doc.attachments.values() {
+        match attachment {
+            &Attachment::Saved(ref attachment) if attachment.is_missing_content() => {
+                return Err(Error::UnexpectedResponse("The multipart response has no content for an attachment"));
+            }
+            _ => (),
+        }
+    }
+    Ok(Document::new_from_decoded(db_name, decoded_doc))
+}
+
 /// Specifies the attachments, if any, for which the CouchDB server should send
 /// content.
 ///
@@ -153,7 +356,7 @@ pub enum AttachmentContent {
 #[cfg(test)]
 mod tests {
 
-    use {DatabaseName, Error, Revision};
+    use {DatabaseName, Error, Revision, serde_json};
     use super::*;
     use document::DocumentBuilder;
     use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
@@ -223,6 +426,142 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn make_request_with_multipart_attachments() {
+
+        let transport = MockTransport::new();
+
+        let expected = (transport.get(vec!["foo", "bar"])
+            .with_accept_json_or_multipart_related()
+            .with_query_literal("attachments", "true"),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = ReadDocument::new(&transport, "/foo/bar")
+                .with_attachment_content(AttachmentContent::All)
+                .with_multipart_attachments(true);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_conflicts() {
+
+        let transport = MockTransport::new();
+
+        let expected = (transport.get(vec!["foo", "bar"]).with_accept_json().with_query_literal("conflicts", "true"),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = ReadDocument::new(&transport, "/foo/bar").with_conflicts(true);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_revisions_info() {
+
+        let transport = MockTransport::new();
+
+        let expected = (transport.get(vec!["foo", "bar"]).with_accept_json().with_query_literal("revs_info", "true"),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = ReadDocument::new(&transport, "/foo/bar").with_revisions_info(true);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_latest() {
+
+        let transport = MockTransport::new();
+
+        let expected = (transport.get(vec!["foo", "bar"])
+            .with_accept_json()
+            .with_query_literal("rev", "1-1234567890abcdef1234567890abcdef")
+            .with_query_literal("latest", "true"),
+                        DatabaseName::from("foo"));
+
+        let rev = Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap();
+        let got = {
+            let mut action = ReadDocument::new(&transport, "/foo/bar").with_revision(&rev).with_latest(true);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_nok_latest_without_revision() {
+
+        let transport = MockTransport::new();
+        let mut action = ReadDocument::new(&transport, "/foo/bar").with_latest(true);
+
+        match action.make_request() {
+            Err(Error::InvalidOptions(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn make_request_with_query_param() {
+
+        let transport = MockTransport::new();
+
+        let expected = (transport.get(vec!["foo", "bar"])
+            .with_accept_json()
+            .with_query_literal("rev", "1-1234567890abcdef1234567890abcdef")
+            .with_query_literal("new option", "a&b"),
+                        DatabaseName::from("foo"));
+
+        let rev = Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap();
+        let got = {
+            let mut action = ReadDocument::new(&transport, "/foo/bar")
+                .with_revision(&rev)
+                .with_query_param("new option", "a&b")
+                .with_query_param("rev", "2-fedcba0987654321fedcba0987654321");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_nok_query_param_with_control_character() {
+
+        let transport = MockTransport::new();
+        let mut action = ReadDocument::new(&transport, "/foo/bar").with_query_param("alpha", "bravo\ncharlie");
+
+        match action.make_request() {
+            Err(Error::InvalidOptions(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn make_request_with_if_match() {
+
+        let transport = MockTransport::new();
+        let rev = Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap();
+
+        let expected = (transport.get(vec!["foo", "bar"]).with_accept_json().with_if_match(&rev),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = ReadDocument::new(&transport, "/foo/bar").with_if_match(&rev);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
     #[test]
     fn handle_response_ok() {
 
@@ -240,6 +579,63 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn handle_response_ok_multipart() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"_id": "bar", "_rev": "1-1234567890abcdef1234567890abcdef",
+                                       "_attachments": {"baz": {"content_type": "text/plain",
+                                                                "digest": "md5-1QaNgHhTBlpDFXHOqlX5lA==",
+                                                                "length": 11, "revpos": 1, "follows": true}}}"#)
+            .with_part("baz", b"baz content".to_vec())
+            .unwrap();
+
+        let doc = super::handle_response(response, DatabaseName::from("foo")).unwrap();
+        let content = doc.get_attachment("baz").unwrap().content();
+        assert_eq!(Some(&b"baz content".to_vec()), content);
+    }
+
+    #[test]
+    fn handle_response_nok_multipart_missing_part() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"_id": "bar", "_rev": "1-1234567890abcdef1234567890abcdef",
+                                       "_attachments": {"baz": {"content_type": "text/plain",
+                                                                "digest": "md5-1QaNgHhTBlpDFXHOqlX5lA==",
+                                                                "length": 11, "revpos": 1, "follows": true}}}"#)
+            .unwrap();
+
+        match super::handle_response(response, DatabaseName::from("foo")) {
+            Err(Error::UnexpectedResponse(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_with_raw_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"_id": "bar", "_rev": "1-1234567890abcdef1234567890abcdef", "field": 42,
+                                       "unknown": [1, 2]}"#)
+            .unwrap();
+
+        let rev = Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap();
+
+        let expected_doc = DocumentBuilder::new("/foo/bar", rev)
+            .build_content(|x| x.insert("field", 42).insert_array("unknown", |x| x.push(1).push(2)))
+            .unwrap();
+
+        let expected_raw = serde_json::builder::ObjectBuilder::new()
+            .insert("_id", "bar")
+            .insert("_rev", "1-1234567890abcdef1234567890abcdef")
+            .insert("field", 42)
+            .insert_array("unknown", |x| x.push(1).push(2))
+            .build();
+
+        let got = super::handle_response_with_raw(response, DatabaseName::from("foo")).unwrap();
+        assert_eq!((expected_doc, expected_raw), got);
+    }
+
     #[test]
     fn handle_response_not_found() {
 
@@ -254,6 +650,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn handle_response_precondition_failed() {
+
+        let response = JsonResponseBuilder::new(StatusCode::PreconditionFailed)
+            .with_json_content_raw(r#"{"error":"precondition_failed","reason":"Revision mismatch"}"#)
+            .unwrap();
+
+        match super::handle_response(response, DatabaseName::from("foo")) {
+            Err(Error::PreconditionFailed(ref error_response)) if error_response.error() == "precondition_failed" &&
+                                                                  error_response.reason() == "Revision mismatch" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
     #[test]
     fn handle_response_unauthorized() {
 
@@ -0,0 +1,146 @@
+//! Defines an action for reading a document's current revision.
+
+use {DocumentPath, Error, ErrorResponse, IntoDocumentPath, Revision, std};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads the current revision of a document without reading its content.
+///
+/// Chill reads the revision by sending an HTTP request to `HEAD /db/doc` and
+/// parsing the response's `ETag` header, which CouchDB sets to the document's
+/// current revision. Because the response has no content, this is cheaper
+/// than reading the document—e.g., for checking whether a revision is still
+/// current before attempting an update.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when reading a document's
+/// revision.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The document does not exist or is deleted.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read the document.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::UnexpectedResponse</code></td>
+///  <td>The response has no valid <code>ETag</code> header.</td>
+/// </tr>
+/// </table>
+///
+pub struct ReadDocumentRevision<'a, T: Transport + 'a, P: IntoDocumentPath> {
+    transport: &'a T,
+    doc_path: Option<P>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDocumentPath> ReadDocumentRevision<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, doc_path: P) -> Self {
+        ReadDocumentRevision {
+            transport: transport,
+            doc_path: Some(doc_path),
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Revision, Error> {
+        let doc_path = try!(std::mem::replace(&mut self.doc_path, None).unwrap().into_document_path());
+        self.transport.send(self.make_request(&doc_path),
+                            JsonResponseDecoder::new(handle_response).without_content_type_check())
+    }
+
+    fn make_request(&self, doc_path: &DocumentPath) -> Request {
+        self.transport.head(doc_path.iter())
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<Revision, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            match response.headers().etag().and_then(|x| Revision::parse(x).ok()) {
+                Some(rev) => Ok(rev),
+                None => Err(Error::UnexpectedResponse("The response has no valid ETag revision")),
+            }
+        }
+        // The response to a HEAD request normally has no content, so there is
+        // no server error response to decode.
+        StatusCode::NotFound => Err(Error::NotFound(ErrorResponse::new("not_found", "missing"))),
+        StatusCode::Unauthorized => {
+            Err(match Error::unauthorized(&response) {
+                Error::JsonDecode { .. } => {
+                    Error::Unauthorized(ErrorResponse::new("unauthorized", "Authentication required."))
+                }
+                e @ _ => e,
+            })
+        }
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {Error, IntoDocumentPath, Revision};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.head(vec!["foo", "bar"]);
+        let got = ReadDocumentRevision::new(&transport, "/foo/bar")
+            .make_request(&"/foo/bar".into_document_path().unwrap());
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_raw_header("ETag", r#""1-1234567890abcdef1234567890abcdef""#)
+            .unwrap();
+        let expected = Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap();
+        assert_eq!(expected, super::handle_response(response).unwrap());
+    }
+
+    #[test]
+    fn handle_response_nok_no_etag() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok).unwrap();
+        match super::handle_response(response) {
+            Err(Error::UnexpectedResponse(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+        let response = JsonResponseBuilder::new(StatusCode::NotFound).unwrap();
+        match super::handle_response(response) {
+            Err(Error::NotFound(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized_without_content() {
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized).unwrap();
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(r#"{"error":"unauthorized","reason":"Authentication required."}"#)
+            .unwrap();
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(ref error_response)) if error_response.error() == "unauthorized" &&
+                                                            error_response.reason() == "Authentication required." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Defines an action for reading multiple documents by id from the CouchDB
+//! server.
+
+use {DatabaseName, Document, DocumentId, Error, IntoDatabasePath, serde_json, std};
+use action::query_keys::*;
+use document::JsonDecodableDocument;
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads multiple documents by id from the CouchDB server and returns the
+/// result.
+///
+/// Chill reads the documents by sending an HTTP request to `POST
+/// /db/_all_docs?include_docs=true` with the document ids as the `keys` field
+/// of the request body. The result contains one entry per requested document,
+/// in the same order as requested. An entry is `None` if the document does not
+/// exist or is deleted.
+///
+/// Each document is the document's current revision.
+///
+pub struct ReadDocuments<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    doc_ids: Vec<DocumentId>,
+    custom_query: Vec<(String, String)>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> ReadDocuments<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P, doc_ids: Vec<DocumentId>) -> Self {
+        ReadDocuments {
+            transport: transport,
+            db_path: Some(db_path),
+            doc_ids: doc_ids,
+            custom_query: Vec::new(),
+        }
+    }
+
+    /// Modifies the action to include a query parameter that Chill does not
+    /// otherwise support.
+    ///
+    /// The parameter goes in the query string, whereas the document ids go in
+    /// the request body. Chill always sets the `include_docs` parameter, so a
+    /// custom parameter with that key is ignored. See
+    /// `ExecuteView::with_query_param` for how Chill encodes the parameter
+    /// and when the action fails.
+    ///
+    pub fn with_query_param<K, V>(mut self, key: K, value: V) -> Self
+        where K: Into<String>,
+              V: Into<String>
+    {
+        self.custom_query.push((key.into(), value.into()));
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Vec<Option<Document>>, Error> {
+        let (request, db_name) = try!(self.make_request());
+        self.transport.send(request,
+                            JsonResponseDecoder::new(move |response| handle_response(response, db_name)))
+    }
+
+    fn make_request(&mut self) -> Result<(Request, DatabaseName), Error> {
+
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+        let db_name = db_path.database_name().clone();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert("keys", &self.doc_ids)
+            .build();
+
+        let request = try!(self.transport
+            .post(db_path.iter().chain(std::iter::once("_all_docs")))
+            .with_accept_json()
+            .with_query(IncludeDocsQueryKey, &true)
+            .with_custom_query(&self.custom_query)
+            .and_then(|x| x.with_json_content(&body)));
+
+        Ok((request, db_name))
+    }
+}
+
+fn handle_response(response: JsonResponse, db_name: DatabaseName) -> Result<Vec<Option<Document>>, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let mut body: serde_json::Value = try!(response.decode_content());
+            let rows = match body.as_object_mut().and_then(|x| x.remove("rows")) {
+                Some(serde_json::Value::Array(rows)) => rows,
+                _ => return Err(Error::UnexpectedResponse("The response has no rows")),
+            };
+            rows.into_iter()
+                .map(|mut row| {
+                    match row.as_object_mut().and_then(|x| x.remove("doc")) {
+                        None |
+                        Some(serde_json::Value::Null) => Ok(None),
+                        Some(doc) => {
+                            let doc: JsonDecodableDocument = try!(serde_json::from_value(doc)
+                                .map_err(|e| Error::JsonDecode { cause: Box::new(e) }));
+                            Ok(Some(Document::new_from_decoded(db_name.clone(), doc)))
+                        }
+                    }
+                })
+                .collect()
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {DatabaseName, DocumentId, Error, Revision, serde_json};
+    use super::*;
+    use document::DocumentBuilder;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_array("keys", |x| x.push("bar").push("qux"))
+            .build();
+
+        let expected = (transport.post(vec!["foo", "_all_docs"])
+            .with_accept_json()
+            .with_query_literal("include_docs", "true")
+            .with_json_content(&body)
+            .unwrap(),
+                        DatabaseName::from("foo"));
+
+        let got = {
+            let mut action = ReadDocuments::new(&transport,
+                                                "/foo",
+                                                vec![DocumentId::from("bar"), DocumentId::from("qux")]);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"total_rows": 2, "offset": 0, "rows": [
+                {"id": "bar", "key": "bar", "value": {"rev": "1-1234567890abcdef1234567890abcdef"},
+                 "doc": {"_id": "bar", "_rev": "1-1234567890abcdef1234567890abcdef", "field": 42}},
+                {"key": "qux", "error": "not_found"},
+                {"id": "baz", "key": "baz", "value": {"rev": "2-1234567890abcdef1234567890abcdef", "deleted": true},
+                 "doc": null}
+            ]}"#)
+            .unwrap();
+
+        let expected = vec![Some(DocumentBuilder::new("/foo/bar",
+                                                      Revision::parse("1-1234567890abcdef1234567890abcdef")
+                                                          .unwrap())
+                                     .build_content(|x| x.insert("field", 42))
+                                     .unwrap()),
+                            None,
+                            None];
+
+        let got = super::handle_response(response, DatabaseName::from("foo")).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"no_db_file"}"#)
+            .unwrap();
+
+        match super::handle_response(response, DatabaseName::from("foo")) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" &&
+                                                        error_response.reason() == "no_db_file" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(r#"{"error":"unauthorized","reason":"Authentication required."}"#)
+            .unwrap();
+
+        match super::handle_response(response, DatabaseName::from("foo")) {
+            Err(Error::Unauthorized(ref error_response)) if error_response.error() == "unauthorized" &&
+                                                            error_response.reason() == "Authentication required." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
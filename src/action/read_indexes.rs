@@ -0,0 +1,154 @@
+//! Defines an action for listing a database's Mango indexes.
+
+use {DatabasePath, Error, IndexInfo, IntoDatabasePath, serde_json, std};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Lists the Mango indexes of a database.
+///
+/// Chill lists the indexes by sending an HTTP request to `GET /db/_index`.
+/// The result contains one `IndexInfo` per index, including the built-in
+/// `_all_docs` index—e.g., for reconciling the indexes an application
+/// declares via `CreateIndex` with the indexes that exist, and deleting the
+/// rest via `DeleteIndex`.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when listing indexes.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to list the indexes.</td>
+/// </tr>
+/// </table>
+///
+pub struct ReadIndexes<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> ReadIndexes<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P) -> Self {
+        ReadIndexes {
+            transport: transport,
+            db_path: Some(db_path),
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Vec<IndexInfo>, Error> {
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+        self.transport.send(self.make_request(&db_path), JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&self, db_path: &DatabasePath) -> Request {
+        self.transport.get(db_path.iter().chain(std::iter::once("_index"))).with_accept_json()
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<Vec<IndexInfo>, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let mut body: serde_json::Value = try!(response.decode_content());
+            let indexes = match body.as_object_mut().and_then(|x| x.remove("indexes")) {
+                Some(serde_json::Value::Array(indexes)) => indexes,
+                _ => return Err(Error::UnexpectedResponse("The response has no indexes")),
+            };
+            indexes.into_iter().map(decode_index).collect()
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+fn decode_index(index: serde_json::Value) -> Result<IndexInfo, Error> {
+
+    let design_doc = index.find("ddoc").and_then(|x| x.as_str()).map(|x| String::from(x));
+
+    let name = match index.find("name").and_then(|x| x.as_str()) {
+        Some(x) => String::from(x),
+        None => return Err(Error::UnexpectedResponse("The index has no name")),
+    };
+
+    let kind = match index.find("type").and_then(|x| x.as_str()) {
+        Some(x) => String::from(x),
+        None => return Err(Error::UnexpectedResponse("The index has no type")),
+    };
+
+    // Each field is an object mapping the field name to its sort direction—
+    // e.g., `{"type": "asc"}`.
+    let fields = match index.find_path(&["def", "fields"]).and_then(|x| x.as_array()) {
+        Some(fields) => {
+            try!(fields.iter()
+                .map(|x| match x.as_object().and_then(|x| x.keys().next()) {
+                    Some(x) => Ok(x.clone()),
+                    None => Err(Error::UnexpectedResponse("The index has an invalid field")),
+                })
+                .collect::<Result<Vec<_>, _>>())
+        }
+        None => return Err(Error::UnexpectedResponse("The index has no fields")),
+    };
+
+    let partial_filter_selector = index.find_path(&["def", "partial_filter_selector"]).map(|x| x.clone());
+
+    Ok(IndexInfo::new(design_doc, name, kind, fields, partial_filter_selector))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {Error, IndexInfo, IntoDatabasePath, serde_json};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_index"]).with_accept_json();
+        let got = ReadIndexes::new(&transport, "/foo").make_request(&"/foo".into_database_path().unwrap());
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"total_rows": 2, "indexes": [
+                {"ddoc": null, "name": "_all_docs", "type": "special", "def": {"fields": [{"_id": "asc"}]}},
+                {"ddoc": "_design/by-type", "name": "type-index", "type": "json",
+                 "def": {"fields": [{"type": "asc"}, {"created_at": "asc"}],
+                         "partial_filter_selector": {"archived": false}}}]}"#)
+            .unwrap();
+        let selector = serde_json::builder::ObjectBuilder::new().insert("archived", false).build();
+        let expected = vec![IndexInfo::new(None,
+                                           String::from("_all_docs"),
+                                           String::from("special"),
+                                           vec![String::from("_id")],
+                                           None),
+                            IndexInfo::new(Some(String::from("_design/by-type")),
+                                           String::from("type-index"),
+                                           String::from("json"),
+                                           vec![String::from("type"), String::from("created_at")],
+                                           Some(selector))];
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+        assert!(got[0].is_built_in());
+        assert!(!got[1].is_built_in());
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Database does not exist."}"#)
+            .unwrap();
+        match super::handle_response(response) {
+            Err(Error::NotFound(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
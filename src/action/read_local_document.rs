@@ -0,0 +1,130 @@
+//! Defines an action for reading a local document from the CouchDB server.
+
+use {DocumentId, DocumentPath, Error, IntoDocumentPath, LocalDocument, serde_json, std};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads a local document from the CouchDB server and returns the result.
+///
+/// Chill reads the local document by sending an HTTP request to `GET` from
+/// the document's path—e.g., `/db/_local/docid`. Local documents have
+/// integer-style revisions, such as `0-1`, so Chill returns a `LocalDocument`
+/// instead of a `Document`.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when reading a local
+/// document.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::InvalidOptions</code></td>
+///  <td>The document path does not specify a local document.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database or local document does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read the local document.</td>
+/// </tr>
+/// </table>
+///
+pub struct ReadLocalDocument<'a, T: Transport + 'a, P: IntoDocumentPath> {
+    transport: &'a T,
+    doc_path: Option<P>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDocumentPath> ReadLocalDocument<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, doc_path: P) -> Self {
+        ReadLocalDocument {
+            transport: transport,
+            doc_path: Some(doc_path),
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<LocalDocument, Error> {
+        let (request, doc_path) = try!(self.make_request());
+        self.transport.send(request,
+                            JsonResponseDecoder::new(move |response| handle_response(response, doc_path)))
+    }
+
+    fn make_request(&mut self) -> Result<(Request, DocumentPath), Error> {
+        let doc_path = try!(std::mem::replace(&mut self.doc_path, None).unwrap().into_document_path());
+        match doc_path.document_id() {
+            &DocumentId::Local(..) => (),
+            _ => return Err(Error::InvalidOptions("The document path does not specify a local document")),
+        }
+        let request = self.transport.get(doc_path.iter()).with_accept_json();
+        Ok((request, doc_path))
+    }
+}
+
+fn handle_response(response: JsonResponse, doc_path: DocumentPath) -> Result<LocalDocument, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let content: serde_json::Value = try!(response.decode_content());
+            LocalDocument::new_from_json(doc_path, content)
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {Error, IntoDocumentPath, LocalRevision};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_local", "bar"]).with_accept_json();
+        let (got, doc_path) = ReadLocalDocument::new(&transport, "/foo/_local/bar").make_request().unwrap();
+        assert_eq!(expected, got);
+        assert_eq!("/foo/_local/bar".into_document_path().unwrap(), doc_path);
+    }
+
+    #[test]
+    fn make_request_nok_normal_document() {
+        let transport = MockTransport::new();
+        match ReadLocalDocument::new(&transport, "/foo/bar").make_request() {
+            Err(Error::InvalidOptions(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"_id":"_local/bar","_rev":"0-1","field":42}"#)
+            .unwrap();
+
+        let got = super::handle_response(response, "/foo/_local/bar".into_document_path().unwrap()).unwrap();
+        assert_eq!(&LocalRevision::parse("0-1").unwrap(), got.revision());
+        assert_eq!(&"/foo/_local/bar".into_document_path().unwrap(), got.path());
+
+        let expected = serde_json::builder::ObjectBuilder::new().insert("field", 42).build();
+        assert_eq!(expected, got.get_content::<serde_json::Value>().unwrap());
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"missing"}"#)
+            .unwrap();
+
+        match super::handle_response(response, "/foo/_local/bar".into_document_path().unwrap()) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" &&
+                                                        error_response.reason() == "missing" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Defines an action for listing the local documents in a database.
+
+use {DatabaseName, DatabasePath, DocumentId, DocumentPath, Error, IntoDatabasePath, LocalDocument, LocalDocumentRow,
+     LocalRevision, serde_json, std};
+use action::query_keys::*;
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Lists the local documents in a database and returns the result.
+///
+/// Chill lists the local documents by sending an HTTP request to `GET
+/// /db/_local_docs`, which CouchDB v2.2 and later support. Local documents
+/// include replication checkpoints, so listing them helps with debugging
+/// replication.
+///
+/// By default, the result contains each local document's path and revision but
+/// not its content—see `with_documents`.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when listing local
+/// documents.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::ServerResponse</code></td>
+///  <td>The server does not support the <code>/db/_local_docs</code>
+///  endpoint.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read the database.</td>
+/// </tr>
+/// </table>
+///
+pub struct ReadLocalDocuments<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    include_docs: bool,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> ReadLocalDocuments<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P) -> Self {
+        ReadLocalDocuments {
+            transport: transport,
+            db_path: Some(db_path),
+            include_docs: false,
+        }
+    }
+
+    /// Modifies the action to retrieve (or not retrieve) each local document's
+    /// content.
+    ///
+    /// The `with_documents` method abstracts the `include_docs` query
+    /// parameter. If enabled, the content is available via the
+    /// `LocalDocumentRow::document` method.
+    ///
+    pub fn with_documents(mut self, include_docs: bool) -> Self {
+        self.include_docs = include_docs;
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Vec<LocalDocumentRow>, Error> {
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+        let db_name = db_path.database_name().clone();
+        let include_docs = self.include_docs;
+        self.transport.send(self.make_request(&db_path),
+                            JsonResponseDecoder::new(move |response| handle_response(response, db_name, include_docs)))
+    }
+
+    fn make_request(&self, db_path: &DatabasePath) -> Request {
+        let request = self.transport.get(db_path.iter().chain(std::iter::once("_local_docs"))).with_accept_json();
+        if self.include_docs {
+            request.with_query(IncludeDocsQueryKey, &true)
+        } else {
+            request
+        }
+    }
+}
+
+fn handle_response(response: JsonResponse,
+                   db_name: DatabaseName,
+                   include_docs: bool)
+                   -> Result<Vec<LocalDocumentRow>, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let mut body: serde_json::Value = try!(response.decode_content());
+            let rows = match body.as_object_mut().and_then(|x| x.remove("rows")) {
+                Some(serde_json::Value::Array(rows)) => rows,
+                _ => return Err(Error::UnexpectedResponse("The response has no rows")),
+            };
+            rows.into_iter().map(|row| decode_row(row, &db_name, include_docs)).collect()
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        // Servers older than CouchDB v2.2 reject `_local_docs` as a bad
+        // document id.
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+fn decode_row(mut row: serde_json::Value,
+              db_name: &DatabaseName,
+              include_docs: bool)
+              -> Result<LocalDocumentRow, Error> {
+
+    let doc_id = match row.find("id").and_then(|x| x.as_str()) {
+        Some(x) if x.starts_with("_local/") => DocumentId::from(x),
+        _ => return Err(Error::UnexpectedResponse("The row's id is not a local document id")),
+    };
+
+    let revision = match row.find_path(&["value", "rev"]).and_then(|x| x.as_str()) {
+        Some(x) => try!(LocalRevision::parse(x)),
+        None => return Err(Error::UnexpectedResponse("The row has no revision")),
+    };
+
+    let doc_path = DocumentPath::from((db_name.clone(), doc_id));
+
+    let doc = if include_docs {
+        row.as_object_mut().and_then(|x| x.remove("doc"))
+    } else {
+        None
+    };
+
+    let document = match doc {
+        None => None,
+        Some(doc) => Some(try!(LocalDocument::new_from_json(doc_path.clone(), doc))),
+    };
+
+    Ok(LocalDocumentRow::new(doc_path, revision, document))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {DatabaseName, DocumentPath, Error, IntoDatabasePath, IntoDocumentPath, LocalDocument, LocalDocumentRow,
+         LocalRevision, serde_json};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_local_docs"]).with_accept_json();
+        let action = ReadLocalDocuments::new(&transport, "/foo");
+        let got = action.make_request(&"/foo".into_database_path().unwrap());
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_documents() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_local_docs"])
+            .with_accept_json()
+            .with_query_literal("include_docs", "true");
+        let action = ReadLocalDocuments::new(&transport, "/foo").with_documents(true);
+        let got = action.make_request(&"/foo".into_database_path().unwrap());
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok_default() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"total_rows": null, "offset": null, "rows": [
+                {"id": "_local/bar", "key": "_local/bar", "value": {"rev": "0-3"}}]}"#)
+            .unwrap();
+
+        let expected = vec![LocalDocumentRow::new("/foo/_local/bar".into_document_path().unwrap(),
+                                                  LocalRevision::parse("0-3").unwrap(),
+                                                  None)];
+
+        let got = super::handle_response(response, DatabaseName::from("foo"), false).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok_with_documents() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"total_rows": null, "offset": null, "rows": [
+                {"id": "_local/bar", "key": "_local/bar", "value": {"rev": "0-3"},
+                 "doc": {"_id": "_local/bar", "_rev": "0-3", "field": 42}}]}"#)
+            .unwrap();
+
+        let doc_path: DocumentPath = "/foo/_local/bar".into_document_path().unwrap();
+        let doc = serde_json::builder::ObjectBuilder::new()
+            .insert("_id", "_local/bar")
+            .insert("_rev", "0-3")
+            .insert("field", 42)
+            .build();
+        let expected = vec![LocalDocumentRow::new(doc_path.clone(),
+                                                  LocalRevision::parse("0-3").unwrap(),
+                                                  Some(LocalDocument::new_from_json(doc_path, doc).unwrap()))];
+
+        let got = super::handle_response(response, DatabaseName::from("foo"), true).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_nok_not_local() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"total_rows": 1, "offset": 0, "rows": [
+                {"id": "bar", "key": "bar", "value": {"rev": "1-1234567890abcdef1234567890abcdef"}}]}"#)
+            .unwrap();
+
+        match super::handle_response(response, DatabaseName::from("foo"), false) {
+            Err(Error::UnexpectedResponse(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_nok_unsupported() {
+
+        let response = JsonResponseBuilder::new(StatusCode::BadRequest)
+            .with_json_content_raw(r#"{"error":"bad_request",
+                                       "reason":"Only reserved document ids may start with underscore."}"#)
+            .unwrap();
+
+        match super::handle_response(response, DatabaseName::from("foo"), false) {
+            Err(Error::ServerResponse { status_code: StatusCode::BadRequest, .. }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! Defines an action for reading the cluster membership of the CouchDB server.
+
+use {Error, Membership};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads the cluster membership of the CouchDB server and returns the result.
+///
+/// Chill reads the membership by sending an HTTP request to `GET
+/// /_membership`. If the server is not clustered—i.e., the server does not
+/// support the `/_membership` endpoint—then the result is a membership
+/// comprising a single node.
+///
+pub struct ReadMembership<'a, T: Transport + 'a> {
+    transport: &'a T,
+}
+
+impl<'a, T: Transport + 'a> ReadMembership<'a, T> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T) -> Self {
+        ReadMembership { transport: transport }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Membership, Error> {
+        self.transport.send(self.make_request(), JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&mut self) -> Request {
+        self.transport.get(vec!["_membership"]).with_accept_json()
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<Membership, Error> {
+    match response.status_code() {
+        StatusCode::Ok => Ok(try!(response.decode_content())),
+        // CouchDB 1.x treats the path as an illegal database name (400) or a
+        // missing database (404).
+        StatusCode::BadRequest | StatusCode::NotFound => Ok(Membership::new_single_node()),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {Error, Membership};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["_membership"]).with_accept_json();
+        let got = ReadMembership::new(&transport).make_request();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"all_nodes":["node1@127.0.0.1"],"cluster_nodes":["node1@127.0.0.1"]}"#)
+            .unwrap();
+
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(&vec![String::from("node1@127.0.0.1")], got.all_nodes());
+        assert_eq!(&vec![String::from("node1@127.0.0.1")], got.cluster_nodes());
+    }
+
+    #[test]
+    fn handle_response_ok_not_clustered() {
+
+        let response = JsonResponseBuilder::new(StatusCode::BadRequest)
+            .with_json_content_raw(r#"{"error":"illegal_database_name","reason":"Name: '_membership'."}"#)
+            .unwrap();
+
+        let expected = Membership::new_single_node();
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(r#"{"error":"unauthorized","reason":"Authentication required."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(ref error_response)) if error_response.error() == "unauthorized" &&
+                                                            error_response.reason() == "Authentication required." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
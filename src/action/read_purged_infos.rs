@@ -0,0 +1,210 @@
+//! Defines an action for reading a database's purge history.
+
+use {DatabasePath, DocumentId, Error, ErrorResponse, IntoDatabasePath, PurgedInfo, Revision, Seq, serde_json, std};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads the purge history of a database.
+///
+/// Chill reads the purge history by sending an HTTP request to `GET
+/// /db/_purged_infos`, which CouchDB v3.x supports. The result contains the
+/// database's current purge sequence and one `PurgedInfo` per purge request,
+/// which an application that syncs purges between clusters may replay on the
+/// target database.
+///
+/// The purge sequence is the checkpoint for syncing purges: if it equals the
+/// purge sequence the application saw last time, then nothing was purged
+/// since. The records themselves carry no purge sequence and the server
+/// ignores the `since` query parameter, so the records are always the whole
+/// purge history the server keeps.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when reading the purge
+/// history.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::ServerResponse</code></td>
+///  <td>The server does not support the <code>_purged_infos</code> endpoint
+///  and mistakes it for a document id, in which case the error response's
+///  error is <q>not_supported</q>.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read the purge history.</td>
+/// </tr>
+/// </table>
+///
+pub struct ReadPurgedInfos<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> ReadPurgedInfos<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P) -> Self {
+        ReadPurgedInfos {
+            transport: transport,
+            db_path: Some(db_path),
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<(Seq, Vec<PurgedInfo>), Error> {
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+        self.transport.send(self.make_request(&db_path), JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&self, db_path: &DatabasePath) -> Request {
+        self.transport.get(db_path.iter().chain(std::iter::once("_purged_infos"))).with_accept_json()
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<(Seq, Vec<PurgedInfo>), Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let mut body: serde_json::Value = try!(response.decode_content());
+            let purge_seq = match body.as_object_mut().and_then(|x| x.remove("purge_seq")) {
+                Some(x) => try!(serde_json::from_value::<Seq>(x).map_err(|e| Error::JsonDecode { cause: Box::new(e) })),
+                None => return Err(Error::UnexpectedResponse("The response has no purge sequence")),
+            };
+            let infos = match body.as_object_mut().and_then(|x| x.remove("purged_infos")) {
+                Some(serde_json::Value::Array(infos)) => infos,
+                _ => return Err(Error::UnexpectedResponse("The response has no purged infos")),
+            };
+            let infos = try!(infos.into_iter().map(decode_info).collect::<Result<Vec<_>, Error>>());
+            Ok((purge_seq, infos))
+        }
+        StatusCode::BadRequest => {
+            let error_response: ErrorResponse = try!(response.decode_content());
+            // An older server mistakes the endpoint for a document id.
+            if error_response.reason().contains("document ids may start with underscore") {
+                return Err(Error::ServerResponse {
+                    status_code: StatusCode::BadRequest,
+                    error_response: Some(ErrorResponse::new("not_supported",
+                                                            "The server does not support the _purged_infos \
+                                                             endpoint.")),
+                    request_context: None,
+                });
+            }
+            Err(Error::server_response(&response))
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+fn decode_info(info: serde_json::Value) -> Result<PurgedInfo, Error> {
+
+    let doc_id = match info.find("id").and_then(|x| x.as_str()) {
+        Some(x) => DocumentId::from(x),
+        None => return Err(Error::UnexpectedResponse("The purged info has no document id")),
+    };
+
+    let revisions = match info.find("revs").and_then(|x| x.as_array()) {
+        Some(revs) => {
+            try!(revs.iter()
+                .map(|x| match x.as_str() {
+                    Some(x) => Revision::parse(x),
+                    None => Err(Error::UnexpectedResponse("The purged info has a non-string revision")),
+                })
+                .collect::<Result<Vec<_>, _>>())
+        }
+        None => return Err(Error::UnexpectedResponse("The purged info has no revisions")),
+    };
+
+    Ok(PurgedInfo::new(doc_id, revisions))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {DocumentId, Error, IntoDatabasePath, PurgedInfo, Revision, Seq};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_purged_infos"]).with_accept_json();
+        let action = ReadPurgedInfos::new(&transport, "/foo");
+        let got = action.make_request(&"/foo".into_database_path().unwrap());
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"purge_seq": "2-g1AAAA", "purged_infos": [
+                {"id": "bar", "revs": ["1-1234567890abcdef1234567890abcdef"]},
+                {"id": "qux", "revs": ["2-abcdef1234567890abcdef1234567890"]}]}"#)
+            .unwrap();
+        let expected = (Seq::from("2-g1AAAA"),
+                        vec![PurgedInfo::new(DocumentId::from("bar"),
+                                             vec![Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap()]),
+                             PurgedInfo::new(DocumentId::from("qux"),
+                                             vec![Revision::parse("2-abcdef1234567890abcdef1234567890").unwrap()])]);
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok_numeric_purge_seq() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"purge_seq": 0, "purged_infos": []}"#)
+            .unwrap();
+        assert_eq!((Seq::from(0), Vec::new()), super::handle_response(response).unwrap());
+    }
+
+    #[test]
+    fn handle_response_nok_no_purge_seq() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"purged_infos": []}"#)
+            .unwrap();
+        match super::handle_response(response) {
+            Err(Error::UnexpectedResponse(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_nok_not_supported() {
+        let response = JsonResponseBuilder::new(StatusCode::BadRequest)
+            .with_json_content_raw(r#"{"error":"bad_request",
+                                       "reason":"Only reserved document ids may start with underscore."}"#)
+            .unwrap();
+        match super::handle_response(response) {
+            Err(Error::ServerResponse { status_code: StatusCode::BadRequest, error_response: Some(ref x), .. })
+                if x.error() == "not_supported" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_nok_bad_request() {
+        let response = JsonResponseBuilder::new(StatusCode::BadRequest)
+            .with_json_content_raw(r#"{"error":"bad_request","reason":"Invalid query parameter."}"#)
+            .unwrap();
+        match super::handle_response(response) {
+            Err(Error::ServerResponse { status_code: StatusCode::BadRequest, error_response: Some(ref x), .. })
+                if x.error() == "bad_request" && x.reason() == "Invalid query parameter." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_nok_not_found() {
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Database does not exist."}"#)
+            .unwrap();
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! Defines an action for reading the current revisions of multiple documents
+//! by id from the CouchDB server.
+
+use {DatabasePath, DocumentId, Error, ErrorResponse, IntoDatabasePath, Revision, serde_json, std};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads the current revisions of multiple documents by id from the CouchDB
+/// server and returns the result.
+///
+/// Chill reads the revisions by sending an HTTP request to `POST
+/// /db/_all_docs` with the document ids as the `keys` field of the request
+/// body. The server sends no document content, so reading revisions is much
+/// cheaper than reading the documents—e.g., as a preflight check before
+/// updating many documents.
+///
+/// The result maps each document id to the document's current revision, or to
+/// `None` if the document does not exist or is deleted.
+///
+pub struct ReadRevisions<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    doc_ids: Vec<DocumentId>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> ReadRevisions<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P, doc_ids: Vec<DocumentId>) -> Self {
+        ReadRevisions {
+            transport: transport,
+            db_path: Some(db_path),
+            doc_ids: doc_ids,
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<std::collections::HashMap<DocumentId, Option<Revision>>, Error> {
+
+        if self.doc_ids.is_empty() {
+            return Ok(std::collections::HashMap::new());
+        }
+
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+        let request = try!(make_request(self.transport, &db_path, &self.doc_ids));
+        let revisions = try!(self.transport.send(request, JsonResponseDecoder::new(handle_response)));
+
+        if revisions.len() != self.doc_ids.len() {
+            return Err(Error::UnexpectedResponse("The number of rows does not match the number of keys"));
+        }
+
+        let doc_ids = std::mem::replace(&mut self.doc_ids, Vec::new());
+        Ok(doc_ids.into_iter().zip(revisions.into_iter().map(|x| x.ok())).collect())
+    }
+}
+
+// The request and response handling are shared with the `DeleteDocuments`
+// action, which reads the revisions before deleting the documents.
+
+#[doc(hidden)]
+pub fn make_request<T>(transport: &T, db_path: &DatabasePath, doc_ids: &[DocumentId]) -> Result<Request, Error>
+    where T: Transport
+{
+
+    let body = serde_json::builder::ObjectBuilder::new()
+        .insert("keys", doc_ids)
+        .build();
+
+    transport.post(db_path.iter().chain(std::iter::once("_all_docs")))
+        .with_accept_json()
+        .with_json_content(&body)
+}
+
+// Returns each document's current revision, or an error response if the
+// document does not exist or is deleted.
+#[doc(hidden)]
+pub fn handle_response(response: JsonResponse) -> Result<Vec<Result<Revision, ErrorResponse>>, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let mut body: serde_json::Value = try!(response.decode_content());
+            let rows = match body.as_object_mut().and_then(|x| x.remove("rows")) {
+                Some(serde_json::Value::Array(rows)) => rows,
+                _ => return Err(Error::UnexpectedResponse("The response has no rows")),
+            };
+            rows.iter()
+                .map(|row| {
+                    if row.find_path(&["value", "deleted"]).and_then(|x| x.as_bool()) == Some(true) {
+                        return Ok(Err(ErrorResponse::new("not_found", "deleted")));
+                    }
+                    match row.find_path(&["value", "rev"]).and_then(|x| x.as_str()) {
+                        Some(revision) => Ok(Ok(try!(Revision::parse(revision)))),
+                        None => Ok(Err(ErrorResponse::new("not_found", "missing"))),
+                    }
+                })
+                .collect()
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {DocumentId, Error, ErrorResponse, IntoDatabasePath, Revision, serde_json, std};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_array("keys", |x| x.push("bar").push("qux"))
+            .build();
+
+        let expected = transport.post(vec!["foo", "_all_docs"]).with_accept_json().with_json_content(&body).unwrap();
+
+        let doc_ids = vec![DocumentId::from("bar"), DocumentId::from("qux")];
+        let got = super::make_request(&transport, &"/foo".into_database_path().unwrap(), &doc_ids).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"total_rows": 2, "offset": 0, "rows": [
+                {"id": "bar", "key": "bar", "value": {"rev": "1-1234567890abcdef1234567890abcdef"}},
+                {"key": "qux", "error": "not_found"},
+                {"id": "baz", "key": "baz", "value": {"rev": "2-1234567890abcdef1234567890abcdef", "deleted": true}}
+            ]}"#)
+            .unwrap();
+
+        let expected = vec![Ok(Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap()),
+                            Err(ErrorResponse::new("not_found", "missing")),
+                            Err(ErrorResponse::new("not_found", "deleted"))];
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_nok_bad_revision() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"total_rows": 1, "offset": 0, "rows": [
+                {"id": "bar", "key": "bar", "value": {"rev": "bad_revision"}}
+            ]}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::RevisionParse { .. }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Database does not exist."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(r#"{"error":"unauthorized","reason":"Authentication required."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(ref error_response)) if error_response.error() == "unauthorized" &&
+                                                            error_response.reason() == "Authentication required." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn run_maps_ids_to_revisions() {
+
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"total_rows": 1, "offset": 0, "rows": [
+                {"key": "qux", "error": "not_found"},
+                {"id": "bar", "key": "bar", "value": {"rev": "1-1234567890abcdef1234567890abcdef"}}
+            ]}"#)
+            .unwrap());
+
+        let expected = vec![(DocumentId::from("qux"), None),
+                            (DocumentId::from("bar"),
+                             Some(Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap()))]
+            .into_iter()
+            .collect::<std::collections::HashMap<_, _>>();
+
+        let got = ReadRevisions::new(&transport, "/foo", vec![DocumentId::from("qux"), DocumentId::from("bar")])
+            .run()
+            .unwrap();
+        assert_eq!(expected, got);
+    }
+}
@@ -0,0 +1,107 @@
+//! Defines an action for reading a database's revision limit.
+
+use {DatabasePath, Error, IntoDatabasePath, std};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads the revision limit of a database—i.e., how many revisions of each
+/// document the database tracks.
+///
+/// Chill reads the revision limit by sending an HTTP request to `GET
+/// /db/_revs_limit`. Unlike most CouchDB endpoints, the response body is a
+/// bare JSON integer—e.g., `1000`—not a JSON object.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when reading the revision
+/// limit.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read the revision limit.</td>
+/// </tr>
+/// </table>
+///
+pub struct ReadRevsLimit<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> ReadRevsLimit<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P) -> Self {
+        ReadRevsLimit {
+            transport: transport,
+            db_path: Some(db_path),
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<u32, Error> {
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+        self.transport.send(self.make_request(&db_path), JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&self, db_path: &DatabasePath) -> Request {
+        self.transport.get(db_path.iter().chain(std::iter::once("_revs_limit"))).with_accept_json()
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<u32, Error> {
+    match response.status_code() {
+        StatusCode::Ok => response.decode_content(),
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {Error, IntoDatabasePath};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_revs_limit"]).with_accept_json();
+        let got = ReadRevsLimit::new(&transport, "/foo").make_request(&"/foo".into_database_path().unwrap());
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok_bare_integer() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw("1000")
+            .unwrap();
+        assert_eq!(1000, super::handle_response(response).unwrap());
+    }
+
+    #[test]
+    fn handle_response_nok_not_integer() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"ok":true}"#)
+            .unwrap();
+        match super::handle_response(response) {
+            Err(Error::JsonDecode { .. }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Database does not exist."}"#)
+            .unwrap();
+        match super::handle_response(response) {
+            Err(Error::NotFound(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Defines an action for reading the replication scheduler's status of a
+//! replication document.
+
+use {Error, SchedulerDoc};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads the replication scheduler's status of a replication document.
+///
+/// Chill reads the status by sending an HTTP request to `GET
+/// /_scheduler/docs/{replicator_db}/{doc_id}`.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when reading the status.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The replicator database or the replication document does not exist,
+///  or the server does not support the replication scheduler.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read the status.</td>
+/// </tr>
+/// </table>
+///
+pub struct ReadSchedulerDoc<'a, T: Transport + 'a> {
+    transport: &'a T,
+    replicator_db: &'a str,
+    doc_id: &'a str,
+}
+
+impl<'a, T: Transport + 'a> ReadSchedulerDoc<'a, T> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, replicator_db: &'a str, doc_id: &'a str) -> Self {
+        ReadSchedulerDoc {
+            transport: transport,
+            replicator_db: replicator_db,
+            doc_id: doc_id,
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<SchedulerDoc, Error> {
+        self.transport.send(self.make_request(), JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&mut self) -> Request {
+        self.transport
+            .get(vec!["_scheduler", "docs", self.replicator_db, self.doc_id])
+            .with_accept_json()
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<SchedulerDoc, Error> {
+    match response.status_code() {
+        StatusCode::Ok => Ok(try!(response.decode_content())),
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use Error;
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["_scheduler", "docs", "_replicator", "my_rep"]).with_accept_json();
+        let got = ReadSchedulerDoc::new(&transport, "_replicator", "my_rep").make_request();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"database":"_replicator","doc_id":"my_rep","state":"running",
+                                      "info":{"changes_pending":7,"docs_written":42},"error_count":0,
+                                      "last_updated":"2017-04-29T05:01:37Z"}"#)
+            .unwrap();
+
+        let got = super::handle_response(response).unwrap();
+        assert_eq!("running", got.state());
+        assert_eq!(Some(7), got.changes_pending());
+        assert_eq!(Some(42), got.docs_written());
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"unknown"}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" &&
+                                                        error_response.reason() == "unknown" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(r#"{"error":"unauthorized","reason":"You are not a server admin."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(ref error_response)) if error_response.error() == "unauthorized" &&
+                                                            error_response.reason() ==
+                                                            "You are not a server admin." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
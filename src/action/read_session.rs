@@ -0,0 +1,162 @@
+//! Defines an action for reading the client's session with the CouchDB
+//! server.
+
+use {Error, SessionInfo, serde_json};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads the client's session with the CouchDB server and returns the result.
+///
+/// Chill reads the session by sending an HTTP request to `GET /_session`. The
+/// result tells who the server thinks the client is, which lets the
+/// application verify its credentials—e.g., after configuring session
+/// authentication via `Client::with_session_auth`.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when reading the session.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The server rejected the client's credentials.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::UnexpectedResponse</code></td>
+///  <td>The response has no user context.</td>
+/// </tr>
+/// </table>
+///
+pub struct ReadSession<'a, T: Transport + 'a> {
+    transport: &'a T,
+}
+
+impl<'a, T: Transport + 'a> ReadSession<'a, T> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T) -> Self {
+        ReadSession { transport: transport }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<SessionInfo, Error> {
+        self.transport.send(self.make_request(), JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&mut self) -> Request {
+        self.transport.get(vec!["_session"]).with_accept_json()
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<SessionInfo, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let body: serde_json::Value = try!(response.decode_content());
+            decode_session_info(&body)
+        }
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+fn decode_session_info(body: &serde_json::Value) -> Result<SessionInfo, Error> {
+
+    let user_ctx = match body.find("userCtx") {
+        Some(x) if x.is_object() => x,
+        _ => return Err(Error::UnexpectedResponse("The session response has no user context")),
+    };
+
+    let ok = body.find("ok").and_then(|x| x.as_bool()).unwrap_or(false);
+
+    // An anonymous session's name is null.
+    let name = user_ctx.find("name").and_then(|x| x.as_str()).map(|x| String::from(x));
+
+    let roles = match user_ctx.find("roles") {
+        None => Vec::new(),
+        Some(&serde_json::Value::Array(ref roles)) => {
+            let mut names = Vec::new();
+            for role in roles {
+                match role.as_str() {
+                    Some(x) => names.push(String::from(x)),
+                    None => return Err(Error::UnexpectedResponse("The session's roles are not all strings")),
+                }
+            }
+            names
+        }
+        Some(_) => return Err(Error::UnexpectedResponse("The session's roles are not an array")),
+    };
+
+    Ok(SessionInfo::new(ok, name, roles))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {Error, SessionInfo};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["_session"]).with_accept_json();
+        let got = ReadSession::new(&transport).make_request();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok_authenticated() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"ok":true,"userCtx":{"name":"alice","roles":["_admin","editor"]},
+                                      "info":{"authenticated":"cookie","authentication_handlers":["cookie"]}}"#)
+            .unwrap();
+
+        let expected = SessionInfo::new(true,
+                                        Some(String::from("alice")),
+                                        vec![String::from("_admin"), String::from("editor")]);
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+        assert!(got.is_authenticated());
+        assert_eq!(Some("alice"), got.name());
+    }
+
+    #[test]
+    fn handle_response_ok_anonymous() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"ok":true,"userCtx":{"name":null,"roles":[]},
+                                      "info":{"authentication_handlers":["cookie","default"]}}"#)
+            .unwrap();
+
+        let got = super::handle_response(response).unwrap();
+        assert!(got.is_ok());
+        assert!(!got.is_authenticated());
+        assert_eq!(None, got.name());
+        assert!(got.roles().is_empty());
+    }
+
+    #[test]
+    fn handle_response_nok_no_user_context() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"ok":true}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::UnexpectedResponse(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(r#"{"error":"unauthorized","reason":"Name or password is incorrect."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
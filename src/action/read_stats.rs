@@ -0,0 +1,83 @@
+//! Defines an action for reading statistics about a CouchDB server node.
+
+use {Error, Stats};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads statistics about a CouchDB server node and returns the result.
+///
+/// Chill reads the statistics by sending an HTTP request to `GET
+/// /_node/{node}/_stats`. The node may be `_local` to specify the node that
+/// handles the request.
+///
+pub struct ReadStats<'a, T: Transport + 'a> {
+    transport: &'a T,
+    node: &'a str,
+}
+
+impl<'a, T: Transport + 'a> ReadStats<'a, T> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, node: &'a str) -> Self {
+        ReadStats {
+            transport: transport,
+            node: node,
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Stats, Error> {
+        self.transport.send(self.make_request(), JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&mut self) -> Request {
+        self.transport.get(vec!["_node", self.node, "_stats"]).with_accept_json()
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<Stats, Error> {
+    match response.status_code() {
+        StatusCode::Ok => Ok(try!(response.decode_content())),
+        StatusCode::Forbidden => Err(Error::forbidden(&response)),
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use Error;
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["_node", "_local", "_stats"]).with_accept_json();
+        let got = ReadStats::new(&transport, "_local").make_request();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"couchdb": {"open_databases": {"value": 17, "type": "counter"}}}"#)
+            .unwrap();
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(Some(17), got.open_databases());
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(r#"{"error":"unauthorized","reason":"Authentication required."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(ref error_response)) if error_response.error() == "unauthorized" &&
+                                                            error_response.reason() == "Authentication required." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Defines an action for updating a configuration value on the CouchDB server.
+
+use Error;
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Updates a configuration value on the CouchDB server.
+///
+/// Chill updates the configuration value by sending an HTTP request to `PUT
+/// /_node/{node}/_config/{section}/{key}` with the JSON-encoded value as the
+/// request body. The node may be `_local` to specify the node that handles the
+/// request.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when updating a
+/// configuration value.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::Forbidden</code></td>
+///  <td>The client is not a server admin.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to update the configuration value.</td>
+/// </tr>
+/// </table>
+///
+pub struct UpdateConfig<'a, T: Transport + 'a> {
+    transport: &'a T,
+    node: &'a str,
+    section: &'a str,
+    key: &'a str,
+    value: &'a str,
+}
+
+impl<'a, T: Transport + 'a> UpdateConfig<'a, T> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, node: &'a str, section: &'a str, key: &'a str, value: &'a str) -> Self {
+        UpdateConfig {
+            transport: transport,
+            node: node,
+            section: section,
+            key: key,
+            value: value,
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<(), Error> {
+        self.transport.send(try!(self.make_request()),
+                            JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+        self.transport
+            .put(vec!["_node", self.node, "_config", self.section, self.key])
+            .with_accept_json()
+            .with_json_content(&self.value)
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<(), Error> {
+    match response.status_code() {
+        StatusCode::Ok => Ok(()),
+        StatusCode::Forbidden => Err(Error::forbidden(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use Error;
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let expected = transport.put(vec!["_node", "_local", "_config", "httpd", "max_http_request_size"])
+            .with_accept_json()
+            .with_json_content(&"67108864")
+            .unwrap();
+
+        let got = {
+            let mut action = UpdateConfig::new(&transport, "_local", "httpd", "max_http_request_size", "67108864");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#""4294967296""#)
+            .unwrap();
+        super::handle_response(response).unwrap();
+    }
+
+    #[test]
+    fn handle_response_forbidden() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Forbidden)
+            .with_json_content_raw(r#"{"error":"forbidden","reason":"You are not a server admin."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Forbidden(ref error_response)) if error_response.error() == "forbidden" &&
+                                                         error_response.reason() == "You are not a server admin." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(r#"{"error":"unauthorized","reason":"Authentication required."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(ref error_response)) if error_response.error() == "unauthorized" &&
+                                                            error_response.reason() == "Authentication required." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
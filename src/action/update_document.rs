@@ -1,4 +1,4 @@
-use {Document, Error, Revision};
+use {Document, Error, Revision, WriteOutcome};
 use action::query_keys::*;
 use document::WriteDocumentResponse;
 use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
@@ -21,7 +21,14 @@ impl<'a, T> UpdateDocument<'a, T>
         }
     }
 
-    pub fn run(mut self) -> Result<Revision, Error> {
+    pub fn run(self) -> Result<Revision, Error> {
+        let (rev, _) = try!(self.run_with_outcome());
+        Ok(rev)
+    }
+
+    /// Executes the action, waits for the result, and also returns how
+    /// durably the server stored the write—see `WriteOutcome`.
+    pub fn run_with_outcome(mut self) -> Result<(Revision, WriteOutcome), Error> {
         self.transport.send(try!(self.make_request()),
                             JsonResponseDecoder::new(handle_response))
     }
@@ -35,11 +42,15 @@ impl<'a, T> UpdateDocument<'a, T>
     }
 }
 
-fn handle_response(response: JsonResponse) -> Result<Revision, Error> {
+fn handle_response(response: JsonResponse) -> Result<(Revision, WriteOutcome), Error> {
     match response.status_code() {
         StatusCode::Created => {
             let body: WriteDocumentResponse = try!(response.decode_content());
-            Ok(body.revision)
+            Ok((body.revision, WriteOutcome::Committed))
+        }
+        StatusCode::Accepted => {
+            let body: WriteDocumentResponse = try!(response.decode_content());
+            Ok((body.revision, WriteOutcome::AcceptedNotDurable))
         }
         StatusCode::Conflict => Err(Error::document_conflict(&response)),
         StatusCode::NotFound => Err(Error::not_found(&response)),
@@ -51,7 +62,7 @@ fn handle_response(response: JsonResponse) -> Result<Revision, Error> {
 #[cfg(test)]
 mod tests {
 
-    use {Error, Revision, serde_json};
+    use {Error, Revision, WriteOutcome, serde_json};
     use super::*;
     use document::DocumentBuilder;
     use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
@@ -88,6 +99,35 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn make_request_with_pegged_revision() {
+
+        let transport = MockTransport::new();
+
+        let doc = DocumentBuilder::new("/foo/bar",
+                                       Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap())
+            .build_content(|x| x.insert("field_1", 42))
+            .unwrap()
+            .with_revision(Revision::parse("2-abcdef1234567890abcdef1234567890").unwrap());
+
+        let request_content = serde_json::builder::ObjectBuilder::new()
+            .insert("field_1", 42)
+            .build();
+
+        let expected = transport.put(vec!["foo", "bar"])
+            .with_accept_json()
+            .with_query_literal("rev", "2-abcdef1234567890abcdef1234567890")
+            .with_json_content(&request_content)
+            .unwrap();
+
+        let got = {
+            let mut action = UpdateDocument::new(&transport, &doc);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
     #[test]
     fn handle_response_created() {
 
@@ -95,7 +135,20 @@ mod tests {
             .with_json_content_raw(r#"{"ok":true,"id":"bar","rev":"1-1234567890abcdef1234567890abcdef"}"#)
             .unwrap();
 
-        let expected = Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap();
+        let expected = (Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(), WriteOutcome::Committed);
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_accepted() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Accepted)
+            .with_json_content_raw(r#"{"ok":true,"id":"bar","rev":"1-1234567890abcdef1234567890abcdef"}"#)
+            .unwrap();
+
+        let expected = (Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
+                        WriteOutcome::AcceptedNotDurable);
         let got = super::handle_response(response).unwrap();
         assert_eq!(expected, got);
     }
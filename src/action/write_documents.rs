@@ -0,0 +1,433 @@
+//! Defines an action for writing multiple documents to the CouchDB server in
+//! one request.
+
+use {BulkDocsResult, BulkWriteResponse, DatabasePath, DocumentId, Error, ErrorResponse, IntoDatabasePath, serde_json,
+     std};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Writes multiple documents to the CouchDB server in one request and returns
+/// the result.
+///
+/// Chill writes the documents by sending an HTTP request to `POST
+/// /db/_bulk_docs`. Each document is a JSON object that may contain the
+/// special `_id`, `_rev`, and `_deleted` fields. The result contains one entry
+/// per document, in the same order as the documents. The action succeeds even
+/// if some documents fail to be written—check each `BulkDocsResult`, or use
+/// `BulkWriteResponse::errors` to iterate through only the failures.
+///
+/// With new edits disabled, the CouchDB server omits the results of documents
+/// it writes, so the result contains only the failures.
+///
+/// # Chunking
+///
+/// A request that is too large—e.g., exceeding the server's
+/// `max_http_request_size`—fails. To avoid this, the action may split the
+/// documents into batches bounded by a document count, via
+/// `with_max_batch_documents`, and by an estimated size of the request body,
+/// via `with_max_batch_bytes`. The action then sends one request per batch, in
+/// order, and concatenates the results in the same order as the documents. A
+/// document larger than the byte budget is sent in a batch by itself. If a
+/// request fails, then the action stops and returns the error, and the batches
+/// before it remain written.
+///
+/// # Validation
+///
+/// CouchDB has no dry run for bulk writes. Instead, the action may check each
+/// document on the client before sending anything, via
+/// `validate_before_send`—e.g., to catch schema errors that the database's
+/// `validate_doc_update` function would reject. If any document fails, then
+/// the action sends no request and fails with `Error::Validation`.
+///
+pub struct WriteDocuments<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    docs: &'a [serde_json::Value],
+    new_edits: Option<bool>,
+    max_batch_documents: Option<usize>,
+    max_batch_bytes: Option<usize>,
+    validator: Option<Box<Fn(&serde_json::Value) -> Result<(), String> + 'a>>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> WriteDocuments<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P, docs: &'a [serde_json::Value]) -> Self {
+        WriteDocuments {
+            transport: transport,
+            db_path: Some(db_path),
+            docs: docs,
+            new_edits: None,
+            max_batch_documents: None,
+            max_batch_bytes: None,
+            validator: None,
+        }
+    }
+
+    /// Modifies the action to store each document's revision as is instead of
+    /// generating a new revision.
+    ///
+    /// The `with_new_edits` method abstracts the `new_edits` field of the
+    /// `_bulk_docs` request body. Disabling new edits is meant for replication
+    /// and for restoring documents with their existing revisions.
+    ///
+    pub fn with_new_edits(mut self, new_edits: bool) -> Self {
+        self.new_edits = Some(new_edits);
+        self
+    }
+
+    /// Modifies the action to send at most the given number of documents per
+    /// request—see the section on chunking.
+    pub fn with_max_batch_documents(mut self, max_batch_documents: usize) -> Self {
+        self.max_batch_documents = Some(max_batch_documents);
+        self
+    }
+
+    /// Modifies the action to limit each request's estimated body size to the
+    /// given number of bytes—see the section on chunking.
+    pub fn with_max_batch_bytes(mut self, max_batch_bytes: usize) -> Self {
+        self.max_batch_bytes = Some(max_batch_bytes);
+        self
+    }
+
+    /// Modifies the action to check each document with the given closure
+    /// before sending any request—see the section on validation.
+    ///
+    /// The closure returns an error message for a document that fails. The
+    /// action checks all documents, so the resulting error contains every
+    /// failure, not only the first.
+    ///
+    pub fn validate_before_send<F>(mut self, validator: F) -> Self
+        where F: Fn(&serde_json::Value) -> Result<(), String> + 'a
+    {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<BulkWriteResponse, Error> {
+
+        let db_path = try!(std::mem::replace(&mut self.db_path, None).unwrap().into_database_path());
+
+        try!(self.validate());
+
+        let mut results = Vec::with_capacity(self.docs.len());
+        for batch in try!(self.make_batches()) {
+            let request = try!(self.make_request(&db_path, batch));
+            let batch_results = try!(self.transport.send(request, JsonResponseDecoder::new(handle_response)));
+            if self.new_edits != Some(false) && batch_results.len() != batch.len() {
+                return Err(Error::UnexpectedResponse("The number of results does not match the number of \
+                                                      documents"));
+            }
+            results.extend(batch_results);
+        }
+
+        Ok(BulkWriteResponse::new(results))
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+
+        let validator = match self.validator {
+            None => return Ok(()),
+            Some(ref x) => x,
+        };
+
+        let failures = self.docs
+            .iter()
+            .filter_map(|doc| match validator(doc) {
+                Ok(()) => None,
+                Err(reason) => {
+                    let doc_id = doc.find("_id").and_then(|x| x.as_str()).map(DocumentId::from);
+                    Some(BulkDocsResult::new_error(doc_id, ErrorResponse::new("forbidden", reason)))
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Validation { failures: failures })
+        }
+    }
+
+    fn make_batches(&self) -> Result<Vec<&'a [serde_json::Value]>, Error> {
+
+        if self.max_batch_documents == Some(0) || self.max_batch_bytes == Some(0) {
+            return Err(Error::InvalidOptions("The batch limits must be nonzero"));
+        }
+
+        let max_documents = self.max_batch_documents.unwrap_or(std::usize::MAX);
+        let max_bytes = self.max_batch_bytes.unwrap_or(std::usize::MAX);
+
+        let mut batches = Vec::new();
+        let mut start = 0;
+        let mut num_bytes = 0;
+
+        for (index, doc) in self.docs.iter().enumerate() {
+            // Estimate the document's size in the request body, including the
+            // separating comma.
+            let doc_bytes = if max_bytes == std::usize::MAX {
+                0
+            } else {
+                try!(serde_json::to_vec(doc).map_err(|e| Error::JsonEncode { cause: Box::new(e) })).len() + 1
+            };
+            if index > start && (index - start >= max_documents || num_bytes + doc_bytes > max_bytes) {
+                batches.push(&self.docs[start..index]);
+                start = index;
+                num_bytes = 0;
+            }
+            num_bytes += doc_bytes;
+        }
+
+        batches.push(&self.docs[start..]);
+        Ok(batches)
+    }
+
+    fn make_request(&self, db_path: &DatabasePath, docs: &[serde_json::Value]) -> Result<Request, Error> {
+
+        let body = serde_json::builder::ObjectBuilder::new().insert("docs", docs);
+        let body = match self.new_edits {
+            None => body,
+            Some(new_edits) => body.insert("new_edits", new_edits),
+        };
+
+        self.transport
+            .post(db_path.iter().chain(std::iter::once("_bulk_docs")))
+            .with_accept_json()
+            .with_json_content(&body.build())
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<Vec<BulkDocsResult>, Error> {
+    match response.status_code() {
+        StatusCode::Created |
+        StatusCode::Accepted => Ok(try!(response.decode_content())),
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {BulkDocsResult, DocumentId, Error, ErrorResponse, IntoDatabasePath, Revision, serde_json};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+
+        let docs = vec![serde_json::builder::ObjectBuilder::new().insert("_id", "bar").build()];
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_array("docs", |x| x.push_object(|x| x.insert("_id", "bar")))
+            .build();
+
+        let expected = transport.post(vec!["foo", "_bulk_docs"]).with_accept_json().with_json_content(&body).unwrap();
+        let got = WriteDocuments::new(&transport, "/foo", &docs)
+            .make_request(&"/foo".into_database_path().unwrap(), &docs)
+            .unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_new_edits() {
+
+        let transport = MockTransport::new();
+
+        let docs = vec![serde_json::builder::ObjectBuilder::new().insert("_id", "bar").build()];
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_array("docs", |x| x.push_object(|x| x.insert("_id", "bar")))
+            .insert("new_edits", false)
+            .build();
+
+        let expected = transport.post(vec!["foo", "_bulk_docs"]).with_accept_json().with_json_content(&body).unwrap();
+        let got = WriteDocuments::new(&transport, "/foo", &docs)
+            .with_new_edits(false)
+            .make_request(&"/foo".into_database_path().unwrap(), &docs)
+            .unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_created() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Created)
+            .with_json_content_raw(r#"[{"ok": true, "id": "bar", "rev": "1-1234567890abcdef1234567890abcdef"},
+                                       {"id": "qux", "error": "conflict", "reason": "Document update conflict."}]"#)
+            .unwrap();
+
+        let expected = vec![BulkDocsResult::new_ok(DocumentId::from("bar"),
+                                                   Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap()),
+                            BulkDocsResult::new_error(Some(DocumentId::from("qux")),
+                                                      ErrorResponse::new("conflict", "Document update conflict."))];
+
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_batches_default() {
+        let transport = MockTransport::new();
+        let docs = vec![serde_json::Value::U64(1), serde_json::Value::U64(2), serde_json::Value::U64(3)];
+        let got = WriteDocuments::new(&transport, "/foo", &docs).make_batches().unwrap();
+        assert_eq!(vec![&docs[..]], got);
+    }
+
+    #[test]
+    fn make_batches_with_max_batch_documents() {
+        let transport = MockTransport::new();
+        let docs = vec![serde_json::Value::U64(1), serde_json::Value::U64(2), serde_json::Value::U64(3)];
+        let got = WriteDocuments::new(&transport, "/foo", &docs).with_max_batch_documents(2).make_batches().unwrap();
+        assert_eq!(vec![&docs[0..2], &docs[2..3]], got);
+    }
+
+    #[test]
+    fn make_batches_with_max_batch_bytes() {
+        let transport = MockTransport::new();
+        // Each document is estimated at 11 bytes: 10 digits plus a comma.
+        let docs = vec![serde_json::Value::U64(1000000000),
+                        serde_json::Value::U64(2000000000),
+                        serde_json::Value::U64(3000000000)];
+        let got = WriteDocuments::new(&transport, "/foo", &docs).with_max_batch_bytes(25).make_batches().unwrap();
+        assert_eq!(vec![&docs[0..2], &docs[2..3]], got);
+    }
+
+    #[test]
+    fn make_batches_with_oversized_document() {
+        let transport = MockTransport::new();
+        let docs = vec![serde_json::Value::U64(1), serde_json::Value::U64(2000000000), serde_json::Value::U64(3)];
+        let got = WriteDocuments::new(&transport, "/foo", &docs).with_max_batch_bytes(5).make_batches().unwrap();
+        assert_eq!(vec![&docs[0..1], &docs[1..2], &docs[2..3]], got);
+    }
+
+    #[test]
+    fn make_batches_nok_zero_limit() {
+        let transport = MockTransport::new();
+        let docs = vec![serde_json::Value::U64(1)];
+        match WriteDocuments::new(&transport, "/foo", &docs).with_max_batch_documents(0).make_batches() {
+            Err(Error::InvalidOptions(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn run_ok_with_batches() {
+
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::Created)
+                .with_json_content_raw(r#"[{"ok": true, "id": "alpha", "rev": "1-1234567890abcdef1234567890abcdef"},
+                                           {"id": "bravo", "error": "conflict",
+                                            "reason": "Document update conflict."}]"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Created)
+                .with_json_content_raw(r#"[{"ok": true, "id": "charlie",
+                                            "rev": "1-fedcba0987654321fedcba0987654321"}]"#)
+                .unwrap());
+
+        let docs = vec![serde_json::builder::ObjectBuilder::new().insert("_id", "alpha").build(),
+                        serde_json::builder::ObjectBuilder::new().insert("_id", "bravo").build(),
+                        serde_json::builder::ObjectBuilder::new().insert("_id", "charlie").build()];
+
+        let expected = vec![BulkDocsResult::new_ok(DocumentId::from("alpha"),
+                                                   Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap()),
+                            BulkDocsResult::new_error(Some(DocumentId::from("bravo")),
+                                                      ErrorResponse::new("conflict", "Document update conflict.")),
+                            BulkDocsResult::new_ok(DocumentId::from("charlie"),
+                                                   Revision::parse("1-fedcba0987654321fedcba0987654321").unwrap())];
+
+        let got = WriteDocuments::new(&transport, "/foo", &docs).with_max_batch_documents(2).run().unwrap();
+        assert_eq!(&expected, got.results());
+    }
+
+    #[test]
+    fn run_nok_result_count_mismatch() {
+
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Created)
+            .with_json_content_raw(r#"[{"ok": true, "id": "bar", "rev": "1-1234567890abcdef1234567890abcdef"}]"#)
+            .unwrap());
+
+        let docs = vec![serde_json::builder::ObjectBuilder::new().insert("_id", "bar").build(),
+                        serde_json::builder::ObjectBuilder::new().insert("_id", "qux").build()];
+
+        match WriteDocuments::new(&transport, "/foo", &docs).run() {
+            Err(Error::UnexpectedResponse(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn run_ok_validated() {
+
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Created)
+            .with_json_content_raw(r#"[{"ok": true, "id": "bar", "rev": "1-1234567890abcdef1234567890abcdef"}]"#)
+            .unwrap());
+
+        let docs = vec![serde_json::builder::ObjectBuilder::new().insert("_id", "bar").insert("type", "x").build()];
+
+        let got = WriteDocuments::new(&transport, "/foo", &docs)
+            .validate_before_send(|doc| match doc.find("type") {
+                Some(_) => Ok(()),
+                None => Err(String::from("The document has no type")),
+            })
+            .run()
+            .unwrap();
+        assert!(got.is_ok());
+    }
+
+    #[test]
+    fn run_nok_validation_failed() {
+
+        let transport = MockTransport::new();
+
+        let docs = vec![serde_json::builder::ObjectBuilder::new().insert("_id", "alpha").build(),
+                        serde_json::builder::ObjectBuilder::new().insert("_id", "bravo").insert("type", "x").build(),
+                        serde_json::builder::ObjectBuilder::new().insert("field", 42).build()];
+
+        let expected = vec![BulkDocsResult::new_error(Some(DocumentId::from("alpha")),
+                                                      ErrorResponse::new("forbidden", "The document has no type")),
+                            BulkDocsResult::new_error(None,
+                                                      ErrorResponse::new("forbidden", "The document has no type"))];
+
+        let got = WriteDocuments::new(&transport, "/foo", &docs)
+            .validate_before_send(|doc| match doc.find("type") {
+                Some(_) => Ok(()),
+                None => Err(String::from("The document has no type")),
+            })
+            .run();
+        match got {
+            Err(Error::Validation { ref failures }) if *failures == expected => (),
+            x @ _ => unexpected_result!(x),
+        }
+
+        assert!(transport.take_requests().is_empty());
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"no_db_file"}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" &&
+                                                        error_response.reason() == "no_db_file" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(r#"{"error":"unauthorized","reason":"Authentication required."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(ref error_response)) if error_response.error() == "unauthorized" &&
+                                                            error_response.reason() == "Authentication required." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
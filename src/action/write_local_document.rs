@@ -0,0 +1,191 @@
+//! Defines an action for writing a local document to the CouchDB server.
+
+use {DocumentId, Error, IntoDocumentPath, LocalRevision, serde, serde_json, std};
+use action::query_keys::*;
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Creates or updates a local document on the CouchDB server and returns the
+/// document's new revision.
+///
+/// Chill writes the local document by sending an HTTP request to `PUT` to the
+/// document's path—e.g., `/db/_local/docid`. To update an existing local
+/// document, the application must specify the document's current revision via
+/// the `with_revision` method.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when writing a local
+/// document.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::DocumentConflict</code></td>
+///  <td>The local document exists and its revision does not match the given
+///  revision, if any.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::InvalidOptions</code></td>
+///  <td>The document path does not specify a local document.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to write the local document.</td>
+/// </tr>
+/// </table>
+///
+pub struct WriteLocalDocument<'a, T, P, C>
+    where C: serde::Serialize + 'a,
+          P: IntoDocumentPath,
+          T: Transport + 'a
+{
+    transport: &'a T,
+    doc_path: Option<P>,
+    content: &'a C,
+    revision: Option<&'a LocalRevision>,
+}
+
+impl<'a, C, P, T> WriteLocalDocument<'a, T, P, C>
+    where C: serde::Serialize + 'a,
+          P: IntoDocumentPath,
+          T: Transport + 'a
+{
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, doc_path: P, content: &'a C) -> Self {
+        WriteLocalDocument {
+            transport: transport,
+            doc_path: Some(doc_path),
+            content: content,
+            revision: None,
+        }
+    }
+
+    /// Modifies the action to update the local document of the given
+    /// revision.
+    ///
+    /// The `with_revision` method abstracts the `rev` query parameter of the
+    /// HTTP request `PUT /db/_local/docid`. Without a revision, the action
+    /// creates a new local document.
+    ///
+    pub fn with_revision(mut self, revision: &'a LocalRevision) -> Self {
+        self.revision = Some(revision);
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<LocalRevision, Error> {
+        self.transport.send(try!(self.make_request()),
+                            JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+        let doc_path = try!(std::mem::replace(&mut self.doc_path, None).unwrap().into_document_path());
+        match doc_path.document_id() {
+            &DocumentId::Local(..) => (),
+            _ => return Err(Error::InvalidOptions("The document path does not specify a local document")),
+        }
+
+        let request = try!(self.transport.put(doc_path.iter()).with_accept_json().with_json_content(self.content));
+
+        let request = match self.revision {
+            None => request,
+            Some(rev) => request.with_query(RevisionQueryKey, rev),
+        };
+
+        Ok(request)
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<LocalRevision, Error> {
+    match response.status_code() {
+        StatusCode::Created => {
+            let content: serde_json::Value = try!(response.decode_content());
+            let revision = try!(content.find("rev")
+                .and_then(|x| x.as_str())
+                .ok_or(Error::UnexpectedResponse("The response has no revision")));
+            LocalRevision::parse(revision)
+        }
+        StatusCode::Conflict => Err(Error::document_conflict(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {Error, LocalRevision, serde_json};
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let content = serde_json::builder::ObjectBuilder::new()
+            .insert("field", 42)
+            .build();
+
+        let transport = MockTransport::new();
+        let expected = transport.put(vec!["foo", "_local", "bar"])
+            .with_accept_json()
+            .with_json_content(&content)
+            .unwrap();
+
+        let got = WriteLocalDocument::new(&transport, "/foo/_local/bar", &content).make_request().unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_revision() {
+
+        let content = serde_json::builder::ObjectBuilder::new()
+            .insert("field", 42)
+            .build();
+        let rev = LocalRevision::parse("0-7").unwrap();
+
+        let transport = MockTransport::new();
+        let expected = transport.put(vec!["foo", "_local", "bar"])
+            .with_accept_json()
+            .with_json_content(&content)
+            .unwrap()
+            .with_query(RevisionQueryKey, &rev);
+
+        let got = WriteLocalDocument::new(&transport, "/foo/_local/bar", &content)
+            .with_revision(&rev)
+            .make_request()
+            .unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_nok_normal_document() {
+        let content = serde_json::builder::ObjectBuilder::new().build();
+        let transport = MockTransport::new();
+        match WriteLocalDocument::new(&transport, "/foo/bar", &content).make_request() {
+            Err(Error::InvalidOptions(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_created() {
+        let response = JsonResponseBuilder::new(StatusCode::Created)
+            .with_json_content_raw(r#"{"ok":true,"id":"_local/bar","rev":"0-1"}"#)
+            .unwrap();
+        let expected = LocalRevision::parse("0-1").unwrap();
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_conflict() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Conflict)
+            .with_json_content_raw(r#"{"error":"conflict","reason":"Document update conflict."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::DocumentConflict(ref error_response)) if error_response.error() == "conflict" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use Error;
 use base64;
 use mime;
 use serde;
@@ -34,7 +35,8 @@ impl Attachment {
         match self {
             &Attachment::Saved(ref inner) => {
                 match inner.content {
-                    SavedAttachmentContent::LengthOnly(len) => len,
+                    SavedAttachmentContent::LengthOnly(len) |
+                    SavedAttachmentContent::Follows(len) => len,
                     SavedAttachmentContent::Bytes(ref bytes) => bytes.len() as u64,
                 }
             }
@@ -57,7 +59,8 @@ impl Attachment {
         match self {
             &Attachment::Saved(ref inner) => {
                 match inner.content {
-                    SavedAttachmentContent::LengthOnly(..) => None,
+                    SavedAttachmentContent::LengthOnly(..) |
+                    SavedAttachmentContent::Follows(..) => None,
                     SavedAttachmentContent::Bytes(ref bytes) => Some(bytes),
                 }
             }
@@ -90,6 +93,8 @@ impl serde::Deserialize for Attachment {
 #[derive(Clone, Debug, PartialEq)]
 enum SavedAttachmentContent {
     LengthOnly(u64),
+    // The content is in a separate part of a multipart response.
+    Follows(u64),
     Bytes(Vec<u8>),
 }
 
@@ -114,17 +119,34 @@ impl SavedAttachment {
 
     pub fn content_length(&self) -> u64 {
         match self.content {
-            SavedAttachmentContent::LengthOnly(length) => length,
+            SavedAttachmentContent::LengthOnly(length) |
+            SavedAttachmentContent::Follows(length) => length,
             SavedAttachmentContent::Bytes(ref bytes) => bytes.len() as u64,
         }
     }
 
     pub fn content_bytes(&self) -> Option<&[u8]> {
         match self.content {
-            SavedAttachmentContent::LengthOnly(..) => None,
+            SavedAttachmentContent::LengthOnly(..) |
+            SavedAttachmentContent::Follows(..) => None,
             SavedAttachmentContent::Bytes(ref bytes) => Some(&bytes),
         }
     }
+
+    // Returns whether the attachment is a `follows` stub still waiting for its
+    // content from a part of a multipart response.
+    #[doc(hidden)]
+    pub fn is_missing_content(&self) -> bool {
+        match self.content {
+            SavedAttachmentContent::Follows(..) => true,
+            _ => false,
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn set_content_bytes(&mut self, bytes: Vec<u8>) {
+        self.content = SavedAttachmentContent::Bytes(bytes);
+    }
 }
 
 impl serde::Serialize for SavedAttachment {
@@ -146,6 +168,7 @@ impl serde::Deserialize for SavedAttachment {
             Digest,
             EncodedLength,
             Encoding,
+            Follows,
             Length,
             Revpos,
             Stub,
@@ -169,6 +192,7 @@ impl serde::Deserialize for SavedAttachment {
                             "digest" => Ok(Field::Digest),
                             "encoded_length" => Ok(Field::EncodedLength),
                             "encoding" => Ok(Field::Encoding),
+                            "follows" => Ok(Field::Follows),
                             "length" => Ok(Field::Length),
                             "revpos" => Ok(Field::Revpos),
                             "stub" => Ok(Field::Stub),
@@ -194,6 +218,7 @@ impl serde::Deserialize for SavedAttachment {
                 let mut digest = None;
                 let mut encoded_length = None;
                 let mut encoding = None;
+                let mut follows = false;
                 let mut length = None;
                 let mut revpos = None;
 
@@ -214,6 +239,11 @@ impl serde::Deserialize for SavedAttachment {
                         Some(Field::Encoding) => {
                             encoding = Some(try!(visitor.visit_value()));
                         }
+                        Some(Field::Follows) => {
+                            // The content follows in a separate part of a
+                            // multipart response.
+                            follows = try!(visitor.visit_value());
+                        }
                         Some(Field::Length) => {
                             length = Some(try!(visitor.visit_value()));
                         }
@@ -234,6 +264,7 @@ impl serde::Deserialize for SavedAttachment {
 
                 let content = match (data, length) {
                     (Some(Base64JsonDecodable(data)), None) => SavedAttachmentContent::Bytes(data),
+                    (None, Some(length)) if follows => SavedAttachmentContent::Follows(length),
                     (None, Some(length)) => SavedAttachmentContent::LengthOnly(length),
                     (None, None) => {
                         use serde::de::Error;
@@ -289,7 +320,7 @@ impl serde::Deserialize for SavedAttachment {
         }
 
         static FIELDS: &'static [&'static str] =
-            &["content_type", "data", "digest", "encoded_length", "encoding", "length", "revpos", "stub"];
+            &["content_type", "data", "digest", "encoded_length", "encoding", "follows", "length", "revpos", "stub"];
         deserializer.deserialize_struct("SavedAttachment", FIELDS, Visitor)
     }
 }
@@ -313,6 +344,162 @@ impl serde::Serialize for UnsavedAttachment {
     }
 }
 
+/// Contains a byte range of an attachment's content.
+///
+/// An `AttachmentRange` is the result of `Database::get_attachment_range`. If
+/// the server honored the range request, then the content is the requested
+/// slice and `content_range` returns the server's `Content-Range` header.
+/// Otherwise, the content is the attachment's full content, starting at
+/// offset zero.
+///
+/// To resume a download that failed partway, request the range starting at
+/// the number of bytes already received—see `Database::resume_attachment`.
+/// Whether that is worthwhile depends on `accepts_ranges`.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttachmentRange {
+    content_type: mime::Mime,
+    content: Vec<u8>,
+    content_range: Option<String>,
+    offset: u64,
+    total_length: Option<u64>,
+    accepts_ranges: bool,
+}
+
+impl AttachmentRange {
+    #[doc(hidden)]
+    pub fn new_full(content_type: mime::Mime, content: Vec<u8>) -> Self {
+        let total_length = content.len() as u64;
+        AttachmentRange {
+            content_type: content_type,
+            content: content,
+            content_range: None,
+            offset: 0,
+            total_length: Some(total_length),
+            accepts_ranges: false,
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn new_partial(content_type: mime::Mime, content: Vec<u8>, content_range: &str) -> Result<Self, Error> {
+        let (offset, total_length) = match parse_content_range(content_range) {
+            Some(x) => x,
+            None => return Err(Error::UnexpectedResponse("The response has an invalid Content-Range header")),
+        };
+        Ok(AttachmentRange {
+            content_type: content_type,
+            content: content,
+            content_range: Some(String::from(content_range)),
+            offset: offset,
+            total_length: total_length,
+            accepts_ranges: true,
+        })
+    }
+
+    #[doc(hidden)]
+    pub fn with_accepts_ranges(mut self, accepts_ranges: bool) -> Self {
+        self.accepts_ranges = accepts_ranges;
+        self
+    }
+
+    /// Returns the attachment's content type.
+    pub fn content_type(&self) -> &mime::Mime {
+        &self.content_type
+    }
+
+    /// Returns the content of the range.
+    pub fn content(&self) -> &[u8] {
+        &self.content
+    }
+
+    /// Takes ownership of the content of the range.
+    pub fn into_content(self) -> Vec<u8> {
+        self.content
+    }
+
+    /// Returns whether the content is a slice of the attachment's content, as
+    /// opposed to the full content.
+    pub fn is_partial(&self) -> bool {
+        self.content_range.is_some()
+    }
+
+    /// Returns the server's `Content-Range` header—e.g., <q>bytes
+    /// 0-99/1234</q>—if the server honored the range request.
+    pub fn content_range(&self) -> Option<&str> {
+        self.content_range.as_ref().map(|x| x.as_str())
+    }
+
+    /// Returns the position of the content's first byte within the
+    /// attachment's full content.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the size of the attachment's full content, in bytes, if known.
+    pub fn total_length(&self) -> Option<u64> {
+        self.total_length
+    }
+
+    /// Returns the position just past the content's last byte within the
+    /// attachment's full content—i.e., the offset from which to request the
+    /// next range.
+    pub fn end_offset(&self) -> u64 {
+        self.offset + self.content.len() as u64
+    }
+
+    /// Returns whether the content reaches the end of the attachment's full
+    /// content.
+    ///
+    /// The result is false if the total length is unknown.
+    ///
+    pub fn is_complete(&self) -> bool {
+        self.total_length.map(|x| x <= self.end_offset()).unwrap_or(false)
+    }
+
+    /// Returns whether the server supports range requests for the
+    /// attachment—i.e., whether it honored the range request or advertised
+    /// `Accept-Ranges: bytes`.
+    ///
+    /// If false, then resuming a failed download re-reads the full content.
+    ///
+    pub fn accepts_ranges(&self) -> bool {
+        self.accepts_ranges
+    }
+}
+
+// Parses the first byte position and the complete length from the value of a
+// Content-Range header, e.g., "bytes 0-99/1234" or "bytes 0-99/*".
+fn parse_content_range(content_range: &str) -> Option<(u64, Option<u64>)> {
+    let mut parts = content_range.trim().splitn(2, ' ');
+    if parts.next() != Some("bytes") {
+        return None;
+    }
+    let mut parts = match parts.next() {
+        Some(x) => x.trim().splitn(2, '/'),
+        None => return None,
+    };
+    let (first, last) = match parts.next().map(|x| x.splitn(2, '-').collect::<Vec<_>>()) {
+        Some(ref x) if x.len() == 2 => {
+            match (x[0].parse::<u64>(), x[1].parse::<u64>()) {
+                (Ok(first), Ok(last)) if first <= last => (first, last),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    let total_length = match parts.next() {
+        Some("*") => None,
+        Some(x) => {
+            match x.parse::<u64>() {
+                Ok(x) if last < x => Some(x),
+                _ => return None,
+            }
+        }
+        None => return None,
+    };
+    Some((first, total_length))
+}
+
 #[derive(Debug, PartialEq)]
 struct Base64JsonDecodable(Vec<u8>);
 
@@ -463,7 +650,7 @@ mod tests {
     use serde_json;
     use super::*;
     use super::{AttachmentEncodingInfo, Base64JsonDecodable, Base64JsonEncodable, ContentTypeJsonDecodable,
-                SavedAttachmentContent};
+                SavedAttachmentContent, parse_content_range};
 
     #[test]
     fn attachment_serialize_saved() {
@@ -581,6 +768,30 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn saved_attachment_deserialize_ok_as_follows() {
+
+        let expected = SavedAttachment {
+            content_type: "text/plain".parse().unwrap(),
+            digest: "md5-iMaiC8wqiFlD2NjLTemvCQ==".to_string(),
+            sequence_number: 11,
+            content: SavedAttachmentContent::Follows(5),
+            encoding_info: None,
+        };
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("content_type", "text/plain")
+            .insert("digest", "md5-iMaiC8wqiFlD2NjLTemvCQ==")
+            .insert("length", 5)
+            .insert("revpos", 11)
+            .insert("follows", true)
+            .build();
+
+        let source = serde_json::to_string(&source).unwrap();
+        let got = serde_json::from_str(&source).unwrap();
+        assert_eq!(expected, got);
+    }
+
     #[test]
     fn saved_attachment_deserialize_ok_as_stub_with_encoding_info() {
 
@@ -742,4 +953,18 @@ mod tests {
         let got = serde_json::from_str::<ContentTypeJsonDecodable>(&source);
         expect_json_error_invalid_value!(got);
     }
+
+    #[test]
+    fn parse_content_range_ok() {
+        assert_eq!(parse_content_range("bytes 0-99/1234"), Some((0, Some(1234))));
+        assert_eq!(parse_content_range("bytes 100-199/*"), Some((100, None)));
+    }
+
+    #[test]
+    fn parse_content_range_nok() {
+        assert_eq!(parse_content_range("items 0-99/1234"), None);
+        assert_eq!(parse_content_range("bytes 99-0/1234"), None);
+        assert_eq!(parse_content_range("bytes 0-99/50"), None);
+        assert_eq!(parse_content_range("bytes */1234"), None);
+    }
 }
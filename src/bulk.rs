@@ -0,0 +1,340 @@
+use {DocumentId, ErrorResponse, Revision, serde, std};
+
+/// Contains the outcome of writing one document as part of a bulk write.
+///
+/// The CouchDB server sends one result per document in response to the HTTP
+/// request `POST /db/_bulk_docs`, in the same order as the documents in the
+/// request. A result either has a new revision, if the write succeeded, or an
+/// error response, if the write failed.
+///
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct BulkDocsResult {
+    doc_id: Option<DocumentId>,
+    revision: Option<Revision>,
+    error_response: Option<ErrorResponse>,
+}
+
+impl BulkDocsResult {
+    #[doc(hidden)]
+    pub fn new_ok(doc_id: DocumentId, revision: Revision) -> Self {
+        BulkDocsResult {
+            doc_id: Some(doc_id),
+            revision: Some(revision),
+            error_response: None,
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn new_error(doc_id: Option<DocumentId>, error_response: ErrorResponse) -> Self {
+        BulkDocsResult {
+            doc_id: doc_id,
+            revision: None,
+            error_response: Some(error_response),
+        }
+    }
+
+    /// Returns whether the document was written.
+    pub fn is_ok(&self) -> bool {
+        self.error_response.is_none()
+    }
+
+    /// Returns whether the document was not written because of a document
+    /// conflict—i.e., the document's revision is not its current revision.
+    pub fn is_conflict(&self) -> bool {
+        self.error_response.as_ref().map(|x| x.error() == "conflict").unwrap_or(false)
+    }
+
+    /// Returns the document's id, if available.
+    ///
+    /// The id is unavailable if the document failed to be written before the
+    /// server assigned it an id.
+    ///
+    pub fn doc_id(&self) -> Option<&DocumentId> {
+        self.doc_id.as_ref()
+    }
+
+    /// Returns the document's new revision, if the document was written.
+    pub fn revision(&self) -> Option<&Revision> {
+        self.revision.as_ref()
+    }
+
+    /// Returns the error response, if the document was not written.
+    pub fn error_response(&self) -> Option<&ErrorResponse> {
+        self.error_response.as_ref()
+    }
+}
+
+/// Contains the outcome of a bulk write—see the `WriteDocuments` action.
+///
+/// A `BulkWriteResponse` contains one `BulkDocsResult` per document, in the
+/// same order as the documents in the request, so that the application can
+/// correlate each result with its document by position.
+///
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BulkWriteResponse {
+    results: Vec<BulkDocsResult>,
+}
+
+impl BulkWriteResponse {
+    #[doc(hidden)]
+    pub fn new(results: Vec<BulkDocsResult>) -> Self {
+        BulkWriteResponse { results: results }
+    }
+
+    /// Returns whether every document was written.
+    pub fn is_ok(&self) -> bool {
+        self.results.iter().all(|x| x.is_ok())
+    }
+
+    /// Returns the results, in request order.
+    pub fn results(&self) -> &Vec<BulkDocsResult> {
+        &self.results
+    }
+
+    /// Converts the response into its results, in request order.
+    pub fn into_results(self) -> Vec<BulkDocsResult> {
+        self.results
+    }
+
+    /// Returns an iterator over the documents that were not written, yielding
+    /// each document's id, if available, and error response.
+    pub fn errors(&self) -> BulkWriteErrors {
+        BulkWriteErrors { inner: self.results.iter() }
+    }
+}
+
+impl IntoIterator for BulkWriteResponse {
+    type Item = BulkDocsResult;
+    type IntoIter = std::vec::IntoIter<BulkDocsResult>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.results.into_iter()
+    }
+}
+
+/// Iterates through the failures of a bulk write—see
+/// `BulkWriteResponse::errors`.
+pub struct BulkWriteErrors<'a> {
+    inner: std::slice::Iter<'a, BulkDocsResult>,
+}
+
+impl<'a> Iterator for BulkWriteErrors<'a> {
+    type Item = (Option<&'a DocumentId>, &'a ErrorResponse);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(result) = self.inner.next() {
+            if let Some(ref error_response) = result.error_response {
+                return Some((result.doc_id.as_ref(), error_response));
+            }
+        }
+        None
+    }
+}
+
+/// Summarizes the outcome of importing documents from a newline-delimited
+/// JSON source—see the `ImportNdjson` action.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ImportReport {
+    documents_written: u64,
+    failures: Vec<BulkDocsResult>,
+    malformed_lines: Vec<u64>,
+}
+
+impl ImportReport {
+    #[doc(hidden)]
+    pub fn add_result(&mut self, result: BulkDocsResult) {
+        if result.is_ok() {
+            self.documents_written += 1;
+        } else {
+            self.failures.push(result);
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn add_documents_written(&mut self, num_documents: u64) {
+        self.documents_written += num_documents;
+    }
+
+    #[doc(hidden)]
+    pub fn add_malformed_line(&mut self, line_number: u64) {
+        self.malformed_lines.push(line_number);
+    }
+
+    /// Returns the number of documents written.
+    pub fn documents_written(&self) -> u64 {
+        self.documents_written
+    }
+
+    /// Returns the results of the documents the server failed to write, in
+    /// source order.
+    pub fn failures(&self) -> &Vec<BulkDocsResult> {
+        &self.failures
+    }
+
+    /// Returns the line numbers, starting at 1, of the source lines that are
+    /// not JSON objects.
+    pub fn malformed_lines(&self) -> &Vec<u64> {
+        &self.malformed_lines
+    }
+}
+
+impl serde::Deserialize for BulkDocsResult {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+        where D: serde::Deserializer
+    {
+        enum Field {
+            Error,
+            Id,
+            Ignored,
+            Reason,
+            Rev,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+                where D: serde::Deserializer
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Self::Value, E>
+                        where E: serde::de::Error
+                    {
+                        match value {
+                            "error" => Ok(Field::Error),
+                            "id" => Ok(Field::Id),
+                            "reason" => Ok(Field::Reason),
+                            "rev" => Ok(Field::Rev),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = BulkDocsResult;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+                where V: serde::de::MapVisitor
+            {
+                let mut error = None;
+                let mut id = None;
+                let mut reason = None;
+                let mut rev = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Error) => {
+                            error = Some(try!(visitor.visit_value::<String>()));
+                        }
+                        Some(Field::Id) => {
+                            id = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde::de::impls::IgnoredAny>());
+                        }
+                        Some(Field::Reason) => {
+                            reason = Some(try!(visitor.visit_value::<String>()));
+                        }
+                        Some(Field::Rev) => {
+                            rev = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                let error_response = error.map(|error| ErrorResponse::new(error, reason.unwrap_or(String::new())));
+
+                let revision = match (rev, error_response.is_some()) {
+                    (rev, true) => rev,
+                    (Some(rev), false) => Some(rev),
+                    (None, false) => try!(visitor.missing_field("rev")),
+                };
+
+                Ok(BulkDocsResult {
+                    doc_id: id,
+                    revision: revision,
+                    error_response: error_response,
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["error", "id", "reason", "rev"];
+        deserializer.deserialize_struct("BulkDocsResult", FIELDS, Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {DocumentId, ErrorResponse, Revision, serde_json};
+    use super::*;
+
+    #[test]
+    fn bulk_docs_result_deserialize_ok() {
+        let expected = BulkDocsResult::new_ok(DocumentId::from("foo"),
+                                              Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap());
+        let source = r#"{"ok": true, "id": "foo", "rev": "1-1234567890abcdef1234567890abcdef"}"#;
+        let got = serde_json::from_str(source).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn bulk_docs_result_deserialize_error() {
+        let expected = BulkDocsResult::new_error(Some(DocumentId::from("foo")),
+                                                 ErrorResponse::new("conflict", "Document update conflict."));
+        let source = r#"{"id": "foo", "error": "conflict", "reason": "Document update conflict."}"#;
+        let got: BulkDocsResult = serde_json::from_str(source).unwrap();
+        assert_eq!(expected, got);
+        assert!(!got.is_ok());
+    }
+
+    #[test]
+    fn bulk_docs_result_is_conflict() {
+        let conflict = BulkDocsResult::new_error(Some(DocumentId::from("foo")),
+                                                 ErrorResponse::new("conflict", "Document update conflict."));
+        let forbidden = BulkDocsResult::new_error(Some(DocumentId::from("foo")),
+                                                  ErrorResponse::new("forbidden", "Invalid document."));
+        let ok = BulkDocsResult::new_ok(DocumentId::from("foo"),
+                                        Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap());
+        assert!(conflict.is_conflict());
+        assert!(!forbidden.is_conflict());
+        assert!(!ok.is_conflict());
+    }
+
+    #[test]
+    fn bulk_write_response_errors() {
+        let response = BulkWriteResponse::new(vec![
+            BulkDocsResult::new_ok(DocumentId::from("alpha"),
+                                   Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap()),
+            BulkDocsResult::new_error(Some(DocumentId::from("bravo")),
+                                      ErrorResponse::new("conflict", "Document update conflict.")),
+            BulkDocsResult::new_error(None, ErrorResponse::new("forbidden", "Invalid document.")),
+        ]);
+
+        let bravo = DocumentId::from("bravo");
+        let conflict = ErrorResponse::new("conflict", "Document update conflict.");
+        let forbidden = ErrorResponse::new("forbidden", "Invalid document.");
+        let expected = vec![(Some(&bravo), &conflict), (None, &forbidden)];
+        let got = response.errors().collect::<Vec<_>>();
+        assert_eq!(expected, got);
+        assert!(!response.is_ok());
+    }
+
+    #[test]
+    fn bulk_docs_result_deserialize_nok_missing_rev() {
+        let source = r#"{"ok": true, "id": "foo"}"#;
+        let got = serde_json::from_str::<BulkDocsResult>(source);
+        expect_json_error_missing_field!(got, "rev");
+    }
+}
@@ -0,0 +1,176 @@
+use {Error, serde_json};
+
+/// Describes which optional features the CouchDB server supports.
+///
+/// Chill derives the capabilities from the server's version and feature list,
+/// as sent in response to the HTTP request `GET /`. See
+/// `Client::capabilities`.
+///
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Capabilities {
+    version: String,
+    has_mango: bool,
+    has_up: bool,
+    has_bulk_get: bool,
+    has_partitioned: bool,
+    has_design_docs: bool,
+    has_dbs_info: bool,
+}
+
+impl Capabilities {
+    #[doc(hidden)]
+    pub fn from_server_info(content: &serde_json::Value) -> Result<Self, Error> {
+
+        let version = match content.find("version").and_then(|x| x.as_str()) {
+            Some(x) => x,
+            None => return Err(Error::UnexpectedResponse("The server info has no version")),
+        };
+
+        let (major, minor) = match parse_version(version) {
+            Some(x) => x,
+            None => return Err(Error::UnexpectedResponse("The server info has an invalid version")),
+        };
+
+        let has_feature = |name: &str| {
+            content.find("features")
+                .and_then(|x| x.as_array())
+                .map(|x| x.iter().any(|x| x.as_str() == Some(name)))
+                .unwrap_or(false)
+        };
+
+        let at_least = |x: u64, y: u64| (major, minor) >= (x, y);
+
+        Ok(Capabilities {
+            version: String::from(version),
+            has_mango: at_least(2, 0),
+            has_up: at_least(2, 0),
+            has_bulk_get: at_least(2, 0),
+            has_partitioned: has_feature("partitioned"),
+            has_design_docs: at_least(2, 2),
+            has_dbs_info: at_least(2, 2),
+        })
+    }
+
+    /// Returns the server's version string—e.g., <q>2.3.1</q>.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Returns whether the server supports Mango queries, via `POST
+    /// /db/_find`.
+    pub fn has_mango(&self) -> bool {
+        self.has_mango
+    }
+
+    /// Returns whether the server supports the `GET /_up` endpoint, which
+    /// CouchDB 2.0 introduced.
+    pub fn has_up(&self) -> bool {
+        self.has_up
+    }
+
+    /// Returns whether the server supports the `POST /db/_bulk_get` endpoint.
+    pub fn has_bulk_get(&self) -> bool {
+        self.has_bulk_get
+    }
+
+    /// Returns whether the server has partitioned databases enabled.
+    pub fn has_partitioned(&self) -> bool {
+        self.has_partitioned
+    }
+
+    /// Returns whether the server supports the `GET /db/_design_docs`
+    /// endpoint.
+    pub fn has_design_docs(&self) -> bool {
+        self.has_design_docs
+    }
+
+    /// Returns whether the server supports the `POST /_dbs_info` endpoint.
+    pub fn has_dbs_info(&self) -> bool {
+        self.has_dbs_info
+    }
+}
+
+// Parses the major and minor numbers from a version string, ignoring any
+// patch number and suffix—e.g., "2.3.1" or "3.2.2-a1b2c3d".
+fn parse_version(version: &str) -> Option<(u64, u64)> {
+    let mut parts = version.split(|c: char| !c.is_digit(10));
+    let major = match parts.next().and_then(|x| x.parse().ok()) {
+        Some(x) => x,
+        None => return None,
+    };
+    let minor = parts.next().and_then(|x| x.parse().ok()).unwrap_or(0);
+    Some((major, minor))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {Error, serde_json};
+    use super::*;
+
+    #[test]
+    fn from_server_info_couchdb_1() {
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("couchdb", "Welcome")
+            .insert("version", "1.6.1")
+            .build();
+        let got = Capabilities::from_server_info(&source).unwrap();
+        assert_eq!(got.version(), "1.6.1");
+        assert!(!got.has_mango());
+        assert!(!got.has_up());
+        assert!(!got.has_bulk_get());
+        assert!(!got.has_partitioned());
+        assert!(!got.has_design_docs());
+        assert!(!got.has_dbs_info());
+    }
+
+    #[test]
+    fn from_server_info_couchdb_2_0() {
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("couchdb", "Welcome")
+            .insert("version", "2.0.0")
+            .build();
+        let got = Capabilities::from_server_info(&source).unwrap();
+        assert_eq!(got.version(), "2.0.0");
+        assert!(got.has_mango());
+        assert!(got.has_up());
+        assert!(got.has_bulk_get());
+        assert!(!got.has_partitioned());
+        assert!(!got.has_design_docs());
+        assert!(!got.has_dbs_info());
+    }
+
+    #[test]
+    fn from_server_info_couchdb_3() {
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("couchdb", "Welcome")
+            .insert("version", "3.2.2")
+            .insert_array("features", |x| x.push("scheduler").push("partitioned"))
+            .build();
+        let got = Capabilities::from_server_info(&source).unwrap();
+        assert_eq!(got.version(), "3.2.2");
+        assert!(got.has_mango());
+        assert!(got.has_up());
+        assert!(got.has_bulk_get());
+        assert!(got.has_partitioned());
+        assert!(got.has_design_docs());
+        assert!(got.has_dbs_info());
+    }
+
+    #[test]
+    fn from_server_info_nok_no_version() {
+        let source = serde_json::builder::ObjectBuilder::new().insert("couchdb", "Welcome").build();
+        match Capabilities::from_server_info(&source) {
+            Err(Error::UnexpectedResponse(..)) => {}
+            x => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn parse_version_ok() {
+        assert_eq!(parse_version("2.3.1"), Some((2, 3)));
+        assert_eq!(parse_version("3.2.2-a1b2c3d"), Some((3, 2)));
+        assert_eq!(parse_version("2"), Some((2, 0)));
+        assert_eq!(parse_version("devel"), None);
+    }
+}
@@ -0,0 +1,239 @@
+use {DatabaseName, Document, DocumentId, Error, Revision, Seq, serde, serde_json};
+use document::JsonDecodableDocument;
+
+/// Contains a batch of changes from a database's changes feed.
+///
+/// The CouchDB server sends changes in response to the HTTP request `GET
+/// /db/_changes`. Each change is a `ChangeRow`, and the `last_seq` is the
+/// update sequence to resume from to receive subsequent changes.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Changes {
+    results: Vec<ChangeRow>,
+    last_seq: Seq,
+    pending: Option<u64>,
+}
+
+impl Changes {
+    #[doc(hidden)]
+    pub fn new_from_json(db_name: DatabaseName, mut body: serde_json::Value) -> Result<Self, Error> {
+
+        let body = match body.as_object_mut() {
+            Some(x) => x,
+            None => return Err(Error::UnexpectedResponse("The changes response is not a JSON object")),
+        };
+
+        let results = match body.remove("results") {
+            Some(serde_json::Value::Array(results)) => results,
+            _ => return Err(Error::UnexpectedResponse("The changes response has no results")),
+        };
+
+        let results = try!(results.into_iter()
+            .map(|x| ChangeRow::new_from_json(db_name.clone(), x))
+            .collect::<Result<Vec<_>, Error>>());
+
+        let last_seq = match body.remove("last_seq") {
+            Some(x) => try!(serde_json::from_value(x).map_err(|e| Error::JsonDecode { cause: Box::new(e) })),
+            None => return Err(Error::UnexpectedResponse("The changes response has no last sequence")),
+        };
+
+        let pending = body.get("pending").and_then(|x| x.as_u64());
+
+        Ok(Changes {
+            results: results,
+            last_seq: last_seq,
+            pending: pending,
+        })
+    }
+
+    /// Returns the changes, in the order the server sent them.
+    pub fn results(&self) -> &Vec<ChangeRow> {
+        &self.results
+    }
+
+    /// Converts the batch into its changes.
+    pub fn into_results(self) -> Vec<ChangeRow> {
+        self.results
+    }
+
+    /// Returns the update sequence of the last change in the batch—i.e., the
+    /// sequence to resume from.
+    pub fn last_seq(&self) -> &Seq {
+        &self.last_seq
+    }
+
+    /// Returns the number of changes remaining after the batch, if the server
+    /// reports it.
+    ///
+    /// CouchDB 2.x and later send the `pending` field. On a clustered server,
+    /// the count is an estimate summed across shards and may be inexact.
+    ///
+    pub fn pending(&self) -> Option<u64> {
+        self.pending
+    }
+}
+
+/// Contains one change from a database's changes feed.
+///
+/// A change identifies a document and its leaf revisions as of the change's
+/// update sequence. The change contains the document itself only if the
+/// request included documents.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChangeRow {
+    seq: Seq,
+    doc_id: DocumentId,
+    revisions: Vec<Revision>,
+    deleted: bool,
+    doc: Option<Document>,
+}
+
+impl ChangeRow {
+    #[doc(hidden)]
+    pub fn new_from_json(db_name: DatabaseName, mut row: serde_json::Value) -> Result<Self, Error> {
+
+        fn decode<T: serde::Deserialize>(value: serde_json::Value) -> Result<T, Error> {
+            serde_json::from_value(value).map_err(|e| Error::JsonDecode { cause: Box::new(e) })
+        }
+
+        let row = match row.as_object_mut() {
+            Some(x) => x,
+            None => return Err(Error::UnexpectedResponse("The change is not a JSON object")),
+        };
+
+        let seq = match row.remove("seq") {
+            Some(x) => try!(decode(x)),
+            None => return Err(Error::UnexpectedResponse("The change has no sequence")),
+        };
+
+        let doc_id = match row.remove("id") {
+            Some(x) => try!(decode(x)),
+            None => return Err(Error::UnexpectedResponse("The change has no document id")),
+        };
+
+        let revisions = match row.remove("changes") {
+            Some(serde_json::Value::Array(changes)) => {
+                try!(changes.into_iter()
+                    .map(|mut x| match x.as_object_mut().and_then(|x| x.remove("rev")) {
+                        Some(rev) => decode(rev),
+                        None => Err(Error::UnexpectedResponse("The change has a revision entry without a revision")),
+                    })
+                    .collect::<Result<Vec<_>, Error>>())
+            }
+            _ => Vec::new(),
+        };
+
+        let deleted = row.remove("deleted").and_then(|x| x.as_bool()).unwrap_or(false);
+
+        let doc = match row.remove("doc") {
+            None |
+            Some(serde_json::Value::Null) => None,
+            Some(x) => {
+                let doc: JsonDecodableDocument = try!(decode(x));
+                Some(Document::new_from_decoded(db_name, doc))
+            }
+        };
+
+        Ok(ChangeRow {
+            seq: seq,
+            doc_id: doc_id,
+            revisions: revisions,
+            deleted: deleted,
+            doc: doc,
+        })
+    }
+
+    /// Returns the change's update sequence.
+    pub fn seq(&self) -> &Seq {
+        &self.seq
+    }
+
+    /// Returns the id of the changed document.
+    pub fn doc_id(&self) -> &DocumentId {
+        &self.doc_id
+    }
+
+    /// Returns the document's leaf revisions as of the change.
+    pub fn revisions(&self) -> &Vec<Revision> {
+        &self.revisions
+    }
+
+    /// Returns whether the change deleted the document.
+    pub fn is_deleted(&self) -> bool {
+        self.deleted
+    }
+
+    /// Returns the document, if available.
+    ///
+    /// The document is available if and only if the request included
+    /// documents.
+    ///
+    pub fn document(&self) -> Option<&Document> {
+        self.doc.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {DatabaseName, DocumentId, Error, Revision, Seq, serde_json};
+    use super::*;
+
+    #[test]
+    fn changes_new_from_json_ok() {
+
+        let source = serde_json::from_str(r#"{"results": [
+            {"seq": 1, "id": "bar", "changes": [{"rev": "1-1234567890abcdef1234567890abcdef"}]},
+            {"seq": 3, "id": "qux", "changes": [{"rev": "2-1234567890abcdef1234567890abcdef"}], "deleted": true}
+        ], "last_seq": 3}"#)
+            .unwrap();
+
+        let expected = Changes {
+            results: vec![ChangeRow {
+                              seq: Seq::from(1),
+                              doc_id: DocumentId::from("bar"),
+                              revisions: vec![Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap()],
+                              deleted: false,
+                              doc: None,
+                          },
+                          ChangeRow {
+                              seq: Seq::from(3),
+                              doc_id: DocumentId::from("qux"),
+                              revisions: vec![Revision::parse("2-1234567890abcdef1234567890abcdef").unwrap()],
+                              deleted: true,
+                              doc: None,
+                          }],
+            last_seq: Seq::from(3),
+            pending: None,
+        };
+
+        let got = Changes::new_from_json(DatabaseName::from("foo"), source).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn changes_new_from_json_ok_opaque_seq_and_doc() {
+
+        let source = serde_json::from_str(r#"{"results": [
+            {"seq": "1-g1AAAA", "id": "bar", "changes": [{"rev": "1-1234567890abcdef1234567890abcdef"}],
+             "doc": {"_id": "bar", "_rev": "1-1234567890abcdef1234567890abcdef", "field": 42}}
+        ], "last_seq": "1-g1AAAA", "pending": 5}"#)
+            .unwrap();
+
+        let got = Changes::new_from_json(DatabaseName::from("foo"), source).unwrap();
+        assert_eq!(&Seq::from("1-g1AAAA"), got.last_seq());
+        assert_eq!(Some(5), got.pending());
+        assert_eq!(&Seq::from("1-g1AAAA"), got.results()[0].seq());
+        let doc = got.results()[0].document().unwrap();
+        assert_eq!(42, doc.get_content::<serde_json::Value>().unwrap().find("field").unwrap().as_u64().unwrap());
+    }
+
+    #[test]
+    fn changes_new_from_json_nok_no_results() {
+        let source = serde_json::from_str(r#"{"last_seq": 3}"#).unwrap();
+        match Changes::new_from_json(DatabaseName::from("foo"), source) {
+            Err(Error::UnexpectedResponse(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
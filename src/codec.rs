@@ -0,0 +1,35 @@
+use {serde_json, std};
+
+/// Encodes and decodes the JSON content of HTTP requests and responses.
+///
+/// By default, Chill uses `serde_json` to encode and decode JSON. An
+/// application may instead provide its own codec—e.g., to control float
+/// formatting or whitespace—via the `Client::with_json_codec` method.
+///
+/// The codec works on `serde_json::Value`, not on application types. Chill
+/// converts between application types and `serde_json::Value` as usual, so
+/// the codec need only handle the textual JSON representation. Consequently,
+/// a codec adds no speed over the default: each request and response passes
+/// through an intermediate `serde_json::Value`.
+///
+/// Errors returned by the codec surface as `Error::JsonEncode` and
+/// `Error::JsonDecode`.
+///
+/// # Large integers
+///
+/// CouchDB stores numbers as written, so a document may contain integers that
+/// exceed the precision of `f64`. The default codec decodes any integer in the
+/// range of `i64` or `u64` exactly, and encodes it back unchanged, so ids and
+/// amounts up to 2<sup>64</sup>&nbsp;&minus;&nbsp;1 round-trip without loss.
+/// An integer beyond that range decodes as `f64` and loses precision. A codec
+/// cannot prevent this because `serde_json::Value` has no representation for
+/// such integers, so an application that stores them should store them as
+/// strings.
+///
+pub trait JsonCodec: Send + Sync {
+    /// Encodes a JSON value as bytes.
+    fn encode(&self, value: &serde_json::Value) -> Result<Vec<u8>, Box<std::error::Error + Send + Sync>>;
+
+    /// Decodes bytes as a JSON value.
+    fn decode(&self, content: &[u8]) -> Result<serde_json::Value, Box<std::error::Error + Send + Sync>>;
+}
@@ -0,0 +1,1585 @@
+use {AttachmentName, AttachmentPath, AttachmentRange, BulkDocsResult, DatabaseName, DatabasePath, Document, DocumentId,
+     DocumentPath, Error, ErrorResponse, IndexDef, IndexInfo, IndexOutcome, IntoDatabasePath, LocalDocumentName,
+     PurgedInfo, Revision, Seq, Since, ViewRow, action, mime, serde, serde_json, std};
+use transport::{StatusCode, Transport};
+
+/// Refers to a database on the CouchDB server.
+///
+/// A `Database` is a convenience for building actions that target the same
+/// database. It is obtained in one of two ways:
+///
+/// * `Client::database` constructs the handle without any network I/O. The
+///   database may or may not exist—an action built from the handle fails if
+///   it does not.
+///
+/// * `Client::open_database` first checks that the database exists, by
+///   sending an HTTP request to `HEAD /db`, and fails with `Error::NotFound`
+///   if it does not.
+///
+pub struct Database<'a, T: Transport + 'a> {
+    transport: &'a T,
+    db_path: DatabasePath,
+}
+
+impl<'a, T: Transport + 'a> Database<'a, T> {
+    #[doc(hidden)]
+    pub fn new<P: IntoDatabasePath>(transport: &'a T, db_path: P) -> Result<Self, Error> {
+        Ok(Database {
+            transport: transport,
+            db_path: try!(db_path.into_database_path()),
+        })
+    }
+
+    #[doc(hidden)]
+    pub fn open<P: IntoDatabasePath>(transport: &'a T, db_path: P) -> Result<Self, Error> {
+        let db = try!(Database::new(transport, db_path));
+        match action::PingDatabase::new(transport, db.db_path.clone()).run() {
+            Ok(()) => Ok(db),
+            Err(Error::ServerResponse { status_code: StatusCode::NotFound, .. }) => {
+                Err(Error::NotFound(ErrorResponse::new("not_found", "Database does not exist.")))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns the database's path.
+    pub fn path(&self) -> &DatabasePath {
+        &self.db_path
+    }
+
+    /// Builds an action to create a document in this database.
+    pub fn create_document<C>(&self, content: &'a C) -> action::CreateDocument<'a, T, DatabasePath, C>
+        where C: serde::Serialize + 'a
+    {
+        action::CreateDocument::new(self.transport, self.db_path.clone(), content)
+    }
+
+    /// Builds an action to read a document in this database.
+    pub fn read_document<D: Into<DocumentId>>(&self, doc_id: D) -> action::ReadDocument<'a, T, DocumentPath> {
+        action::ReadDocument::new(self.transport, self.make_document_path(doc_id))
+    }
+
+    /// Checks whether the given revision is the current revision of a document
+    /// in this database.
+    ///
+    /// The `is_current_revision` method executes the `ReadDocumentRevision`
+    /// action—see its documentation for details—and compares the result to
+    /// the given revision. This lets an application skip an update that would
+    /// certainly fail with a conflict. The result is `Error::NotFound` if the
+    /// document does not exist.
+    ///
+    pub fn is_current_revision<D>(&self, doc_id: D, revision: &Revision) -> Result<bool, Error>
+        where D: Into<DocumentId>
+    {
+        let current = try!(action::ReadDocumentRevision::new(self.transport, self.make_document_path(doc_id)).run());
+        Ok(current == *revision)
+    }
+
+    /// Reads a document in this database and also returns the document as
+    /// the server sent it.
+    ///
+    /// The `get_document_with_raw` method is equivalent to executing the
+    /// `ReadDocument` action with `run_with_raw`—see its documentation for
+    /// details.
+    ///
+    pub fn get_document_with_raw<D>(&self, doc_id: D) -> Result<(Document, serde_json::Value), Error>
+        where D: Into<DocumentId>
+    {
+        action::ReadDocument::new(self.transport, self.make_document_path(doc_id)).run_with_raw()
+    }
+
+    /// Reads the latest leaf revision of a document in this database that
+    /// descends from the given revision.
+    ///
+    /// The `get_document_latest` method is equivalent to executing the
+    /// `ReadDocument` action with `with_revision` and `with_latest(true)`. If
+    /// the given revision is stale, then the result is its newest descendant,
+    /// not the given revision—which is what a replicator needs when the
+    /// source document changes between reading the changes feed and reading
+    /// the document.
+    ///
+    pub fn get_document_latest<D>(&self, doc_id: D, revision: &Revision) -> Result<Document, Error>
+        where D: Into<DocumentId>
+    {
+        action::ReadDocument::new(self.transport, self.make_document_path(doc_id))
+            .with_revision(revision)
+            .with_latest(true)
+            .run()
+    }
+
+    /// Reads a document in this database, creating the document with the given
+    /// content if it does not exist.
+    ///
+    /// This is useful for initializing a singleton document, such as an
+    /// application's configuration. If the server responds to the create with
+    /// a conflict—because another client created the document in the
+    /// meantime—then the method reads the document the other client created.
+    /// Either way, the result is the document as stored on the server,
+    /// including its current revision.
+    ///
+    /// The method sends two or three HTTP requests: a `GET` to read the
+    /// document, and, if the document does not exist, a `PUT` to create it
+    /// and another `GET` to read it back.
+    ///
+    pub fn get_or_create_document<D, C>(&self, doc_id: D, default: &C) -> Result<Document, Error>
+        where C: serde::Serialize,
+              D: Into<DocumentId>
+    {
+        let doc_id = doc_id.into();
+
+        match action::ReadDocument::new(self.transport, self.make_document_path(doc_id.clone())).run() {
+            Err(Error::NotFound(..)) => (),
+            x @ _ => return x,
+        }
+
+        match action::CreateDocument::new(self.transport, self.db_path.clone(), default)
+            .with_document_id(doc_id.clone())
+            .run() {
+            Ok(..) | Err(Error::DocumentConflict(..)) => (),
+            Err(e) => return Err(e),
+        }
+
+        action::ReadDocument::new(self.transport, self.make_document_path(doc_id)).run()
+    }
+
+    /// Builds an action to read multiple documents in this database by id.
+    pub fn read_documents<I>(&self, doc_ids: I) -> action::ReadDocuments<'a, T, DatabasePath>
+        where I: IntoIterator,
+              I::Item: Into<DocumentId>
+    {
+        action::ReadDocuments::new(self.transport,
+                                   self.db_path.clone(),
+                                   doc_ids.into_iter().map(|x| x.into()).collect())
+    }
+
+    /// Builds an action to delete a document in this database.
+    pub fn delete_document<D>(&self, doc_id: D, revision: &'a Revision) -> action::DeleteDocument<'a, T, DocumentPath>
+        where D: Into<DocumentId>
+    {
+        action::DeleteDocument::new(self.transport, self.make_document_path(doc_id), revision)
+    }
+
+    /// Reads a byte range of an attachment's content in this database.
+    ///
+    /// The range starts at byte offset `start` and ends at byte offset `end`,
+    /// inclusive, or at the end of the content if `end` is `None`. The
+    /// `get_attachment_range` method is equivalent to executing the
+    /// `ReadAttachmentRange` action—see its documentation for details.
+    ///
+    pub fn get_attachment_range<D, N>(&self,
+                                      doc_id: D,
+                                      att_name: N,
+                                      start: u64,
+                                      end: Option<u64>)
+                                      -> Result<AttachmentRange, Error>
+        where D: Into<DocumentId>,
+              N: Into<AttachmentName>
+    {
+        let att_path = AttachmentPath::from((self.make_document_path(doc_id), att_name.into()));
+        action::ReadAttachmentRange::new(self.transport, att_path, start, end).run()
+    }
+
+    /// Reads the rest of an attachment's content in this database, starting
+    /// at the given byte offset—e.g., to resume a download that failed after
+    /// receiving `offset` bytes.
+    ///
+    /// The `resume_attachment` method is equivalent to `get_attachment_range`
+    /// with an open end. If the server does not support range requests, then
+    /// the result is the full content, starting at offset zero, so the caller
+    /// must check `AttachmentRange::offset` before appending the content to
+    /// what it already received.
+    ///
+    pub fn resume_attachment<D, N>(&self, doc_id: D, att_name: N, offset: u64) -> Result<AttachmentRange, Error>
+        where D: Into<DocumentId>,
+              N: Into<AttachmentName>
+    {
+        self.get_attachment_range(doc_id, att_name, offset, None)
+    }
+
+    /// Uploads an attachment in this database, reading its content from the
+    /// given source, and returns the document's new revision.
+    ///
+    /// The content is streamed into the request body instead of being held in
+    /// memory—see `PutAttachmentStream`. If the length is given, then it must
+    /// be the content's exact length. The revision is required if the
+    /// document exists. A read error on the source is an `Error::Io`.
+    ///
+    pub fn put_attachment_stream<D, N, R>(&self,
+                                          doc_id: D,
+                                          revision: Option<&Revision>,
+                                          att_name: N,
+                                          content_type: mime::Mime,
+                                          source: R,
+                                          length: Option<u64>)
+                                          -> Result<Revision, Error>
+        where D: Into<DocumentId>,
+              N: Into<AttachmentName>,
+              R: std::io::Read
+    {
+        let att_path = AttachmentPath::from((self.make_document_path(doc_id), att_name.into()));
+        let action = action::PutAttachmentStream::new(self.transport, att_path, content_type, source, length);
+        match revision {
+            None => action.run(),
+            Some(revision) => action.with_revision(revision).run(),
+        }
+    }
+
+    /// Deletes multiple documents in this database by id.
+    ///
+    /// The `bulk_delete` method is equivalent to executing the
+    /// `DeleteDocuments` action—see its documentation for details. A document
+    /// that does not exist yields a `not_found` error response in its entry of
+    /// the result instead of failing the whole deletion.
+    ///
+    pub fn bulk_delete<I>(&self, doc_ids: I) -> Result<Vec<BulkDocsResult>, Error>
+        where I: IntoIterator,
+              I::Item: Into<DocumentId>
+    {
+        action::DeleteDocuments::new(self.transport,
+                                     self.db_path.clone(),
+                                     doc_ids.into_iter().map(|x| x.into()).collect())
+            .run()
+    }
+
+    /// Deletes this database, including all its documents.
+    ///
+    /// The `delete` method sends an HTTP request to `DELETE /db`. It consumes
+    /// the handle so that the application cannot keep using a database that no
+    /// longer exists.
+    ///
+    /// # Errors
+    ///
+    /// The method fails with `Error::NotFound` if the database does not exist,
+    /// and with `Error::Unauthorized` or `Error::Forbidden` if the client lacks
+    /// permission to delete the database.
+    ///
+    pub fn delete(self) -> Result<(), Error> {
+        action::DeleteDatabase::new(self.transport, self.db_path).run()
+    }
+
+    /// Returns the current revisions of multiple documents in this database.
+    ///
+    /// The `current_revisions` method is equivalent to executing the
+    /// `ReadRevisions` action—see its documentation for details. A document
+    /// that does not exist, or is deleted, maps to `None`.
+    ///
+    pub fn current_revisions<I>(&self,
+                                doc_ids: I)
+                                -> Result<std::collections::HashMap<DocumentId, Option<Revision>>, Error>
+        where I: IntoIterator,
+              I::Item: Into<DocumentId>
+    {
+        action::ReadRevisions::new(self.transport,
+                                   self.db_path.clone(),
+                                   doc_ids.into_iter().map(|x| x.into()).collect())
+            .run()
+    }
+
+    /// Builds an action to read meta-information about this database.
+    pub fn info(&self) -> action::ReadDatabaseInfo<'a, T, DatabasePath> {
+        action::ReadDatabaseInfo::new(self.transport, self.db_path.clone())
+    }
+
+    /// Returns the number of documents in this database.
+    ///
+    /// The count is the `doc_count` field of the database's info, via `GET
+    /// /db`, which excludes deleted documents and local documents but includes
+    /// design documents.
+    ///
+    pub fn count_documents(&self) -> Result<u64, Error> {
+        let info = try!(action::ReadDatabaseInfo::new(self.transport, self.db_path.clone()).run());
+        Ok(info.document_count())
+    }
+
+    /// Returns the number of documents in this database, including deleted
+    /// documents.
+    ///
+    /// The count is the sum of the `doc_count` and `doc_del_count` fields of
+    /// the database's info. A deleted document remains in the database as a
+    /// tombstone until the database is compacted and the document is purged,
+    /// so this count reflects how many documents replication and the changes
+    /// feed may report.
+    ///
+    pub fn count_including_deleted(&self) -> Result<u64, Error> {
+        let info = try!(action::ReadDatabaseInfo::new(self.transport, self.db_path.clone()).run());
+        Ok(info.document_count() + info.deleted_document_count())
+    }
+
+    /// Reads the revision limit of this database.
+    ///
+    /// The `revs_limit` method is equivalent to executing the `ReadRevsLimit`
+    /// action—see its documentation for details.
+    ///
+    pub fn revs_limit(&self) -> Result<u32, Error> {
+        action::ReadRevsLimit::new(self.transport, self.db_path.clone()).run()
+    }
+
+    /// Reads the documents in this database whose ids start with the given
+    /// prefix—e.g., `"user:"`.
+    ///
+    /// The `docs_with_prefix` method is equivalent to executing the
+    /// `ReadAllDocuments` action with `with_prefix` and `with_documents`—see
+    /// its documentation for details. Each row's key is the document id, and,
+    /// if `include_docs` is true, its document is the document's content. As
+    /// a safeguard, the result excludes any row whose id lacks the prefix.
+    ///
+    pub fn docs_with_prefix(&self, prefix: &str, include_docs: bool) -> Result<Vec<ViewRow>, Error> {
+        let response = try!(action::ReadAllDocuments::new(self.transport, self.db_path.clone())
+            .with_prefix(prefix)
+            .with_documents(include_docs)
+            .run());
+        Ok(response.rows()
+            .iter()
+            .filter(|row| row.document_path().map(|x| x.document_id().to_string().starts_with(prefix)).unwrap_or(false))
+            .cloned()
+            .collect())
+    }
+
+    /// Builds an action to list the design documents in this database.
+    pub fn design_documents(&self) -> action::ReadDesignDocuments<'a, T, DatabasePath> {
+        action::ReadDesignDocuments::new(self.transport, self.db_path.clone())
+    }
+
+    /// Builds an action to list the local documents in this database—e.g.,
+    /// replication checkpoints.
+    pub fn local_docs(&self) -> action::ReadLocalDocuments<'a, T, DatabasePath> {
+        action::ReadLocalDocuments::new(self.transport, self.db_path.clone())
+    }
+
+    /// Reads a replication checkpoint stored in this database.
+    ///
+    /// A checkpoint is a local document, `_local/{id}`, whose `last_seq` field
+    /// holds the last update sequence a replicator or changes consumer
+    /// processed. The result is `None` if the checkpoint does not exist—e.g.,
+    /// on the first run—so the caller can start from the beginning.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let client = chill::Client::new("http://localhost:5984").unwrap();
+    /// let db = client.database("/baseball").unwrap();
+    /// let since = db.read_checkpoint("sync-1").unwrap().map(chill::Since::from).unwrap_or(chill::Since::Zero);
+    /// let changes = db.changes().with_since(since).run().unwrap();
+    /// db.write_checkpoint("sync-1", changes.last_seq()).unwrap();
+    /// ```
+    ///
+    pub fn read_checkpoint<N: Into<LocalDocumentName>>(&self, id: N) -> Result<Option<Seq>, Error> {
+        let name: LocalDocumentName = id.into();
+        let doc = match action::ReadLocalDocument::new(self.transport, self.make_document_path(name)).run() {
+            Ok(x) => x,
+            Err(Error::NotFound(..)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let content: serde_json::Value = try!(doc.get_content());
+        match content.find("last_seq") {
+            Some(x) => {
+                serde_json::from_value(x.clone()).map(Some).map_err(|e| Error::JsonDecode { cause: Box::new(e) })
+            }
+            None => Err(Error::UnexpectedResponse("The checkpoint has no last_seq field")),
+        }
+    }
+
+    /// Writes a replication checkpoint to this database, creating or updating
+    /// the local document `_local/{id}`—see `read_checkpoint`.
+    ///
+    /// The method first reads the checkpoint to learn its current revision and
+    /// then writes the checkpoint with the given sequence as its `last_seq`
+    /// field, preserving any other fields. If another client updates the
+    /// checkpoint in between, then the method fails with
+    /// `Error::DocumentConflict`.
+    ///
+    pub fn write_checkpoint<N: Into<LocalDocumentName>>(&self, id: N, seq: &Seq) -> Result<(), Error> {
+
+        let name: LocalDocumentName = id.into();
+        let doc_path = self.make_document_path(name);
+
+        let (mut content, revision) = match action::ReadLocalDocument::new(self.transport, doc_path.clone()).run() {
+            Ok(doc) => (try!(doc.get_content::<serde_json::Value>()), Some(doc.revision().clone())),
+            Err(Error::NotFound(..)) => (serde_json::builder::ObjectBuilder::new().build(), None),
+            Err(e) => return Err(e),
+        };
+
+        match content.as_object_mut() {
+            Some(fields) => fields.insert(String::from("last_seq"), serde_json::to_value(seq)),
+            None => return Err(Error::UnexpectedResponse("The checkpoint is not a JSON object")),
+        };
+
+        let action = action::WriteLocalDocument::new(self.transport, doc_path, &content);
+        let action = match revision {
+            None => action,
+            Some(ref rev) => action.with_revision(rev),
+        };
+        try!(action.run());
+        Ok(())
+    }
+
+    /// Reads the current purge sequence and the whole purge history of this
+    /// database.
+    ///
+    /// The `purged_infos` method is equivalent to executing the
+    /// `ReadPurgedInfos` action—see its documentation for details. If the
+    /// server lacks the `_purged_infos` endpoint, then the method fails with an
+    /// `Error::ServerResponse` whose error response's error is
+    /// <q>not_supported</q>.
+    ///
+    pub fn purged_infos(&self) -> Result<(Seq, Vec<PurgedInfo>), Error> {
+        action::ReadPurgedInfos::new(self.transport, self.db_path.clone()).run()
+    }
+
+    /// Builds an action to write multiple documents in this database in one
+    /// request.
+    pub fn write_documents(&self, docs: &'a [serde_json::Value]) -> action::WriteDocuments<'a, T, DatabasePath> {
+        action::WriteDocuments::new(self.transport, self.db_path.clone(), docs)
+    }
+
+    /// Builds an action to query documents in this database with a Mango
+    /// selector.
+    pub fn find(&self, selector: &'a serde_json::Value) -> action::Find<'a, T, DatabasePath> {
+        action::Find::new(self.transport, self.db_path.clone(), selector)
+    }
+
+    /// Builds an action to query the documents in one partition of this
+    /// database with a Mango selector.
+    ///
+    /// The `find_in_partition` method is equivalent to executing the `Find`
+    /// action with `with_partition`—see its documentation for details.
+    ///
+    pub fn find_in_partition(&self,
+                             partition: &str,
+                             selector: &'a serde_json::Value)
+                             -> action::Find<'a, T, DatabasePath> {
+        action::Find::new(self.transport, self.db_path.clone(), selector).with_partition(partition)
+    }
+
+    /// Creates a Mango index in this database, unless an identical index
+    /// already exists.
+    ///
+    /// The `ensure_index` method is equivalent to executing the `CreateIndex`
+    /// action—see its documentation for details. The outcome tells whether the
+    /// server created the index or found it already existing, and it carries
+    /// the index's name and design document id as assigned by the server.
+    ///
+    pub fn ensure_index(&self, index: IndexDef) -> Result<IndexOutcome, Error> {
+        action::CreateIndex::new(self.transport, self.db_path.clone(), &index).run()
+    }
+
+    /// Lists the Mango indexes in this database, including the built-in
+    /// `_all_docs` index.
+    ///
+    /// The `indexes` method is equivalent to executing the `ReadIndexes`
+    /// action—see its documentation for details.
+    ///
+    pub fn indexes(&self) -> Result<Vec<IndexInfo>, Error> {
+        action::ReadIndexes::new(self.transport, self.db_path.clone()).run()
+    }
+
+    /// Deletes a Mango index in this database.
+    ///
+    /// The `drop_index` method is equivalent to executing the `DeleteIndex`
+    /// action—see its documentation for details. The method fails with
+    /// `Error::NotFound` if the index does not exist and with
+    /// `Error::InvalidOptions` if the index is the built-in `_all_docs` index
+    /// or the design document is empty.
+    ///
+    pub fn drop_index(&self, design_doc: &str, name: &str) -> Result<(), Error> {
+        action::DeleteIndex::new(self.transport, self.db_path.clone(), design_doc, name).run()
+    }
+
+    /// Builds an action to find documents in this database that have
+    /// conflicting revisions.
+    pub fn find_conflicts(&self, limit: u64) -> action::FindConflicts<'a, T, DatabasePath> {
+        action::FindConflicts::new(self.transport, self.db_path.clone(), limit)
+    }
+
+    /// Builds an action to read this database's changes feed.
+    pub fn changes(&self) -> action::ReadChanges<'a, T, DatabasePath> {
+        action::ReadChanges::new(self.transport, self.db_path.clone())
+    }
+
+    /// Returns roughly how many changes this database has accumulated since
+    /// the given update sequence—e.g., to monitor replication lag.
+    ///
+    /// The count is the `pending` field of a changes request with `limit=0`,
+    /// so no changes are transferred. The count is an estimate: on a clustered
+    /// server, it is summed across shards and may be inexact. The method fails
+    /// with `Error::UnexpectedResponse` if the server does not report a
+    /// pending count, as CouchDB 1.x doesn't.
+    ///
+    pub fn changes_count_since<S: Into<Since>>(&self, since: S) -> Result<u64, Error> {
+        let changes = try!(action::ReadChanges::new(self.transport, self.db_path.clone())
+            .with_since(since)
+            .with_limit(0)
+            .run());
+        changes.pending().ok_or(Error::UnexpectedResponse("The changes response has no pending count"))
+    }
+
+    /// Opens a continuous changes feed on this database and returns it
+    /// together with the update sequence it starts from.
+    ///
+    /// The starting sequence lets an application record where it began before
+    /// processing any changes. If `since` is `Since::Now`, then the method
+    /// first resolves it to the database's current update sequence, by
+    /// sending an HTTP request to `GET /db/_changes?since=now&limit=0`, and
+    /// the feed starts from that concrete sequence. If `since` is
+    /// `Since::Zero`, then the starting sequence is `0`.
+    ///
+    /// The feed is equivalent to executing the `ReadChanges` action with
+    /// `with_since` and then `into_continuous`—see its documentation for
+    /// details.
+    ///
+    pub fn open_changes<S: Into<Since>>(&self, since: S) -> Result<(Seq, action::ContinuousChanges<'a, T>), Error> {
+        let (seq, since) = match since.into() {
+            Since::Seq(seq) => (seq.clone(), Since::Seq(seq)),
+            Since::Zero => (Seq::from(0), Since::Zero),
+            Since::Now => {
+                let changes = try!(action::ReadChanges::new(self.transport, self.db_path.clone())
+                    .with_since(Since::Now)
+                    .with_limit(0)
+                    .run());
+                let seq = changes.last_seq().clone();
+                (seq.clone(), Since::Seq(seq))
+            }
+        };
+        let stream = action::ReadChanges::new(self.transport, self.db_path.clone()).with_since(since).into_continuous();
+        Ok((seq, stream))
+    }
+
+    /// Returns an iterator through this database's changes, starting from a
+    /// persisted checkpoint.
+    ///
+    /// If the checkpoint is `None`, then the iterator starts at the beginning
+    /// of the database's history. Each item pairs a change with the checkpoint
+    /// to persist _after_ the application has processed the change. Persisting
+    /// only after processing ensures that, after a crash, the application
+    /// resumes from the first change it did not finish processing.
+    ///
+    /// The iterator long-polls for new changes and reconnects after a dropped
+    /// connection—see `ReadChanges::into_continuous` and
+    /// `ReadChanges::with_resilient`. It blocks while waiting for new changes
+    /// and ends only after an error.
+    ///
+    /// # Examples
+    ///
+    /// The following program demonstrates a consumer that resumes where it
+    /// left off.
+    ///
+    /// ```no_run
+    /// extern crate chill;
+    ///
+    /// # fn load_checkpoint() -> Option<String> { None }
+    /// # fn save_checkpoint(_: &str) {}
+    /// # fn process(_: &chill::ChangeRow) {}
+    /// let client = chill::Client::new("http://localhost:5984").unwrap();
+    /// let db = client.database("/baseball").unwrap();
+    ///
+    /// for item in db.changes_since_stream(load_checkpoint()) {
+    ///     let (row, checkpoint) = item.unwrap();
+    ///     process(&row);
+    ///     save_checkpoint(&checkpoint);
+    /// }
+    /// ```
+    ///
+    pub fn changes_since_stream(&self, checkpoint: Option<String>) -> action::CheckpointedChanges<'a, T> {
+        action::ReadChanges::new(self.transport, self.db_path.clone())
+            .with_since(checkpoint.map(Since::from).unwrap_or(Since::Zero))
+            .with_resilient(true)
+            .into_continuous()
+            .into_checkpointed()
+    }
+
+    /// Builds an action to import documents into this database from a
+    /// newline-delimited JSON source.
+    pub fn import_ndjson<R>(&self, reader: R, batch_size: usize) -> action::ImportNdjson<'a, T, DatabasePath, R>
+        where R: std::io::Read
+    {
+        action::ImportNdjson::new(self.transport, self.db_path.clone(), reader, batch_size)
+    }
+
+    /// Builds an action to pull one batch of changes from the given source
+    /// database into this database.
+    ///
+    /// The source and this database may be on different servers, provided
+    /// both use the same kind of transport. See `PullReplication` for
+    /// details.
+    ///
+    pub fn pull_once(&self,
+                     source: &Database<'a, T>,
+                     batch_size: u64)
+                     -> action::PullReplication<'a, T, DatabasePath, DatabasePath> {
+        action::PullReplication::new(source.transport,
+                                     source.db_path.clone(),
+                                     self.transport,
+                                     self.db_path.clone(),
+                                     batch_size)
+    }
+
+    /// Builds an action to export all documents in this database as
+    /// newline-delimited JSON.
+    pub fn export_ndjson<W>(&self, writer: W) -> action::ExportNdjson<'a, T, DatabasePath, W>
+        where W: std::io::Write
+    {
+        action::ExportNdjson::new(self.transport, self.db_path.clone(), writer)
+    }
+
+    fn make_document_path<D: Into<DocumentId>>(&self, doc_id: D) -> DocumentPath {
+        DocumentPath::from((self.db_path.database_name().clone(), doc_id.into()))
+    }
+}
+
+/// Contains meta-information about a database.
+///
+/// The CouchDB server sends database info in response to the HTTP request
+/// `GET /db` and as part of the response to `POST /_dbs_info`. Chill decodes
+/// only a subset of the fields the server sends—other fields are ignored.
+///
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DatabaseInfo {
+    db_name: DatabaseName,
+    doc_count: u64,
+    doc_del_count: u64,
+    update_seq: Seq,
+    cluster: Option<ClusterInfo>,
+    partitioned: bool,
+    instance_start_time: Option<String>,
+}
+
+impl DatabaseInfo {
+    /// Returns the database's name.
+    pub fn database_name(&self) -> &DatabaseName {
+        &self.db_name
+    }
+
+    /// Returns the number of non-deleted documents in the database.
+    pub fn document_count(&self) -> u64 {
+        self.doc_count
+    }
+
+    /// Returns the number of deleted documents in the database.
+    pub fn deleted_document_count(&self) -> u64 {
+        self.doc_del_count
+    }
+
+    /// Returns the database's current update sequence.
+    pub fn update_sequence(&self) -> &Seq {
+        &self.update_seq
+    }
+
+    /// Returns the database's sharding and quorum configuration, if known.
+    ///
+    /// CouchDB 2.x and later send the configuration as the `cluster` field of
+    /// the database info. CouchDB 1.x does not, in which case the
+    /// configuration is `None`.
+    ///
+    pub fn cluster(&self) -> Option<&ClusterInfo> {
+        self.cluster.as_ref()
+    }
+
+    /// Returns whether the database is partitioned.
+    ///
+    /// The CouchDB server sends this as the `partitioned` field of the
+    /// `props` field of the database info. Servers that predate partitioned
+    /// databases don't send it, in which case the database is not
+    /// partitioned.
+    ///
+    pub fn is_partitioned(&self) -> bool {
+        self.partitioned
+    }
+
+    /// Returns the time the database was opened, if known, as an opaque
+    /// string.
+    ///
+    /// On CouchDB 1.x, the start time changes whenever the server restarts,
+    /// which invalidates update sequences cached by the application—see
+    /// `ReadChanges::with_restart_detection`. CouchDB 2.x and later always
+    /// send the placeholder `"0"`, so the start time can't be used to detect
+    /// restarts on those servers.
+    ///
+    pub fn instance_start_time(&self) -> Option<&str> {
+        self.instance_start_time.as_ref().map(|x| x.as_str())
+    }
+}
+
+/// Contains the sharding and quorum configuration of a clustered database.
+///
+/// `ClusterInfo` corresponds to the `cluster` field of the database info that
+/// CouchDB 2.x and later send—see `DatabaseInfo::cluster`.
+///
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ClusterInfo {
+    q: u64,
+    n: u64,
+    w: u64,
+    r: u64,
+}
+
+impl ClusterInfo {
+    /// Returns the number of shards (`q`).
+    pub fn shard_count(&self) -> u64 {
+        self.q
+    }
+
+    /// Returns the number of replicas of each shard (`n`).
+    pub fn replica_count(&self) -> u64 {
+        self.n
+    }
+
+    /// Returns the write quorum (`w`).
+    pub fn write_quorum(&self) -> u64 {
+        self.w
+    }
+
+    /// Returns the read quorum (`r`).
+    pub fn read_quorum(&self) -> u64 {
+        self.r
+    }
+}
+
+impl serde::Deserialize for ClusterInfo {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+        where D: serde::Deserializer
+    {
+        enum Field {
+            Ignored,
+            N,
+            Q,
+            R,
+            W,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+                where D: serde::Deserializer
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                        where E: serde::de::Error
+                    {
+                        match value {
+                            "n" => Ok(Field::N),
+                            "q" => Ok(Field::Q),
+                            "r" => Ok(Field::R),
+                            "w" => Ok(Field::W),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = ClusterInfo;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+                where V: serde::de::MapVisitor
+            {
+                let mut n = None;
+                let mut q = None;
+                let mut r = None;
+                let mut w = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::N) => {
+                            n = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Q) => {
+                            q = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::R) => {
+                            r = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::W) => {
+                            w = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde::de::impls::IgnoredAny>());
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                let n = match n {
+                    Some(x) => x,
+                    None => try!(visitor.missing_field("n")),
+                };
+
+                let q = match q {
+                    Some(x) => x,
+                    None => try!(visitor.missing_field("q")),
+                };
+
+                let r = match r {
+                    Some(x) => x,
+                    None => try!(visitor.missing_field("r")),
+                };
+
+                let w = match w {
+                    Some(x) => x,
+                    None => try!(visitor.missing_field("w")),
+                };
+
+                Ok(ClusterInfo {
+                    q: q,
+                    n: n,
+                    w: w,
+                    r: r,
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["n", "q", "r", "w"];
+        deserializer.deserialize_struct("ClusterInfo", FIELDS, Visitor)
+    }
+}
+
+impl serde::Deserialize for DatabaseInfo {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+        where D: serde::Deserializer
+    {
+        enum Field {
+            Cluster,
+            DbName,
+            DocCount,
+            DocDelCount,
+            Ignored,
+            InstanceStartTime,
+            Props,
+            UpdateSeq,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+                where D: serde::Deserializer
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                        where E: serde::de::Error
+                    {
+                        match value {
+                            "cluster" => Ok(Field::Cluster),
+                            "db_name" => Ok(Field::DbName),
+                            "doc_count" => Ok(Field::DocCount),
+                            "doc_del_count" => Ok(Field::DocDelCount),
+                            "instance_start_time" => Ok(Field::InstanceStartTime),
+                            "props" => Ok(Field::Props),
+                            "update_seq" => Ok(Field::UpdateSeq),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = DatabaseInfo;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+                where V: serde::de::MapVisitor
+            {
+                let mut cluster = None;
+                let mut db_name = None;
+                let mut doc_count = None;
+                let mut doc_del_count = None;
+                let mut update_seq = None;
+                let mut partitioned = false;
+                let mut instance_start_time = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Cluster) => {
+                            cluster = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::DbName) => {
+                            db_name = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::DocCount) => {
+                            doc_count = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::DocDelCount) => {
+                            doc_del_count = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::InstanceStartTime) => {
+                            instance_start_time = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Props) => {
+                            let props: std::collections::BTreeMap<String, serde_json::Value> =
+                                try!(visitor.visit_value());
+                            partitioned = props.get("partitioned").and_then(|x| x.as_bool()) == Some(true);
+                        }
+                        Some(Field::UpdateSeq) => {
+                            update_seq = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde::de::impls::IgnoredAny>());
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                let db_name = match db_name {
+                    Some(x) => x,
+                    None => try!(visitor.missing_field("db_name")),
+                };
+
+                let doc_count = match doc_count {
+                    Some(x) => x,
+                    None => try!(visitor.missing_field("doc_count")),
+                };
+
+                let doc_del_count = match doc_del_count {
+                    Some(x) => x,
+                    None => try!(visitor.missing_field("doc_del_count")),
+                };
+
+                let update_seq = match update_seq {
+                    Some(x) => x,
+                    None => try!(visitor.missing_field("update_seq")),
+                };
+
+                Ok(DatabaseInfo {
+                    db_name: db_name,
+                    doc_count: doc_count,
+                    doc_del_count: doc_del_count,
+                    update_seq: update_seq,
+                    cluster: cluster,
+                    partitioned: partitioned,
+                    instance_start_time: instance_start_time,
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["db_name", "doc_count", "doc_del_count", "update_seq"];
+        deserializer.deserialize_struct("DatabaseInfo", FIELDS, Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {DatabaseName, DocumentId, DocumentPath, Error, IndexDef, IntoDatabasePath, Revision, Seq, Since};
+    use serde_json;
+    use super::*;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn database_new_sends_no_request() {
+        // The mock transport panics if sent a request without a queued
+        // response.
+        let transport = MockTransport::new();
+        let db = Database::new(&transport, "/foo").unwrap();
+        assert_eq!(&"/foo".into_database_path().unwrap(), db.path());
+        assert_eq!(DocumentPath::from((DatabaseName::from("foo"), DocumentId::from("bar"))),
+                   db.make_document_path("bar"));
+    }
+
+    #[test]
+    fn database_open_ok() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok).unwrap());
+        let db = Database::open(&transport, "/foo").unwrap();
+        assert_eq!(&"/foo".into_database_path().unwrap(), db.path());
+    }
+
+    #[test]
+    fn database_open_nok_not_found() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::NotFound).unwrap());
+        match Database::open(&transport, "/foo").map(|db| db.path().clone()) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn database_delete_ok() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"ok":true}"#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        db.delete().unwrap();
+    }
+
+    #[test]
+    fn database_delete_nok_not_found() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Database does not exist."}"#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        match db.delete() {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn database_get_attachment_range_ok_range_ignored() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_raw_content(b"hello world".to_vec())
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        let got = db.get_attachment_range("bar", "baz", 6, None).unwrap();
+        assert_eq!(&b"hello world"[..], got.content());
+        assert!(!got.is_partial());
+        let expected = vec![transport.get(vec!["foo", "bar", "baz"]).with_raw_header("Range", "bytes=6-")];
+        assert_eq!(expected, transport.take_requests());
+    }
+
+    #[test]
+    fn database_resume_attachment_ok() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::PartialContent)
+            .with_raw_header("Content-Range", "bytes 6-10/11")
+            .with_raw_content(b"world".to_vec())
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        let got = db.resume_attachment("bar", "baz", 6).unwrap();
+        assert_eq!(&b"world"[..], got.content());
+        assert!(got.is_partial());
+        assert_eq!(6, got.offset());
+        assert_eq!(Some(11), got.total_length());
+        assert!(got.is_complete());
+        let expected = vec![transport.get(vec!["foo", "bar", "baz"]).with_raw_header("Range", "bytes=6-")];
+        assert_eq!(expected, transport.take_requests());
+    }
+
+    #[test]
+    fn database_is_current_revision_ok_match() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_raw_header("ETag", r#""2-1234567890abcdef1234567890abcdef""#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        let rev = Revision::parse("2-1234567890abcdef1234567890abcdef").unwrap();
+        assert!(db.is_current_revision("bar", &rev).unwrap());
+        assert_eq!(vec![transport.head(vec!["foo", "bar"])], transport.take_requests());
+    }
+
+    #[test]
+    fn database_is_current_revision_ok_mismatch() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_raw_header("ETag", r#""3-abcdef1234567890abcdef1234567890""#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        let rev = Revision::parse("2-1234567890abcdef1234567890abcdef").unwrap();
+        assert!(!db.is_current_revision("bar", &rev).unwrap());
+        assert_eq!(vec![transport.head(vec!["foo", "bar"])], transport.take_requests());
+    }
+
+    #[test]
+    fn database_is_current_revision_nok_not_found() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::NotFound).unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        let rev = Revision::parse("2-1234567890abcdef1234567890abcdef").unwrap();
+        match db.is_current_revision("bar", &rev) {
+            Err(Error::NotFound(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn database_get_document_with_raw_ok() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"_id":"bar","_rev":"1-1234567890abcdef1234567890abcdef","field":17}"#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        let (doc, raw) = db.get_document_with_raw("bar").unwrap();
+        assert_eq!(&Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(), doc.revision());
+        assert_eq!(Some("1-1234567890abcdef1234567890abcdef"),
+                   raw.find("_rev").and_then(|x| x.as_str()));
+        assert_eq!(Some(17), raw.find("field").and_then(|x| x.as_u64()));
+    }
+
+    #[test]
+    fn database_get_document_latest_ok() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"_id":"bar","_rev":"3-abcdef1234567890abcdef1234567890","field":17}"#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        let rev = Revision::parse("2-1234567890abcdef1234567890abcdef").unwrap();
+        let doc = db.get_document_latest("bar", &rev).unwrap();
+        assert_eq!(&Revision::parse("3-abcdef1234567890abcdef1234567890").unwrap(), doc.revision());
+        let expected = vec![transport.get(vec!["foo", "bar"])
+                                .with_accept_json()
+                                .with_query_literal("rev", "2-1234567890abcdef1234567890abcdef")
+                                .with_query_literal("latest", "true")];
+        assert_eq!(expected, transport.take_requests());
+    }
+
+    #[test]
+    fn database_get_or_create_document_ok_exists() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"_id":"config","_rev":"1-1234567890abcdef1234567890abcdef","field":17}"#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        let doc = db.get_or_create_document("config", &serde_json::Value::Null).unwrap();
+        assert_eq!(&Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(), doc.revision());
+    }
+
+    #[test]
+    fn database_get_or_create_document_ok_created() {
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::NotFound)
+                .with_json_content_raw(r#"{"error":"not_found","reason":"missing"}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Created)
+                .with_json_content_raw(r#"{"ok":true,"id":"config","rev":"1-1234567890abcdef1234567890abcdef"}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"_id":"config","_rev":"1-1234567890abcdef1234567890abcdef","field":42}"#)
+                .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        let default = serde_json::builder::ObjectBuilder::new().insert("field", 42).build();
+        let doc = db.get_or_create_document("config", &default).unwrap();
+        assert_eq!(default, doc.get_content::<serde_json::Value>().unwrap());
+    }
+
+    #[test]
+    fn database_get_or_create_document_ok_lost_race() {
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::NotFound)
+                .with_json_content_raw(r#"{"error":"not_found","reason":"missing"}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Conflict)
+                .with_json_content_raw(r#"{"error":"conflict","reason":"Document update conflict."}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"_id":"config","_rev":"1-abcdef1234567890abcdef1234567890","field":17}"#)
+                .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        let default = serde_json::builder::ObjectBuilder::new().insert("field", 42).build();
+        let doc = db.get_or_create_document("config", &default).unwrap();
+        assert_eq!(&Revision::parse("1-abcdef1234567890abcdef1234567890").unwrap(), doc.revision());
+    }
+
+    #[test]
+    fn database_get_or_create_document_nok_unauthorized() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(r#"{"error":"unauthorized","reason":"Authentication required."}"#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        match db.get_or_create_document("config", &serde_json::Value::Null) {
+            Err(Error::Unauthorized(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn database_info_deserialize_ok() {
+
+        let expected = DatabaseInfo {
+            db_name: DatabaseName::from("foo"),
+            doc_count: 17,
+            doc_del_count: 3,
+            update_seq: Seq::from(42),
+            cluster: None,
+            partitioned: false,
+            instance_start_time: None,
+        };
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("db_name", "foo")
+            .insert("doc_count", 17)
+            .insert("doc_del_count", 3)
+            .insert("update_seq", 42)
+            .insert("disk_size", 12345)
+            .build();
+
+        let source = serde_json::to_string(&source).unwrap();
+        let got = serde_json::from_str(&source).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn database_info_deserialize_ok_with_opaque_update_seq() {
+
+        let expected = DatabaseInfo {
+            db_name: DatabaseName::from("foo"),
+            doc_count: 17,
+            doc_del_count: 3,
+            update_seq: Seq::from("42-g1AAAABteJzLYWBgYMpgTmHgz8tPSTV0MDQy0TMx0TM0MdEzNjPRMwXpMgbpMWOA6gcAGVIKzw"),
+            cluster: None,
+            partitioned: false,
+            instance_start_time: None,
+        };
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("db_name", "foo")
+            .insert("doc_count", 17)
+            .insert("doc_del_count", 3)
+            .insert("update_seq",
+                    "42-g1AAAABteJzLYWBgYMpgTmHgz8tPSTV0MDQy0TMx0TM0MdEzNjPRMwXpMgbpMWOA6gcAGVIKzw")
+            .build();
+
+        let source = serde_json::to_string(&source).unwrap();
+        let got = serde_json::from_str(&source).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn database_info_deserialize_ok_with_cluster_and_props() {
+
+        let expected = DatabaseInfo {
+            db_name: DatabaseName::from("foo"),
+            doc_count: 17,
+            doc_del_count: 3,
+            update_seq: Seq::from("42-g1AAAABteJzLYWBgYMpgTmHgz8tPSTV0MDQy0TMx0TM0MdEzNjPRMwXpMgbpMWOA6gcAGVIKzw"),
+            cluster: Some(ClusterInfo {
+                q: 8,
+                n: 3,
+                w: 2,
+                r: 2,
+            }),
+            partitioned: true,
+            instance_start_time: Some(String::from("0")),
+        };
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("db_name", "foo")
+            .insert("doc_count", 17)
+            .insert("doc_del_count", 3)
+            .insert("update_seq",
+                    "42-g1AAAABteJzLYWBgYMpgTmHgz8tPSTV0MDQy0TMx0TM0MdEzNjPRMwXpMgbpMWOA6gcAGVIKzw")
+            .insert_object("cluster", |x| x.insert("q", 8).insert("n", 3).insert("w", 2).insert("r", 2))
+            .insert_object("props", |x| x.insert("partitioned", true))
+            .insert("instance_start_time", "0")
+            .insert_object("sizes", |x| x.insert("active", 1234).insert("external", 567).insert("file", 8901))
+            .build();
+
+        let source = serde_json::to_string(&source).unwrap();
+        let got = serde_json::from_str::<DatabaseInfo>(&source).unwrap();
+        assert_eq!(expected, got);
+
+        let cluster = got.cluster().unwrap();
+        assert_eq!(8, cluster.shard_count());
+        assert_eq!(3, cluster.replica_count());
+        assert_eq!(2, cluster.write_quorum());
+        assert_eq!(2, cluster.read_quorum());
+        assert!(got.is_partitioned());
+    }
+
+    #[test]
+    fn database_info_deserialize_ok_with_empty_props() {
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("db_name", "foo")
+            .insert("doc_count", 17)
+            .insert("doc_del_count", 3)
+            .insert("update_seq", "42-g1AAAA")
+            .insert_object("cluster", |x| x.insert("q", 2).insert("n", 1).insert("w", 1).insert("r", 1))
+            .insert_object("props", |x| x)
+            .build();
+
+        let source = serde_json::to_string(&source).unwrap();
+        let got = serde_json::from_str::<DatabaseInfo>(&source).unwrap();
+        assert!(!got.is_partitioned());
+        assert_eq!(2, got.cluster().unwrap().shard_count());
+    }
+
+    #[test]
+    fn database_info_deserialize_nok_missing_db_name() {
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("doc_count", 17)
+            .insert("doc_del_count", 3)
+            .insert("update_seq", 42)
+            .build();
+
+        let source = serde_json::to_string(&source).unwrap();
+        let got = serde_json::from_str::<DatabaseInfo>(&source);
+        expect_json_error_missing_field!(got, "db_name");
+    }
+
+    #[test]
+    fn database_read_checkpoint_ok() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"_id":"_local/sync-1","_rev":"0-3","last_seq":"42-g1AAAA"}"#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        assert_eq!(Some(Seq::from("42-g1AAAA")), db.read_checkpoint("sync-1").unwrap());
+        let expected = vec![transport.get(vec!["foo", "_local", "sync-1"]).with_accept_json()];
+        assert_eq!(expected, transport.take_requests());
+    }
+
+    #[test]
+    fn database_read_checkpoint_ok_missing() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"missing"}"#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        assert_eq!(None, db.read_checkpoint("sync-1").unwrap());
+    }
+
+    #[test]
+    fn database_write_checkpoint_ok_created() {
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::NotFound)
+                .with_json_content_raw(r#"{"error":"not_found","reason":"missing"}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Created)
+                .with_json_content_raw(r#"{"ok":true,"id":"_local/sync-1","rev":"0-1"}"#)
+                .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        db.write_checkpoint("sync-1", &Seq::from(7)).unwrap();
+        let requests = transport.take_requests();
+        assert_eq!(2, requests.len());
+        let expected = transport.put(vec!["foo", "_local", "sync-1"])
+            .with_accept_json()
+            .with_json_content(&serde_json::builder::ObjectBuilder::new().insert("last_seq", 7).build())
+            .unwrap();
+        assert_eq!(expected, requests[1]);
+    }
+
+    #[test]
+    fn database_write_checkpoint_ok_updated() {
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"_id":"_local/sync-1","_rev":"0-3","last_seq":5,"source":"alpha"}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Created)
+                .with_json_content_raw(r#"{"ok":true,"id":"_local/sync-1","rev":"0-4"}"#)
+                .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        db.write_checkpoint("sync-1", &Seq::from("9-g1AAAA")).unwrap();
+        let requests = transport.take_requests();
+        assert_eq!(2, requests.len());
+        let expected = transport.put(vec!["foo", "_local", "sync-1"])
+            .with_accept_json()
+            .with_json_content(&serde_json::builder::ObjectBuilder::new()
+                .insert("last_seq", "9-g1AAAA")
+                .insert("source", "alpha")
+                .build())
+            .unwrap()
+            .with_query_literal("rev", "0-3");
+        assert_eq!(expected, requests[1]);
+    }
+
+    #[test]
+    fn database_count_documents_ok() {
+        let make_transport = || {
+            MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"db_name":"foo","doc_count":17,"doc_del_count":5,"update_seq":42}"#)
+                .unwrap())
+        };
+
+        let transport = make_transport();
+        let db = Database::new(&transport, "/foo").unwrap();
+        assert_eq!(17, db.count_documents().unwrap());
+        assert_eq!(vec![transport.get(vec!["foo"]).with_accept_json()], transport.take_requests());
+
+        let transport = make_transport();
+        let db = Database::new(&transport, "/foo").unwrap();
+        assert_eq!(22, db.count_including_deleted().unwrap());
+    }
+
+    #[test]
+    fn database_put_attachment_stream_ok() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Created)
+            .with_json_content_raw(r#"{"ok":true,"id":"bar","rev":"1-1234567890abcdef1234567890abcdef"}"#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        let got = db.put_attachment_stream("bar", None, "baz", mime!(Text / Plain), &b"hello"[..], None).unwrap();
+        assert_eq!(Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(), got);
+        let expected = transport.put(vec!["foo", "bar", "baz"])
+            .with_accept_json()
+            .with_content(mime!(Text / Plain), b"hello".to_vec());
+        assert_eq!(vec![expected], transport.take_requests());
+    }
+
+    #[test]
+    fn database_changes_count_since_ok() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"results":[],"last_seq":"42-g1AAAA","pending":17}"#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        assert_eq!(17, db.changes_count_since("40-g1AAAA").unwrap());
+        let expected = transport.get(vec!["foo", "_changes"])
+            .with_accept_json()
+            .with_query_literal("since", "40-g1AAAA")
+            .with_query_literal("limit", "0");
+        assert_eq!(vec![expected], transport.take_requests());
+    }
+
+    #[test]
+    fn database_changes_count_since_nok_no_pending() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"results":[],"last_seq":42}"#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        match db.changes_count_since(40) {
+            Err(Error::UnexpectedResponse(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn database_indexes_then_drop_undeclared() {
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"total_rows": 2, "indexes": [
+                    {"ddoc": null, "name": "_all_docs", "type": "special", "def": {"fields": [{"_id": "asc"}]}},
+                    {"ddoc": "_design/stale", "name": "stale-index", "type": "json",
+                     "def": {"fields": [{"old": "asc"}]}}]}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"ok":true}"#)
+                .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        for index in db.indexes().unwrap().iter().filter(|x| !x.is_built_in()) {
+            db.drop_index(index.design_document().unwrap(), index.name()).unwrap();
+        }
+        let expected = vec![transport.get(vec!["foo", "_index"]).with_accept_json(),
+                            transport.delete(vec!["foo", "_index", "stale", "json", "stale-index"]).with_accept_json()];
+        assert_eq!(expected, transport.take_requests());
+    }
+
+    #[test]
+    fn database_ensure_index_created_then_exists() {
+        let transport = MockTransport::new()
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"result":"created","id":"_design/by-type","name":"type-index"}"#)
+                .unwrap())
+            .with_response(JsonResponseBuilder::new(StatusCode::Ok)
+                .with_json_content_raw(r#"{"result":"exists","id":"_design/by-type","name":"type-index"}"#)
+                .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        let index = IndexDef::new(vec!["type"]).with_name("type-index").with_design_document("by-type");
+
+        let got = db.ensure_index(index.clone()).unwrap();
+        assert!(got.is_created());
+        assert_eq!("_design/by-type", got.design_document());
+
+        let got = db.ensure_index(index).unwrap();
+        assert!(!got.is_created());
+        assert_eq!("type-index", got.name());
+
+        let requests = transport.take_requests();
+        assert_eq!(2, requests.len());
+        assert_eq!(requests[0], requests[1]);
+    }
+
+    #[test]
+    fn database_drop_index_ok() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"ok":true}"#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        db.drop_index("_design/by-type", "type-index").unwrap();
+        let expected = transport.delete(vec!["foo", "_index", "by-type", "json", "type-index"]).with_accept_json();
+        assert_eq!(vec![expected], transport.take_requests());
+    }
+
+    #[test]
+    fn database_drop_index_nok_not_found() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Index not found"}"#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        match db.drop_index("by-type", "type-index") {
+            Err(Error::NotFound(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn database_drop_index_nok_all_docs() {
+        let transport = MockTransport::new();
+        let db = Database::new(&transport, "/foo").unwrap();
+        match db.drop_index("", "_all_docs") {
+            Err(Error::InvalidOptions(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+        assert!(transport.take_requests().is_empty());
+    }
+
+    #[test]
+    fn database_revs_limit_ok() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw("1000")
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        assert_eq!(1000, db.revs_limit().unwrap());
+        let expected = transport.get(vec!["foo", "_revs_limit"]).with_accept_json();
+        assert_eq!(vec![expected], transport.take_requests());
+    }
+
+    #[test]
+    fn database_open_changes_resolves_now() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"results":[],"last_seq":"42-g1AAAA"}"#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        let (seq, stream) = db.open_changes(Since::Now).unwrap();
+        assert_eq!(Seq::from("42-g1AAAA"), seq);
+        assert_eq!(&Since::Seq(seq), stream.since());
+        let expected = transport.get(vec!["foo", "_changes"])
+            .with_accept_json()
+            .with_query_literal("since", "now")
+            .with_query_literal("limit", "0");
+        assert_eq!(vec![expected], transport.take_requests());
+    }
+
+    #[test]
+    fn database_open_changes_from_seq_sends_nothing() {
+        let transport = MockTransport::new();
+        let db = Database::new(&transport, "/foo").unwrap();
+        let (seq, stream) = db.open_changes(17).unwrap();
+        assert_eq!(Seq::from(17), seq);
+        assert_eq!(&Since::from(17), stream.since());
+        let (seq, _) = db.open_changes(Since::Zero).unwrap();
+        assert_eq!(Seq::from(0), seq);
+        assert!(transport.take_requests().is_empty());
+    }
+
+    #[test]
+    fn database_docs_with_prefix() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"total_rows": 4, "offset": 1, "rows": [
+                {"id": "user:alice", "key": "user:alice", "value": {"rev": "1-1234567890abcdef1234567890abcdef"}},
+                {"id": "user:bob", "key": "user:bob", "value": {"rev": "1-1234567890abcdef1234567890abcdef"}},
+                {"id": "users", "key": "users", "value": {"rev": "1-1234567890abcdef1234567890abcdef"}}]}"#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        let got = db.docs_with_prefix("user:", false).unwrap();
+        let got = got.iter().map(|x| x.document_path().unwrap().document_id().to_string()).collect::<Vec<_>>();
+        assert_eq!(vec!["user:alice", "user:bob"], got);
+        let expected = transport.get(vec!["foo", "_all_docs"])
+            .with_accept_json()
+            .with_query_literal("startkey", r#""user:""#)
+            .with_query_literal("endkey", "\"user:\u{fff0}\"");
+        assert_eq!(vec![expected], transport.take_requests());
+    }
+
+    #[test]
+    fn database_server_error_has_request_context() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::InternalServerError)
+            .with_json_content_raw(r#"{"error":"unknown_error","reason":"function_clause"}"#)
+            .unwrap());
+        let db = Database::new(&transport, "/foo").unwrap();
+        match db.revs_limit() {
+            Err(ref e @ Error::ServerResponse { .. }) => {
+                assert_eq!(Some(("GET", "/foo/_revs_limit")), e.request_context());
+            }
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
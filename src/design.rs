@@ -1,4 +1,4 @@
-use {ViewName, serde, std};
+use {DesignDocumentPath, Error, Revision, ViewName, serde, std};
 
 /// Container for a _map_ and optional _reduce_ function of a view.
 ///
@@ -58,6 +58,195 @@ impl ViewFunction {
             _dummy: std::marker::PhantomData,
         }
     }
+
+    /// Returns a copy of the view function that has the given _map function_.
+    pub fn with_map<M: Into<String>>(mut self, map: M) -> Self {
+        self.map = map.into();
+        self
+    }
+
+    /// Returns a copy of the view function that has the given _reduce
+    /// function_, replacing any existing one.
+    pub fn with_reduce<R: Into<String>>(mut self, reduce: R) -> Self {
+        self.reduce = Some(reduce.into());
+        self
+    }
+
+    /// Returns a copy of the view function that has no _reduce function_.
+    pub fn without_reduce(mut self) -> Self {
+        self.reduce = None;
+        self
+    }
+
+    /// Checks the view's functions for common copy-paste errors before the
+    /// view is deployed.
+    ///
+    /// Otherwise, a broken function fails only when the server builds the
+    /// view's index, and the error is easy to miss. The check is a heuristic,
+    /// _not_ a JavaScript validator: it checks only that each function is
+    /// nonempty, contains the `function` keyword, has balanced braces,
+    /// brackets, and parentheses outside of strings and comments, and has a
+    /// nonempty body. A function that passes may still have a syntax error,
+    /// and a function containing a regular expression literal with an
+    /// unbalanced bracket or a quote may fail even though it's valid.
+    ///
+    /// Built-in reduce functions, such as `_sum`, are not checked.
+    ///
+    /// # Errors
+    ///
+    /// The check fails with `Error::ViewFunctionSyntax` for the first problem
+    /// it finds.
+    ///
+    pub fn validate_syntax(&self) -> Result<(), Error> {
+        try!(check_function_syntax(&self.map).map_err(|x| Error::ViewFunctionSyntax(x.describe("map"))));
+        match self.reduce {
+            Some(ref reduce) if !reduce.trim().starts_with('_') => {
+                check_function_syntax(reduce).map_err(|x| Error::ViewFunctionSyntax(x.describe("reduce")))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum SyntaxProblem {
+    Empty,
+    EmptyBody,
+    NoFunctionKeyword,
+    Unbalanced,
+    UnterminatedComment,
+    UnterminatedString,
+}
+
+impl SyntaxProblem {
+    fn describe(&self, function_kind: &'static str) -> &'static str {
+        match (self, function_kind == "map") {
+            (&SyntaxProblem::Empty, true) => "The map function is empty",
+            (&SyntaxProblem::Empty, false) => "The reduce function is empty",
+            (&SyntaxProblem::EmptyBody, true) => "The map function has an empty body",
+            (&SyntaxProblem::EmptyBody, false) => "The reduce function has an empty body",
+            (&SyntaxProblem::NoFunctionKeyword, true) => "The map function has no 'function' keyword",
+            (&SyntaxProblem::NoFunctionKeyword, false) => "The reduce function has no 'function' keyword",
+            (&SyntaxProblem::Unbalanced, true) => "The map function has unbalanced braces, brackets, or parentheses",
+            (&SyntaxProblem::Unbalanced, false) => {
+                "The reduce function has unbalanced braces, brackets, or parentheses"
+            }
+            (&SyntaxProblem::UnterminatedComment, true) => "The map function has an unterminated comment",
+            (&SyntaxProblem::UnterminatedComment, false) => "The reduce function has an unterminated comment",
+            (&SyntaxProblem::UnterminatedString, true) => "The map function has an unterminated string",
+            (&SyntaxProblem::UnterminatedString, false) => "The reduce function has an unterminated string",
+        }
+    }
+}
+
+// Scans the function's source, skipping strings and comments, and tracks the
+// nesting of braces, brackets, and parentheses. The body is the first brace
+// block after the `function` keyword, and it must contain something other
+// than whitespace and comments.
+fn check_function_syntax(source: &str) -> Result<(), SyntaxProblem> {
+
+    fn is_identifier_char(c: char) -> bool {
+        c.is_alphanumeric() || c == '_' || c == '$'
+    }
+
+    if source.trim().is_empty() {
+        return Err(SyntaxProblem::Empty);
+    }
+
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut stack = Vec::new();
+    let mut found_function = false;
+    let mut body_depth = None;
+    let mut body_has_content = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).cloned();
+        let in_body = body_depth.is_some() && !body_has_content;
+        match c {
+            '/' if next == Some('/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            '/' if next == Some('*') => {
+                i += 2;
+                loop {
+                    if i + 1 >= chars.len() {
+                        return Err(SyntaxProblem::UnterminatedComment);
+                    }
+                    if chars[i] == '*' && chars[i + 1] == '/' {
+                        break;
+                    }
+                    i += 1;
+                }
+                i += 2;
+                continue;
+            }
+            '"' | '\'' | '`' => {
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(SyntaxProblem::UnterminatedString),
+                        Some(&'\\') => i += 2,
+                        Some(&x) if x == c => break,
+                        Some(_) => i += 1,
+                    }
+                }
+                body_has_content = body_has_content || in_body;
+            }
+            '(' | '[' | '{' => {
+                if c == '{' && found_function && body_depth.is_none() {
+                    body_depth = Some(stack.len());
+                } else {
+                    body_has_content = body_has_content || in_body;
+                }
+                stack.push(c);
+            }
+            ')' | ']' | '}' => {
+                let expected = match c {
+                    ')' => '(',
+                    ']' => '[',
+                    _ => '{',
+                };
+                if stack.pop() != Some(expected) {
+                    return Err(SyntaxProblem::Unbalanced);
+                }
+                if c == '}' && body_depth == Some(stack.len()) && !body_has_content {
+                    return Err(SyntaxProblem::EmptyBody);
+                }
+                body_has_content = body_has_content || in_body;
+            }
+            c if c.is_whitespace() => (),
+            c if is_identifier_char(c) => {
+                let start = i;
+                while i < chars.len() && is_identifier_char(chars[i]) {
+                    i += 1;
+                }
+                if chars[start..i].iter().cloned().collect::<String>() == "function" {
+                    found_function = true;
+                }
+                body_has_content = body_has_content || in_body;
+                continue;
+            }
+            _ => {
+                body_has_content = body_has_content || in_body;
+            }
+        }
+        i += 1;
+    }
+
+    if !stack.is_empty() {
+        return Err(SyntaxProblem::Unbalanced);
+    }
+
+    if !found_function {
+        return Err(SyntaxProblem::NoFunctionKeyword);
+    }
+
+    Ok(())
 }
 
 impl serde::Deserialize for ViewFunction {
@@ -158,8 +347,8 @@ impl serde::Serialize for ViewFunction {
 /// design documents.
 ///
 /// Currently, `Design` supports only the `views` field of a design document.
-/// For more information about design documents, please see the CouchDB
-/// documentation.
+/// Other fields, such as `language`, are ignored when decoding. For more
+/// information about design documents, please see the CouchDB documentation.
 ///
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Design {
@@ -176,6 +365,7 @@ impl serde::Deserialize for Design {
         where D: serde::Deserializer
     {
         enum Field {
+            Ignored,
             Views,
         }
 
@@ -193,7 +383,7 @@ impl serde::Deserialize for Design {
                     {
                         match value {
                             "views" => Ok(Field::Views),
-                            _ => Err(E::unknown_field(value)),
+                            _ => Ok(Field::Ignored),
                         }
                     }
                 }
@@ -214,6 +404,9 @@ impl serde::Deserialize for Design {
 
                 loop {
                     match try!(visitor.visit_key()) {
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde::de::impls::IgnoredAny>());
+                        }
                         Some(Field::Views) => {
                             views = Some(try!(visitor.visit_value()));
                         }
@@ -252,11 +445,67 @@ impl serde::Serialize for Design {
     }
 }
 
+/// Identifies a design document within a database, as listed by the
+/// `ReadDesignDocuments` action.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DesignDocumentRow {
+    ddoc_path: DesignDocumentPath,
+    revision: Revision,
+    design: Option<Design>,
+}
+
+impl DesignDocumentRow {
+    #[doc(hidden)]
+    pub fn new(ddoc_path: DesignDocumentPath, revision: Revision, design: Option<Design>) -> Self {
+        DesignDocumentRow {
+            ddoc_path: ddoc_path,
+            revision: revision,
+            design: design,
+        }
+    }
+
+    /// Returns the design document's path.
+    pub fn path(&self) -> &DesignDocumentPath {
+        &self.ddoc_path
+    }
+
+    /// Returns the design document's current revision.
+    pub fn revision(&self) -> &Revision {
+        &self.revision
+    }
+
+    /// Returns the design document's content, if the action read documents—see
+    /// `ReadDesignDocuments::with_documents`.
+    pub fn design(&self) -> Option<&Design> {
+        self.design.as_ref()
+    }
+}
+
 /// Builder for a design document's content.
 ///
 /// `Builder` is a convenience type for applications that create new design
-/// documents. For more information about design documents, please see the
-/// CouchDB documentation.
+/// documents or edit existing ones. For more information about design
+/// documents, please see the CouchDB documentation.
+///
+/// # Examples
+///
+/// ```
+/// extern crate chill;
+///
+/// let design = chill::DesignBuilder::new()
+///     .insert_view("alpha", chill::ViewFunction::new("function(doc) { emit(doc.a); }"))
+///     .insert_view("bravo", chill::ViewFunction::new("function(doc) { emit(doc.b); }"))
+///     .unwrap();
+///
+/// let design = chill::DesignBuilder::from_design(design)
+///     .remove_view("alpha")
+///     .insert_view("charlie", chill::ViewFunction::new("function(doc) { emit(doc.c); }"))
+///     .unwrap();
+///
+/// let mut view_names = design.views.keys().map(|x| x.to_string()).collect::<Vec<_>>();
+/// view_names.sort();
+/// assert_eq!(vec!["bravo", "charlie"], view_names);
+/// ```
 ///
 #[derive(Debug)]
 pub struct DesignBuilder {
@@ -274,6 +523,13 @@ impl DesignBuilder {
         }
     }
 
+    /// Constructs a new builder containing the given design document
+    /// content—e.g., content read from the server, to be edited and written
+    /// back.
+    pub fn from_design(design: Design) -> Self {
+        DesignBuilder { inner: design }
+    }
+
     /// Returns the builder's design document content.
     pub fn unwrap(self) -> Design {
         self.inner
@@ -286,13 +542,27 @@ impl DesignBuilder {
         self.inner.views.insert(view_name.into(), view_function);
         self
     }
+
+    /// Removes a view, if it exists, from the design document content.
+    pub fn remove_view<V>(mut self, view_name: V) -> Self
+        where V: Into<ViewName>
+    {
+        self.inner.views.remove(&view_name.into());
+        self
+    }
+}
+
+impl From<Design> for DesignBuilder {
+    fn from(design: Design) -> Self {
+        DesignBuilder::from_design(design)
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use {serde_json, std};
+    use {Error, serde_json, std};
 
     #[test]
     fn view_function_new() {
@@ -327,6 +597,24 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn view_function_with_map_and_reduce() {
+
+        let original = ViewFunction::new_with_reduce("function(doc) { emit(doc.alpha); }", "_count");
+
+        let got = original.clone().with_map("function(doc) { emit(doc.bravo); }");
+        let expected = ViewFunction::new_with_reduce("function(doc) { emit(doc.bravo); }", "_count");
+        assert_eq!(expected, got);
+
+        let got = original.clone().with_reduce("_sum");
+        let expected = ViewFunction::new_with_reduce("function(doc) { emit(doc.alpha); }", "_sum");
+        assert_eq!(expected, got);
+
+        let got = original.without_reduce();
+        let expected = ViewFunction::new("function(doc) { emit(doc.alpha); }");
+        assert_eq!(expected, got);
+    }
+
     #[test]
     fn view_function_serialize_without_reduce() {
 
@@ -403,6 +691,73 @@ mod tests {
         expect_json_error_missing_field!(got, "map");
     }
 
+    #[test]
+    fn view_function_validate_syntax_ok() {
+        let view_function = ViewFunction::new_with_reduce(r#"function(doc) {
+                // Skip documents without a {type}.
+                if (doc.type === "}") { emit([doc.type, doc.n], 1); }
+            }"#,
+                                                          "_sum");
+        view_function.validate_syntax().unwrap();
+
+        let view_function = ViewFunction::new_with_reduce("function(doc) { emit(doc._id, null); }",
+                                                          "function(keys, values, rereduce) { return sum(values); }");
+        view_function.validate_syntax().unwrap();
+    }
+
+    #[test]
+    fn view_function_validate_syntax_nok_unbalanced_braces() {
+        let view_function = ViewFunction::new("function(doc) { if (doc.type) { emit(doc._id, null); }");
+        match view_function.validate_syntax() {
+            Err(Error::ViewFunctionSyntax(x)) if x.contains("unbalanced") => (),
+            x @ _ => unexpected_result!(x),
+        }
+
+        let view_function = ViewFunction::new("function(doc) { emit(doc._id, null); }")
+            .with_reduce("function(keys, values) { return sum(values); ");
+        match view_function.validate_syntax() {
+            Err(Error::ViewFunctionSyntax(x)) if x.starts_with("The reduce function") => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn view_function_validate_syntax_nok_other_problems() {
+        assert_eq!(Err(SyntaxProblem::Empty), check_function_syntax("  "));
+        assert_eq!(Err(SyntaxProblem::NoFunctionKeyword), check_function_syntax("emit(doc._id, null);"));
+        assert_eq!(Err(SyntaxProblem::EmptyBody),
+                   check_function_syntax("function(doc) { /* TODO */ }"));
+        assert_eq!(Err(SyntaxProblem::UnterminatedString),
+                   check_function_syntax(r#"function(doc) { emit("foo, null); }"#));
+        assert_eq!(Err(SyntaxProblem::UnterminatedComment),
+                   check_function_syntax("function(doc) { emit(doc._id, null); /* }"));
+    }
+
+    #[test]
+    fn design_builder_from_design() {
+
+        let original = DesignBuilder::new()
+            .insert_view("alpha", ViewFunction::new("function(doc) { emit(doc.alpha); }"))
+            .insert_view("bravo", ViewFunction::new("function(doc) { emit(doc.bravo); }"))
+            .unwrap();
+
+        let got = DesignBuilder::from_design(original.clone()).unwrap();
+        assert_eq!(original, got);
+
+        let expected = DesignBuilder::new()
+            .insert_view("bravo", ViewFunction::new("function(doc) { emit(doc.bravo); }"))
+            .insert_view("charlie", ViewFunction::new_with_reduce("function(doc) { emit(doc.charlie); }", "_count"))
+            .unwrap();
+
+        let got = DesignBuilder::from(original)
+            .remove_view("alpha")
+            .remove_view("does_not_exist")
+            .insert_view("charlie", ViewFunction::new_with_reduce("function(doc) { emit(doc.charlie); }", "_count"))
+            .unwrap();
+
+        assert_eq!(expected, got);
+    }
+
     #[test]
     fn design_serialize() {
 
@@ -443,6 +798,19 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn design_deserialize_ok_ignores_other_fields() {
+        let expected = DesignBuilder::new().unwrap();
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("_id", "_design/foo")
+            .insert("_rev", "1-1234567890abcdef1234567890abcdef")
+            .insert("language", "javascript")
+            .build();
+        let source = serde_json::to_string(&source).unwrap();
+        let got = serde_json::from_str(&source).unwrap();
+        assert_eq!(expected, got);
+    }
+
     #[test]
     fn design_deserialize_ok_with_views() {
 
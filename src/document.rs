@@ -1,4 +1,5 @@
-use {Attachment, AttachmentName, AttachmentPath, DatabaseName, DocumentId, DocumentPath, Error, Revision};
+use {Attachment, AttachmentName, AttachmentPath, DatabaseName, DocumentId, DocumentPath, Error, Revision,
+     RevisionInfo};
 use IntoDocumentPath;
 use attachment::AttachmentBuilder;
 use {mime, serde, serde_json, std};
@@ -14,6 +15,8 @@ pub struct Document {
     doc_path: DocumentPath,
     revision: Revision,
     deleted: bool,
+    conflicts: Vec<Revision>,
+    revisions_info: Vec<RevisionInfo>,
     attachments: std::collections::HashMap<AttachmentName, Attachment>,
     content: serde_json::Value,
 }
@@ -25,6 +28,8 @@ impl Document {
             doc_path: DocumentPath::from((db_name, doc.doc_id)),
             revision: doc.revision,
             deleted: doc.deleted,
+            conflicts: doc.conflicts,
+            revisions_info: doc.revisions_info,
             attachments: doc.attachments,
             content: doc.content,
         }
@@ -39,6 +44,20 @@ impl Document {
         &self.revision
     }
 
+    /// Returns a copy of the document pegged to the given revision.
+    ///
+    /// The `UpdateDocument` action sends the document's revision with the
+    /// update, and the server rejects the update as a conflict unless the
+    /// revision is current. A `Document` always has a revision, so an update
+    /// can't accidentally create a new document. The `with_revision` method
+    /// lets the application peg the update to a revision it learned
+    /// elsewhere—e.g., from a changes feed or from a previous write.
+    ///
+    pub fn with_revision(mut self, revision: Revision) -> Self {
+        self.revision = revision;
+        self
+    }
+
     /// Returns `true` if and only if the document is deleted.
     ///
     /// Normally, the CouchDB server returns a `NotFound` error if the
@@ -51,10 +70,37 @@ impl Document {
         self.deleted
     }
 
+    /// Returns the document's conflicting revisions, if any.
+    ///
+    /// The CouchDB server sends conflicting revisions only if the application
+    /// explicitly requests them when reading the document—see the
+    /// `ReadDocument::with_conflicts` method. Otherwise, the vector is empty.
+    ///
+    pub fn conflicts(&self) -> &Vec<Revision> {
+        &self.conflicts
+    }
+
+    /// Returns the availability of the document's past revisions, if known.
+    ///
+    /// The CouchDB server sends revision info only if the application
+    /// explicitly requests it when reading the document—see the
+    /// `ReadDocument::with_revisions_info` method. Otherwise, the vector is
+    /// empty.
+    ///
+    pub fn revisions_info(&self) -> &Vec<RevisionInfo> {
+        &self.revisions_info
+    }
+
     /// Decodes and returns the document content, from a JSON object into a Rust
     /// type.
+    ///
+    /// Integers in the range of `i64` or `u64` decode exactly—including those
+    /// beyond 2<sup>53</sup>, which a JavaScript client would round—whether
+    /// the Rust type is an integer type or `serde_json::Value`. Larger integers
+    /// decode as `f64` and lose precision—store them as strings instead.
+    ///
     pub fn get_content<C: serde::Deserialize>(&self) -> Result<C, Error> {
-        serde_json::from_value(self.content.clone()).map_err(|e| Error::JsonDecode { cause: e })
+        serde_json::from_value(self.content.clone()).map_err(|e| Error::JsonDecode { cause: Box::new(e) })
     }
 
     /// Encodes the document content, from a Rust type into a JSON object.
@@ -166,7 +212,7 @@ impl<'a> Iterator for AttachmentIter<'a> {
 mod document_tests {
 
     use super::*;
-    use {AttachmentName, Error, IntoDocumentPath, Revision};
+    use {AttachmentName, DatabaseName, Error, IntoDocumentPath, Revision};
     use attachment::AttachmentBuilder;
     use {base64, serde_json, std};
 
@@ -182,6 +228,8 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: "1-1234567890abcdef1234567890abcdef".parse().unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: content.clone(),
         };
@@ -191,6 +239,25 @@ mod document_tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn get_content_ok_large_integers_round_trip() {
+
+        let source = r#"{"_id":"document_id","_rev":"1-1234567890abcdef1234567890abcdef",
+                         "big":9007199254740993,"negative":-9007199254740993}"#;
+        let doc = Document::new_from_decoded(DatabaseName::from("database"), serde_json::from_str(source).unwrap());
+
+        let got: std::collections::BTreeMap<String, i64> = doc.get_content().unwrap();
+        assert_eq!(Some(&9007199254740993), got.get("big"));
+        assert_eq!(Some(&-9007199254740993), got.get("negative"));
+
+        let got: serde_json::Value = doc.get_content().unwrap();
+        assert_eq!(Some(9007199254740993), got.find("big").and_then(|x| x.as_u64()));
+
+        let encoded = serde_json::to_string(&doc).unwrap();
+        assert!(encoded.contains(r#""big":9007199254740993"#));
+        assert!(encoded.contains(r#""negative":-9007199254740993"#));
+    }
+
     #[test]
     fn get_content_ok_document_is_deleted() {
 
@@ -200,6 +267,8 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: "1-1234567890abcdef1234567890abcdef".parse().unwrap(),
             deleted: true,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: content.clone(),
         };
@@ -216,6 +285,8 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: "1-1234567890abcdef1234567890abcdef".parse().unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -233,6 +304,8 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: true, // This value should have no effect.
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -250,6 +323,8 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: true, // This value should have no effect.
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: {
                 let mut m = std::collections::HashMap::new();
                 m.insert(AttachmentName::from("attachment_1"),
@@ -304,6 +379,8 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: {
                 let mut m = std::collections::HashMap::new();
                 m.insert(AttachmentName::from("attachment_1"), attachment_1.clone());
@@ -323,6 +400,8 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -338,6 +417,8 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -351,6 +432,8 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: {
                 let mut m = std::collections::HashMap::new();
                 m.insert(AttachmentName::from("foo"),
@@ -373,6 +456,8 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -386,6 +471,8 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: {
                 let mut m = std::collections::HashMap::new();
                 m.insert(AttachmentName::from("foo"),
@@ -406,6 +493,8 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: {
                 let mut m = std::collections::HashMap::new();
                 m.insert(AttachmentName::from("foo"),
@@ -421,6 +510,8 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -435,6 +526,8 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: {
                 let mut m = std::collections::HashMap::new();
                 m.insert(AttachmentName::from("foo"),
@@ -450,6 +543,8 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: {
                 let mut m = std::collections::HashMap::new();
                 m.insert(AttachmentName::from("foo"),
@@ -478,6 +573,8 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: attachments.clone(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -497,6 +594,8 @@ pub struct JsonDecodableDocument {
     pub doc_id: DocumentId,
     pub revision: Revision,
     pub deleted: bool,
+    pub conflicts: Vec<Revision>,
+    pub revisions_info: Vec<RevisionInfo>,
     pub attachments: std::collections::HashMap<AttachmentName, Attachment>,
     pub content: serde_json::Value,
 }
@@ -507,10 +606,12 @@ impl serde::Deserialize for JsonDecodableDocument {
     {
         enum Field {
             Attachments,
+            Conflicts,
             Content(String),
             Deleted,
             Id,
             Rev,
+            RevsInfo,
         }
 
         impl serde::Deserialize for Field {
@@ -527,9 +628,11 @@ impl serde::Deserialize for JsonDecodableDocument {
                     {
                         match value {
                             "_attachments" => Ok(Field::Attachments),
+                            "_conflicts" => Ok(Field::Conflicts),
                             "_deleted" => Ok(Field::Deleted),
                             "_id" => Ok(Field::Id),
                             "_rev" => Ok(Field::Rev),
+                            "_revs_info" => Ok(Field::RevsInfo),
                             _ => Ok(Field::Content(value.to_string())),
                         }
                     }
@@ -548,9 +651,11 @@ impl serde::Deserialize for JsonDecodableDocument {
                 where V: serde::de::MapVisitor
             {
                 let mut attachments = None;
+                let mut conflicts = None;
                 let mut deleted = None;
                 let mut id = None;
                 let mut revision = None;
+                let mut revs_info = None;
                 let mut content_builder = serde_json::builder::ObjectBuilder::new();
 
                 loop {
@@ -558,6 +663,9 @@ impl serde::Deserialize for JsonDecodableDocument {
                         Some(Field::Attachments) => {
                             attachments = Some(try!(visitor.visit_value()));
                         }
+                        Some(Field::Conflicts) => {
+                            conflicts = Some(try!(visitor.visit_value()));
+                        }
                         Some(Field::Content(name)) => {
                             let value = Some(try!(visitor.visit_value::<serde_json::Value>()));
                             content_builder = content_builder.insert(name, value);
@@ -571,6 +679,9 @@ impl serde::Deserialize for JsonDecodableDocument {
                         Some(Field::Rev) => {
                             revision = Some(try!(visitor.visit_value()));
                         }
+                        Some(Field::RevsInfo) => {
+                            revs_info = Some(try!(visitor.visit_value()));
+                        }
                         None => {
                             break;
                         }
@@ -589,17 +700,39 @@ impl serde::Deserialize for JsonDecodableDocument {
                         None => try!(visitor.missing_field("_rev")),
                     },
                     deleted: deleted.unwrap_or(false),
+                    conflicts: conflicts.unwrap_or(Vec::new()),
+                    revisions_info: revs_info.unwrap_or(Vec::new()),
                     attachments: attachments.unwrap_or(std::collections::HashMap::new()),
                     content: content_builder.build(),
                 })
             }
         }
 
-        static FIELDS: &'static [&'static str] = &["_attachments", "_deleted", "_id", "_rev"];
+        static FIELDS: &'static [&'static str] = &["_attachments", "_conflicts", "_deleted", "_id", "_rev",
+                                                   "_revs_info"];
         deserializer.deserialize_struct("JsonDecodableDocument", FIELDS, Visitor)
     }
 }
 
+/// Describes how durably the CouchDB server stored a document write.
+///
+/// A clustered CouchDB server responds to a write with a `201 Created` status
+/// if the write reached a quorum of replicas, and with a `202 Accepted` status
+/// if the server accepted the write but fewer replicas than the quorum
+/// confirmed it. In the latter case, the write may still reach all replicas
+/// eventually, but a subsequent read might not yet reflect it. The server
+/// sends no further quorum information in its response.
+///
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum WriteOutcome {
+    /// The write reached a quorum of replicas.
+    Committed,
+
+    /// The server accepted the write, but the write has not (yet) reached a
+    /// quorum of replicas.
+    AcceptedNotDurable,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct WriteDocumentResponse {
     pub ok: bool,
@@ -703,6 +836,8 @@ impl DocumentBuilder {
             doc_path: doc_path.into_document_path().unwrap().into(),
             revision: revision,
             deleted: false,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         })
@@ -734,6 +869,8 @@ mod tests {
     use attachment::AttachmentBuilder;
     use AttachmentName;
     use DocumentId;
+    use RevisionInfo;
+    use RevisionStatus;
     use serde_json;
     use std;
     use super::*;
@@ -745,6 +882,8 @@ mod tests {
             doc_id: DocumentId::from("document_id"),
             revision: "42-1234567890abcdef1234567890abcdef".parse().unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -766,6 +905,8 @@ mod tests {
             doc_id: DocumentId::from("document_id"),
             revision: "42-1234567890abcdef1234567890abcdef".parse().unwrap(),
             deleted: true,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -788,6 +929,8 @@ mod tests {
             doc_id: DocumentId::from("document_id"),
             revision: "42-1234567890abcdef1234567890abcdef".parse().unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new()
                 .insert("field_1", 42)
@@ -814,6 +957,8 @@ mod tests {
             doc_id: DocumentId::from("document_id"),
             revision: "42-1234567890abcdef1234567890abcdef".parse().unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: {
                 let mut map = std::collections::HashMap::new();
                 map.insert(AttachmentName::from("attachment_1"),
@@ -846,6 +991,44 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn json_decodable_document_deserialize_ok_with_conflicts_and_revisions_info() {
+
+        let expected = JsonDecodableDocument {
+            doc_id: DocumentId::from("document_id"),
+            revision: "42-1234567890abcdef1234567890abcdef".parse().unwrap(),
+            deleted: false,
+            conflicts: vec!["41-fedcba0987654321fedcba0987654321".parse().unwrap()],
+            revisions_info: vec![RevisionInfo::new("42-1234567890abcdef1234567890abcdef".parse().unwrap(),
+                                                   RevisionStatus::Available),
+                                 RevisionInfo::new("41-abcdef1234567890abcdef1234567890".parse().unwrap(),
+                                                   RevisionStatus::Missing)],
+            attachments: std::collections::HashMap::new(),
+            content: serde_json::builder::ObjectBuilder::new().build(),
+        };
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("_id", "document_id")
+            .insert("_rev", "42-1234567890abcdef1234567890abcdef")
+            .insert_array("_conflicts",
+                          |x| x.push("41-fedcba0987654321fedcba0987654321"))
+            .insert_array("_revs_info", |x| {
+                x.push_object(|x| {
+                        x.insert("rev", "42-1234567890abcdef1234567890abcdef")
+                            .insert("status", "available")
+                    })
+                    .push_object(|x| {
+                        x.insert("rev", "41-abcdef1234567890abcdef1234567890")
+                            .insert("status", "missing")
+                    })
+            })
+            .build();
+
+        let source = serde_json::to_string(&source).unwrap();
+        let got = serde_json::from_str(&source).unwrap();
+        assert_eq!(expected, got);
+    }
+
     #[test]
     fn json_decodable_document_deserialize_nok_missing_id() {
 
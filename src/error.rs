@@ -1,4 +1,4 @@
-use {hyper, mime, serde, serde_json, std, url, uuid};
+use {BulkDocsResult, hyper, mime, serde, std, url, uuid};
 use transport::{JsonResponse, StatusCode};
 
 /// Contains information for an error originating from or propagated by Chill.
@@ -13,6 +13,15 @@ pub enum Error {
     /// The database already exists.
     DatabaseExists(ErrorResponse),
 
+    /// The MD5 digest of uploaded content does not match the digest of the
+    /// content the server received.
+    ///
+    /// The `expected` field contains the base64-encoded digest of the content
+    /// the client sent. The server doesn't report the digest of the content it
+    /// received.
+    ///
+    DigestMismatch { expected: String },
+
     /// A document with the same id already exists or the given revision is not
     /// the latest revision for the document.
     DocumentConflict(ErrorResponse),
@@ -20,6 +29,20 @@ pub enum Error {
     #[doc(hidden)]
     DocumentIsDeleted,
 
+    /// The client is authenticated but lacks permission to complete the
+    /// action.
+    Forbidden(ErrorResponse),
+
+    /// The server warned that no index matches a Mango query, and the client
+    /// is configured to treat such a warning as an error—see
+    /// `Client::fail_on_full_scan`.
+    FullScan {
+        warning: String,
+    },
+
+    #[doc(hidden)]
+    InvalidOptions(&'static str),
+
     #[doc(hidden)]
     Io {
         cause: std::io::Error,
@@ -28,12 +51,12 @@ pub enum Error {
 
     #[doc(hidden)]
     JsonDecode {
-        cause: serde_json::Error,
+        cause: Box<std::error::Error + Send + Sync>,
     },
 
     #[doc(hidden)]
     JsonEncode {
-        cause: serde_json::Error,
+        cause: Box<std::error::Error + Send + Sync>,
     },
 
     #[doc(hidden)]
@@ -48,6 +71,21 @@ pub enum Error {
     #[doc(hidden)]
     PathParse(PathParseErrorKind),
 
+    /// The target resource does not satisfy a precondition of the action—e.g.,
+    /// the document's current revision does not match the expected revision.
+    PreconditionFailed(ErrorResponse),
+
+    /// The server responded with a redirect that the client did not follow
+    /// because of its redirect policy—see `RedirectPolicy`.
+    ///
+    /// The `location` field contains the redirect's target, resolved against
+    /// the request URL if possible.
+    ///
+    Redirect {
+        status_code: StatusCode,
+        location: String,
+    },
+
     #[doc(hidden)]
     ResponseNotJson(Option<mime::Mime>),
 
@@ -60,11 +98,25 @@ pub enum Error {
     ServerResponse {
         status_code: StatusCode,
         error_response: Option<ErrorResponse>,
+        request_context: Option<RequestContext>,
+    },
+
+    /// The server is too busy to complete the action and asks the client to
+    /// retry later.
+    ///
+    /// The `retry_after` field contains the delay from the response's
+    /// `Retry-After` header, if the header exists and specifies the delay as a
+    /// number of seconds.
+    ///
+    TooManyRequests {
+        error_response: Option<ErrorResponse>,
+        retry_after: Option<std::time::Duration>,
     },
 
     #[doc(hidden)]
     Transport {
         kind: TransportErrorKind,
+        request_context: Option<RequestContext>,
     },
 
     /// The client lacks permission to complete the action.
@@ -80,6 +132,26 @@ pub enum Error {
     UrlParse {
         cause: url::ParseError,
     },
+
+    /// One or more documents failed the client-side validation of a bulk
+    /// write—see `WriteDocuments::validate_before_send`.
+    ///
+    /// The action sent no documents to the server. The `failures` field
+    /// contains one result per failed document, in the same order as the
+    /// documents, each with a <q>forbidden</q> error response whose reason is
+    /// the validation's message.
+    ///
+    Validation {
+        failures: Vec<BulkDocsResult>,
+    },
+
+    /// A view function failed the heuristic syntax check—see
+    /// `ViewFunction::validate_syntax`.
+    ///
+    /// The string describes the problem—e.g., that the map function has
+    /// unbalanced braces.
+    ///
+    ViewFunctionSyntax(&'static str),
 }
 
 impl Error {
@@ -88,6 +160,7 @@ impl Error {
         Error::ServerResponse {
             status_code: response.status_code(),
             error_response: response.decode_content().ok(),
+            request_context: None,
         }
     }
 
@@ -107,6 +180,14 @@ impl Error {
         }
     }
 
+    #[doc(hidden)]
+    pub fn forbidden(response: &JsonResponse) -> Self {
+        match response.decode_content() {
+            Ok(x) => Error::Forbidden(x),
+            Err(x) => x,
+        }
+    }
+
     #[doc(hidden)]
     pub fn not_found(response: &JsonResponse) -> Self {
         match response.decode_content() {
@@ -115,6 +196,22 @@ impl Error {
         }
     }
 
+    #[doc(hidden)]
+    pub fn precondition_failed(response: &JsonResponse) -> Self {
+        match response.decode_content() {
+            Ok(x) => Error::PreconditionFailed(x),
+            Err(x) => x,
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn too_many_requests(response: &JsonResponse) -> Self {
+        Error::TooManyRequests {
+            error_response: response.decode_content().ok(),
+            retry_after: response.headers().retry_after(),
+        }
+    }
+
     #[doc(hidden)]
     pub fn unauthorized(response: &JsonResponse) -> Self {
         match response.decode_content() {
@@ -122,6 +219,172 @@ impl Error {
             Err(x) => x,
         }
     }
+
+    // Records the request that failed, for an error that doesn't already
+    // record it. Errors of other variants are returned as is.
+    #[doc(hidden)]
+    pub fn with_request_context(self, context: &RequestContext) -> Self {
+        match self {
+            Error::ServerResponse { status_code, error_response, request_context: None } => {
+                Error::ServerResponse {
+                    status_code: status_code,
+                    error_response: error_response,
+                    request_context: Some(context.clone()),
+                }
+            }
+            Error::Transport { kind, request_context: None } => {
+                Error::Transport {
+                    kind: kind,
+                    request_context: Some(context.clone()),
+                }
+            }
+            x @ _ => x,
+        }
+    }
+
+    /// Returns the HTTP method and path of the request that failed, if
+    /// available—e.g., `("GET", "/baseball/babe_ruth")`.
+    ///
+    /// The request context is available for an error caused by a non-success
+    /// response that Chill doesn't otherwise recognize or by a failure in the
+    /// underlying HTTP transport. The path contains neither credentials nor
+    /// the query string, so it is safe to log.
+    ///
+    pub fn request_context(&self) -> Option<(&str, &str)> {
+        match self {
+            &Error::ServerResponse { request_context: Some(ref context), .. } |
+            &Error::Transport { request_context: Some(ref context), .. } => {
+                Some((&context.method, &context.path))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns whether the action may succeed if the client retries it later.
+    ///
+    /// An error is retryable if the server responded with `429 Too Many
+    /// Requests` or `503 Service Unavailable`. Use `retry_after` to learn how
+    /// long the server asks the client to wait before retrying.
+    ///
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            &Error::TooManyRequests { .. } => true,
+            &Error::ServerResponse { status_code: StatusCode::ServiceUnavailable, .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the delay the server asks the client to wait before retrying
+    /// the action, if any.
+    pub fn retry_after(&self) -> Option<std::time::Duration> {
+        match self {
+            &Error::TooManyRequests { retry_after, .. } => retry_after,
+            _ => None,
+        }
+    }
+
+    /// Returns the error's category.
+    ///
+    /// Some `Error` variants are hidden and may change between releases, so
+    /// the application can't match them exhaustively. The `ErrorKind`
+    /// discriminant is stable and lets the application branch on the kind of
+    /// failure without depending on the crate's internals.
+    ///
+    pub fn kind(&self) -> ErrorKind {
+        use Error::*;
+        match self {
+            &DatabaseExists(..) |
+            &DocumentConflict(..) |
+            &PreconditionFailed(..) => ErrorKind::Conflict,
+            &DigestMismatch { .. } |
+            &JsonDecode { .. } |
+            &ResponseNotJson(..) |
+            &UnexpectedResponse(..) => ErrorKind::Decode,
+            &JsonEncode { .. } => ErrorKind::Encode,
+            &Forbidden(..) => ErrorKind::Forbidden,
+            &InvalidOptions(..) |
+            &Validation { .. } |
+            &ViewFunctionSyntax(..) => ErrorKind::InvalidInput,
+            &DocumentIsDeleted |
+            &NotFound(..) => ErrorKind::NotFound,
+            &Mock { .. } => ErrorKind::Other,
+            &PathParse(..) => ErrorKind::Path,
+            &RevisionParse { .. } => ErrorKind::Revision,
+            &FullScan { .. } |
+            &Redirect { .. } |
+            &ServerResponse { .. } |
+            &TooManyRequests { .. } => ErrorKind::Server,
+            &ChannelReceive { .. } |
+            &Io { .. } |
+            &Transport { .. } => ErrorKind::Transport,
+            &Unauthorized(..) => ErrorKind::Unauthorized,
+            &UrlNotSchemeRelative |
+            &UrlParse { .. } => ErrorKind::Url,
+        }
+    }
+}
+
+/// Specifies the category of an error—see `Error::kind`.
+///
+/// Unlike the `Error` type, whose hidden variants come and go, `ErrorKind` is
+/// stable: existing errors keep their kind. New kinds may be added in a later
+/// release, so a `match` on `ErrorKind` must have a wildcard arm.
+///
+/// `ErrorKind` is `Copy`, `Eq`, and `Hash`, so the application may use it as
+/// a key when aggregating errors—e.g., counting errors by kind in a
+/// `HashMap<ErrorKind, u64>`.
+///
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ErrorKind {
+    /// The action conflicts with the database's current state—e.g., the
+    /// document's revision is not current, or the database already exists.
+    Conflict,
+
+    /// The client failed to decode the server's response, or the response
+    /// content is not what the client expected.
+    Decode,
+
+    /// The client failed to encode the request content.
+    Encode,
+
+    /// The server forbids the action for the client's user.
+    Forbidden,
+
+    /// The application passed invalid options or arguments to the action, or
+    /// documents that failed client-side validation.
+    InvalidInput,
+
+    /// The resource—e.g., the database or document—does not exist or is
+    /// deleted.
+    NotFound,
+
+    /// The error does not fit into any other kind.
+    Other,
+
+    /// The application passed an invalid database, document, attachment, or
+    /// view path.
+    Path,
+
+    /// The application passed an invalid revision, or the server responded
+    /// with one.
+    Revision,
+
+    /// The server responded with an error status, with a redirect the client
+    /// did not follow, or with a warning the client treats as an error.
+    Server,
+
+    /// The HTTP transport failed—e.g., the connection was refused or
+    /// dropped.
+    Transport,
+
+    /// The client lacks the credentials for the action.
+    Unauthorized,
+
+    /// The server URL is invalid.
+    Url,
+
+    #[doc(hidden)]
+    __Nonexhaustive,
 }
 
 impl std::error::Error for Error {
@@ -130,14 +393,20 @@ impl std::error::Error for Error {
         match self {
             &ChannelReceive { description, .. } => description,
             &DatabaseExists(..) => "The database already exists",
+            &DigestMismatch { .. } => "The content's MD5 digest does not match the server's",
             &DocumentConflict(..) => "A conflicting document with the same id exists",
             &DocumentIsDeleted => "The document is deleted",
+            &Forbidden(..) => "The CouchDB client is forbidden from completing the action",
+            &FullScan { .. } => "The query requires a full database scan",
+            &InvalidOptions(..) => "The action has an invalid combination of options",
             &Io { description, .. } => description,
             &JsonDecode { .. } => "An error occurred while decoding JSON",
             &JsonEncode { .. } => "An error occurred while encoding JSON",
             &Mock { .. } => "A error occurred while test-mocking",
             &NotFound(..) => "The resource cannot be found",
             &PathParse(..) => "The path is badly formatted",
+            &PreconditionFailed(..) => "A precondition of the action failed",
+            &Redirect { .. } => "The CouchDB server responded with a redirect that the client did not follow",
             &ResponseNotJson(Some(..)) => "The response has non-JSON content",
             &ResponseNotJson(None) => "The response content has no type",
             &RevisionParse { .. } => "The revision is badly formatted",
@@ -148,11 +417,14 @@ impl std::error::Error for Error {
                     _ => "The CouchDB server responded with an unexpected status",
                 }
             }
+            &TooManyRequests { .. } => "The CouchDB server is too busy to complete the action",
             &Transport { .. } => "An HTTP transport error occurred",
             &Unauthorized(..) => "The CouchDB client has insufficient privilege",
             &UnexpectedResponse(..) => "The CouchDB server responded unexpectedly",
             &UrlNotSchemeRelative => "The URL is not scheme relative",
             &UrlParse { .. } => "The URL is badly formatted",
+            &Validation { .. } => "One or more documents failed validation",
+            &ViewFunctionSyntax(..) => "The view function is malformed",
         }
     }
 
@@ -161,22 +433,31 @@ impl std::error::Error for Error {
         match self {
             &ChannelReceive { ref cause, .. } => Some(cause),
             &DatabaseExists(..) => None,
+            &DigestMismatch { .. } => None,
             &DocumentConflict(..) => None,
             &DocumentIsDeleted => None,
+            &Forbidden(..) => None,
+            &FullScan { .. } => None,
+            &InvalidOptions(..) => None,
             &Io { ref cause, .. } => Some(cause),
-            &JsonDecode { ref cause } => Some(cause),
-            &JsonEncode { ref cause } => Some(cause),
+            &JsonDecode { ref cause } => Some(&**cause),
+            &JsonEncode { ref cause } => Some(&**cause),
             &Mock { .. } => None,
             &NotFound(..) => None,
             &PathParse(ref kind) => kind.cause(),
+            &PreconditionFailed(..) => None,
+            &Redirect { .. } => None,
             &ResponseNotJson(..) => None,
             &RevisionParse { ref kind } => kind.cause(),
             &ServerResponse { .. } => None,
-            &Transport { ref kind } => kind.cause(),
+            &TooManyRequests { .. } => None,
+            &Transport { ref kind, .. } => kind.cause(),
             &Unauthorized(..) => None,
             &UnexpectedResponse(..) => None,
             &UrlNotSchemeRelative => None,
             &UrlParse { ref cause } => Some(cause),
+            &Validation { .. } => None,
+            &ViewFunctionSyntax(..) => None,
         }
     }
 }
@@ -188,18 +469,26 @@ impl std::fmt::Display for Error {
         match self {
             &ChannelReceive { ref cause, description } => write!(f, "{}: {}", description, cause),
             &DatabaseExists(ref error_response) => write!(f, "{}: {}", description, error_response),
+            &DigestMismatch { ref expected } => write!(f, "{}: Expected {}", description, expected),
             &DocumentConflict(ref error_response) => write!(f, "{}: {}", description, error_response),
             &DocumentIsDeleted => write!(f, "{}", description),
+            &Forbidden(ref error_response) => write!(f, "{}: {}", description, error_response),
+            &FullScan { ref warning } => write!(f, "{}: {}", description, warning),
+            &InvalidOptions(sub_description) => write!(f, "{}: {}", description, sub_description),
             &Io { ref cause, description } => write!(f, "{}: {}", description, cause),
             &JsonDecode { ref cause } => write!(f, "{}: {}", description, cause),
             &JsonEncode { ref cause } => write!(f, "{}: {}", description, cause),
             &Mock { ref extra_description } => write!(f, "{}: {}", description, extra_description),
             &NotFound(ref error_response) => write!(f, "{}: {}", description, error_response),
             &PathParse(ref kind) => write!(f, "{}: {}", description, kind),
+            &PreconditionFailed(ref error_response) => write!(f, "{}: {}", description, error_response),
+            &Redirect { ref status_code, ref location } => {
+                write!(f, "{} ({}): Location is {}", description, status_code, location)
+            }
             &ResponseNotJson(Some(ref content_type)) => write!(f, "{}: Content type is {}", description, content_type),
             &ResponseNotJson(None) => write!(f, "{}", description),
             &RevisionParse { ref kind } => write!(f, "{}: {}", description, kind),
-            &ServerResponse { ref status_code, ref error_response } => {
+            &ServerResponse { ref status_code, ref error_response, ref request_context } => {
                 try!(write!(f, "{} ({}", description, status_code));
                 try!(match status_code.canonical_reason() {
                     None => write!(f, ")"),
@@ -208,13 +497,31 @@ impl std::fmt::Display for Error {
                 if let &Some(ref error_response) = error_response {
                     try!(write!(f, ": {}", error_response));
                 }
+                if let &Some(ref request_context) = request_context {
+                    try!(write!(f, " ({})", request_context));
+                }
                 Ok(())
             }
-            &Transport { ref kind } => write!(f, "{}: {}", description, kind),
+            &TooManyRequests { ref error_response, ref retry_after } => {
+                try!(write!(f, "{}", description));
+                if let &Some(ref error_response) = error_response {
+                    try!(write!(f, ": {}", error_response));
+                }
+                if let &Some(ref retry_after) = retry_after {
+                    try!(write!(f, " (retry after {} seconds)", retry_after.as_secs()));
+                }
+                Ok(())
+            }
+            &Transport { ref kind, request_context: None } => write!(f, "{}: {}", description, kind),
+            &Transport { ref kind, request_context: Some(ref request_context) } => {
+                write!(f, "{}: {} ({})", description, kind, request_context)
+            }
             &Unauthorized(ref error_response) => write!(f, "{}: {}", description, error_response),
             &UnexpectedResponse(sub_description) => write!(f, "{}: {}", description, sub_description),
             &UrlNotSchemeRelative => write!(f, "{}", description),
             &UrlParse { ref cause } => write!(f, "{}: {}", description, cause),
+            &Validation { ref failures } => write!(f, "{} ({} failed)", description, failures.len()),
+            &ViewFunctionSyntax(sub_description) => write!(f, "{}: {}", description, sub_description),
         }
     }
 }
@@ -253,6 +560,7 @@ impl std::fmt::Display for PathParseErrorKind {
 pub enum RevisionParseErrorKind {
     DigestNotAllHex,
     DigestParse(uuid::ParseError),
+    NonzeroLocalPrefix,
     NumberParse(std::num::ParseIntError),
     TooFewParts,
     ZeroSequenceNumber,
@@ -264,6 +572,7 @@ impl RevisionParseErrorKind {
         match self {
             &DigestNotAllHex => None,
             &DigestParse(ref cause) => Some(cause),
+            &NonzeroLocalPrefix => None,
             &NumberParse(ref cause) => Some(cause),
             &TooFewParts => None,
             &ZeroSequenceNumber => None,
@@ -280,6 +589,7 @@ impl std::fmt::Display for RevisionParseErrorKind {
                        "Digest part contains one or more non-hexadecimal characters")
             }
             &DigestParse(ref cause) => write!(f, "The digest part is invalid: {}", cause),
+            &NonzeroLocalPrefix => write!(f, "The local revision does not begin with zero"),
             &NumberParse(ref cause) => write!(f, "The number part is invalid: {}", cause),
             &TooFewParts => write!(f, "Too few parts, missing number part and/or digest part"),
             &ZeroSequenceNumber => write!(f, "The number part is zero"),
@@ -310,6 +620,30 @@ impl std::fmt::Display for TransportErrorKind {
     }
 }
 
+// Identifies the request that caused an error. The path excludes the server
+// URL's credentials and the query string, either of which may be secret.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RequestContext {
+    method: String,
+    path: String,
+}
+
+impl RequestContext {
+    #[doc(hidden)]
+    pub fn new<M: Into<String>, P: Into<String>>(method: M, path: P) -> Self {
+        RequestContext {
+            method: method.into(),
+            path: path.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for RequestContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(f, "{} {}", self.method, self.path)
+    }
+}
+
 /// Error information returned from the CouchDB server when an error occurs
 /// while processing the client's request.
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -340,6 +674,50 @@ impl ErrorResponse {
     pub fn reason(&self) -> &String {
         &self.reason
     }
+
+    /// Classifies the error by its well-known `error` and `reason`
+    /// combination.
+    ///
+    /// The `classify` method lets the application branch on the error's
+    /// meaning without matching strings itself. An unrecognized combination
+    /// classifies as `ReasonKind::Other`, in which case the raw strings remain
+    /// available via `error` and `reason`.
+    ///
+    pub fn classify(&self) -> ReasonKind {
+        match (self.error.as_str(), self.reason.as_str()) {
+            ("conflict", _) => ReasonKind::UpdateConflict,
+            ("file_exists", _) => ReasonKind::FileExists,
+            ("not_found", "missing") => ReasonKind::Missing,
+            ("not_found", "deleted") => ReasonKind::Deleted,
+            ("not_found", "no_db_file") |
+            ("not_found", "Database does not exist.") => ReasonKind::NoDbFile,
+            _ => ReasonKind::Other,
+        }
+    }
+}
+
+/// Specifies the meaning of a well-known CouchDB error response—see
+/// `ErrorResponse::classify`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ReasonKind {
+    /// The document's revision is not current—i.e., <q>Document update
+    /// conflict.</q>
+    UpdateConflict,
+
+    /// The document does not exist and never existed.
+    Missing,
+
+    /// The document existed but was deleted.
+    Deleted,
+
+    /// The database already exists.
+    FileExists,
+
+    /// The database does not exist.
+    NoDbFile,
+
+    /// The error response is not one of the well-known combinations.
+    Other,
 }
 
 impl std::fmt::Display for ErrorResponse {
@@ -431,8 +809,90 @@ impl serde::Deserialize for ErrorResponse {
 #[cfg(test)]
 mod tests {
 
-    use serde_json;
+    use {serde_json, std};
     use super::*;
+    use transport::StatusCode;
+
+    #[test]
+    fn error_is_retryable() {
+
+        let error = Error::TooManyRequests {
+            error_response: None,
+            retry_after: Some(std::time::Duration::from_secs(7)),
+        };
+        assert!(error.is_retryable());
+        assert_eq!(Some(std::time::Duration::from_secs(7)), error.retry_after());
+
+        let error = Error::ServerResponse {
+            status_code: StatusCode::ServiceUnavailable,
+            error_response: None,
+            request_context: None,
+        };
+        assert!(error.is_retryable());
+        assert_eq!(None, error.retry_after());
+
+        let error = Error::ServerResponse {
+            status_code: StatusCode::InternalServerError,
+            error_response: None,
+            request_context: None,
+        };
+        assert!(!error.is_retryable());
+        assert!(!Error::DocumentIsDeleted.is_retryable());
+    }
+
+    #[test]
+    fn error_kind() {
+        let error_response = ErrorResponse::new("conflict", "Document update conflict.");
+        assert_eq!(ErrorKind::Conflict, Error::DocumentConflict(error_response).kind());
+        assert_eq!(ErrorKind::NotFound, Error::DocumentIsDeleted.kind());
+        assert_eq!(ErrorKind::Decode, Error::UnexpectedResponse("foo").kind());
+        assert_eq!(ErrorKind::InvalidInput, Error::InvalidOptions("foo").kind());
+        assert_eq!(ErrorKind::Path, Error::PathParse(PathParseErrorKind::NoLeadingSlash).kind());
+        assert_eq!(ErrorKind::Url, Error::UrlNotSchemeRelative.kind());
+
+        let error = Error::ServerResponse {
+            status_code: StatusCode::InternalServerError,
+            error_response: None,
+            request_context: None,
+        };
+        assert_eq!(ErrorKind::Server, error.kind());
+    }
+
+    #[test]
+    fn error_request_context() {
+
+        let error = Error::ServerResponse {
+            status_code: StatusCode::InternalServerError,
+            error_response: None,
+            request_context: None,
+        };
+        assert_eq!(None, error.request_context());
+
+        let context = RequestContext::new("GET", "/foo/bar");
+        let error = error.with_request_context(&context);
+        assert_eq!(Some(("GET", "/foo/bar")), error.request_context());
+        assert!(error.to_string().ends_with(" (GET /foo/bar)"));
+
+        let error = Error::DocumentIsDeleted.with_request_context(&context);
+        assert_eq!(None, error.request_context());
+    }
+
+    #[test]
+    fn error_kind_as_map_key() {
+        let mut counts = std::collections::HashMap::new();
+        let errors = vec![Error::DocumentIsDeleted,
+                          Error::UrlNotSchemeRelative,
+                          Error::NotFound(ErrorResponse::new("not_found", "missing")),
+                          Error::InvalidOptions("foo")];
+        for error in errors {
+            *counts.entry(error.kind()).or_insert(0u64) += 1;
+        }
+        assert_eq!(3, counts.len());
+        assert_eq!(Some(&2), counts.get(&ErrorKind::NotFound));
+        assert_eq!(Some(&1), counts.get(&ErrorKind::Url));
+        assert_eq!(Some(&1), counts.get(&ErrorKind::InvalidInput));
+        assert_eq!(None, counts.get(&ErrorKind::Conflict));
+    }
 
     #[test]
     fn error_response_display() {
@@ -481,4 +941,26 @@ mod tests {
         let got = serde_json::from_str::<ErrorResponse>(&source);
         expect_json_error_missing_field!(got, "reason");
     }
+
+    #[test]
+    fn error_response_classify_well_known() {
+        let cases = [("conflict", "Document update conflict.", ReasonKind::UpdateConflict),
+                     ("not_found", "missing", ReasonKind::Missing),
+                     ("not_found", "deleted", ReasonKind::Deleted),
+                     ("file_exists", "The database could not be created, the file already exists.",
+                      ReasonKind::FileExists),
+                     ("not_found", "no_db_file", ReasonKind::NoDbFile),
+                     ("not_found", "Database does not exist.", ReasonKind::NoDbFile)];
+        for &(error, reason, expected) in &cases {
+            assert_eq!(expected, ErrorResponse::new(error, reason).classify());
+        }
+    }
+
+    #[test]
+    fn error_response_classify_other() {
+        let error_response = ErrorResponse::new("not_found", "Document is missing attachment");
+        assert_eq!(ReasonKind::Other, error_response.classify());
+        assert_eq!("Document is missing attachment", error_response.reason());
+        assert_eq!(ReasonKind::Other, ErrorResponse::new("forbidden", "missing").classify());
+    }
 }
@@ -0,0 +1,368 @@
+use {DatabaseName, Document, DocumentId, Error, Revision, serde, serde_json};
+use document::JsonDecodableDocument;
+
+/// Contains the result of a Mango query.
+///
+/// The CouchDB server sends a `FindResponse` in response to the HTTP request
+/// `POST /db/_find`.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct FindResponse {
+    documents: Vec<Document>,
+    bookmark: Option<String>,
+    warning: Option<String>,
+    execution_stats: Option<ExecutionStats>,
+}
+
+impl FindResponse {
+    #[doc(hidden)]
+    pub fn new_from_json(db_name: DatabaseName, mut body: serde_json::Value) -> Result<Self, Error> {
+
+        let body = match body.as_object_mut() {
+            Some(x) => x,
+            None => return Err(Error::UnexpectedResponse("The find response is not a JSON object")),
+        };
+
+        let docs = match body.remove("docs") {
+            Some(serde_json::Value::Array(docs)) => docs,
+            _ => return Err(Error::UnexpectedResponse("The find response has no documents")),
+        };
+
+        let documents = try!(docs.into_iter()
+            .map(|doc| {
+                let doc: JsonDecodableDocument = try!(serde_json::from_value(doc)
+                    .map_err(|e| Error::JsonDecode { cause: Box::new(e) }));
+                Ok(Document::new_from_decoded(db_name.clone(), doc))
+            })
+            .collect::<Result<Vec<_>, Error>>());
+
+        let bookmark = match body.remove("bookmark") {
+            Some(serde_json::Value::String(x)) => Some(x),
+            _ => None,
+        };
+
+        let warning = match body.remove("warning") {
+            Some(serde_json::Value::String(x)) => Some(x),
+            _ => None,
+        };
+
+        let execution_stats = body.get("execution_stats").map(ExecutionStats::new_from_json);
+
+        Ok(FindResponse {
+            documents: documents,
+            bookmark: bookmark,
+            warning: warning,
+            execution_stats: execution_stats,
+        })
+    }
+
+    /// Returns the documents matching the query.
+    pub fn documents(&self) -> &Vec<Document> {
+        &self.documents
+    }
+
+    /// Converts the response into the documents matching the query.
+    pub fn into_documents(self) -> Vec<Document> {
+        self.documents
+    }
+
+    /// Returns the token for resuming the query after the last document in
+    /// this response, if available.
+    ///
+    /// The bookmark is unavailable from CouchDB servers older than v2.1.
+    ///
+    pub fn bookmark(&self) -> Option<&str> {
+        self.bookmark.as_ref().map(|x| x.as_str())
+    }
+
+    /// Returns the server's warning about the query, if any—e.g., that no
+    /// index matches the query.
+    pub fn warning(&self) -> Option<&str> {
+        self.warning.as_ref().map(|x| x.as_str())
+    }
+
+    /// Returns whether the server warned that no index matches the query—i.e.,
+    /// that the server scanned the whole database to answer the query.
+    pub fn is_full_scan(&self) -> bool {
+        self.warning().map(|x| x.to_lowercase().contains("no matching index")).unwrap_or(false)
+    }
+
+    /// Returns statistics about how the server executed the query, if
+    /// available.
+    ///
+    /// The statistics are available only if the query enabled them—see
+    /// `Find::with_execution_stats`—and the CouchDB server is v2.1 or newer.
+    ///
+    pub fn execution_stats(&self) -> Option<&ExecutionStats> {
+        self.execution_stats.as_ref()
+    }
+}
+
+/// Contains statistics about how the CouchDB server executed a Mango query.
+///
+/// Comparing the number of documents examined with the number of results
+/// returned tells how efficient the query is. A query that examines many
+/// more documents than it returns—e.g., a full scan—likely lacks a suitable
+/// index.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExecutionStats {
+    total_keys_examined: u64,
+    total_docs_examined: u64,
+    total_quorum_docs_examined: u64,
+    results_returned: u64,
+    execution_time_ms: f64,
+}
+
+impl ExecutionStats {
+    // Decodes the statistics leniently—a missing or malformed field yields
+    // zero—because the set of fields varies between CouchDB versions.
+    fn new_from_json(value: &serde_json::Value) -> Self {
+        let count = |key: &str| value.find(key).and_then(|x| x.as_u64()).unwrap_or(0);
+        ExecutionStats {
+            total_keys_examined: count("total_keys_examined"),
+            total_docs_examined: count("total_docs_examined"),
+            total_quorum_docs_examined: count("total_quorum_docs_examined"),
+            results_returned: count("results_returned"),
+            execution_time_ms: value.find("execution_time_ms").and_then(|x| x.as_f64()).unwrap_or(0.0),
+        }
+    }
+
+    /// Returns the number of index keys the server examined.
+    pub fn keys_examined(&self) -> u64 {
+        self.total_keys_examined
+    }
+
+    /// Returns the number of documents the server examined.
+    pub fn docs_examined(&self) -> u64 {
+        self.total_docs_examined
+    }
+
+    /// Returns the number of documents the server examined using an
+    /// out-of-band read quorum—i.e., when the query specifies a read quorum
+    /// other than one.
+    pub fn quorum_docs_examined(&self) -> u64 {
+        self.total_quorum_docs_examined
+    }
+
+    /// Returns the number of documents the query returned.
+    pub fn results_returned(&self) -> u64 {
+        self.results_returned
+    }
+
+    /// Returns how long the server took to execute the query, in
+    /// milliseconds.
+    pub fn execution_time_ms(&self) -> f64 {
+        self.execution_time_ms
+    }
+}
+
+/// Identifies a document that has conflicting revisions—see the
+/// `FindConflicts` action.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ConflictedDoc {
+    doc_id: DocumentId,
+    revision: Revision,
+    conflicts: Vec<Revision>,
+}
+
+impl ConflictedDoc {
+    #[doc(hidden)]
+    pub fn new(doc_id: DocumentId, revision: Revision, conflicts: Vec<Revision>) -> Self {
+        ConflictedDoc {
+            doc_id: doc_id,
+            revision: revision,
+            conflicts: conflicts,
+        }
+    }
+
+    /// Returns the document's id.
+    pub fn doc_id(&self) -> &DocumentId {
+        &self.doc_id
+    }
+
+    /// Returns the document's current revision—i.e., the revision the CouchDB
+    /// server chose as the winner.
+    pub fn revision(&self) -> &Revision {
+        &self.revision
+    }
+
+    /// Returns the document's conflicting revisions.
+    pub fn conflicts(&self) -> &Vec<Revision> {
+        &self.conflicts
+    }
+}
+
+impl serde::Deserialize for ConflictedDoc {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+        where D: serde::Deserializer
+    {
+        enum Field {
+            Conflicts,
+            Id,
+            Ignored,
+            Rev,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+                where D: serde::Deserializer
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Self::Value, E>
+                        where E: serde::de::Error
+                    {
+                        match value {
+                            "_conflicts" => Ok(Field::Conflicts),
+                            "_id" => Ok(Field::Id),
+                            "_rev" => Ok(Field::Rev),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = ConflictedDoc;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+                where V: serde::de::MapVisitor
+            {
+                let mut conflicts = None;
+                let mut id = None;
+                let mut rev = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Conflicts) => {
+                            conflicts = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Id) => {
+                            id = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde::de::impls::IgnoredAny>());
+                        }
+                        Some(Field::Rev) => {
+                            rev = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                Ok(ConflictedDoc {
+                    doc_id: match id {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("_id")),
+                    },
+                    revision: match rev {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("_rev")),
+                    },
+                    conflicts: conflicts.unwrap_or(Vec::new()),
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["_conflicts", "_id", "_rev"];
+        deserializer.deserialize_struct("ConflictedDoc", FIELDS, Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {DatabaseName, Error, Revision, serde_json};
+    use super::*;
+    use document::DocumentBuilder;
+
+    #[test]
+    fn find_response_new_from_json_ok() {
+
+        let source = serde_json::from_str(r#"{"docs": [{"_id": "bar", "_rev": "1-1234567890abcdef1234567890abcdef",
+                                                       "field": 42}],
+                                              "bookmark": "g1AAAA",
+                                              "warning": "no matching index found"}"#)
+            .unwrap();
+
+        let expected = FindResponse {
+            documents: vec![DocumentBuilder::new("/foo/bar",
+                                                 Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap())
+                                .build_content(|x| x.insert("field", 42))
+                                .unwrap()],
+            bookmark: Some(String::from("g1AAAA")),
+            warning: Some(String::from("no matching index found")),
+            execution_stats: None,
+        };
+
+        let got = FindResponse::new_from_json(DatabaseName::from("foo"), source).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn find_response_new_from_json_ok_without_bookmark() {
+        let source = serde_json::from_str(r#"{"docs": []}"#).unwrap();
+        let got = FindResponse::new_from_json(DatabaseName::from("foo"), source).unwrap();
+        assert_eq!(None, got.bookmark());
+        assert_eq!(None, got.warning());
+        assert_eq!(None, got.execution_stats());
+        assert!(got.documents().is_empty());
+    }
+
+    #[test]
+    fn find_response_new_from_json_ok_with_execution_stats() {
+
+        let source = serde_json::from_str(r#"{"docs": [],
+                                              "execution_stats": {
+                                                  "total_keys_examined": 0,
+                                                  "total_docs_examined": 200,
+                                                  "total_quorum_docs_examined": 0,
+                                                  "results_returned": 0,
+                                                  "execution_time_ms": 5.52
+                                              }}"#)
+            .unwrap();
+
+        let expected = ExecutionStats {
+            total_keys_examined: 0,
+            total_docs_examined: 200,
+            total_quorum_docs_examined: 0,
+            results_returned: 0,
+            execution_time_ms: 5.52,
+        };
+
+        let got = FindResponse::new_from_json(DatabaseName::from("foo"), source).unwrap();
+        assert_eq!(Some(&expected), got.execution_stats());
+    }
+
+    #[test]
+    fn find_response_new_from_json_ok_with_partial_execution_stats() {
+        let source = serde_json::from_str(r#"{"docs": [], "execution_stats": {"results_returned": 3}}"#).unwrap();
+        let got = FindResponse::new_from_json(DatabaseName::from("foo"), source).unwrap();
+        let stats = got.execution_stats().unwrap();
+        assert_eq!(3, stats.results_returned());
+        assert_eq!(0, stats.docs_examined());
+        assert_eq!(0.0, stats.execution_time_ms());
+    }
+
+    #[test]
+    fn find_response_new_from_json_nok_no_docs() {
+        let source = serde_json::from_str(r#"{"bookmark": "nil"}"#).unwrap();
+        match FindResponse::new_from_json(DatabaseName::from("foo"), source) {
+            Err(Error::UnexpectedResponse(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use serde_json;
+
+/// Specifies a Mango index to create—see the `CreateIndex` action.
+///
+/// An `IndexDef` abstracts the request body of `POST /db/_index`. By default,
+/// the CouchDB server generates the index's name and the id of the design
+/// document containing it. Naming both makes creating the index idempotent:
+/// creating the same index again reports that it already exists.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexDef {
+    fields: Vec<String>,
+    name: Option<String>,
+    design_doc: Option<String>,
+    partial_filter_selector: Option<serde_json::Value>,
+}
+
+impl IndexDef {
+    /// Constructs a definition of an index on the given fields.
+    pub fn new<I, S>(fields: I) -> Self
+        where I: IntoIterator<Item = S>,
+              S: Into<String>
+    {
+        IndexDef {
+            fields: fields.into_iter().map(|x| x.into()).collect(),
+            name: None,
+            design_doc: None,
+            partial_filter_selector: None,
+        }
+    }
+
+    /// Returns a copy of the definition that names the index.
+    pub fn with_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Returns a copy of the definition that stores the index in the given
+    /// design document—e.g., `"by-type"`, without the `_design/` prefix.
+    pub fn with_design_document<S: Into<String>>(mut self, design_doc: S) -> Self {
+        self.design_doc = Some(design_doc.into());
+        self
+    }
+
+    /// Returns a copy of the definition that indexes only the documents that
+    /// match the given selector.
+    pub fn with_partial_filter_selector(mut self, selector: serde_json::Value) -> Self {
+        self.partial_filter_selector = Some(selector);
+        self
+    }
+
+    /// Returns the indexed fields.
+    pub fn fields(&self) -> &Vec<String> {
+        &self.fields
+    }
+
+    #[doc(hidden)]
+    pub fn to_json(&self) -> serde_json::Value {
+
+        let body = serde_json::builder::ObjectBuilder::new().insert_object("index", |x| {
+            let x = x.insert("fields", &self.fields);
+            match self.partial_filter_selector {
+                None => x,
+                Some(ref selector) => x.insert("partial_filter_selector", selector),
+            }
+        });
+
+        let body = match self.name {
+            None => body,
+            Some(ref name) => body.insert("name", name),
+        };
+
+        let body = match self.design_doc {
+            None => body,
+            Some(ref design_doc) => body.insert("ddoc", design_doc),
+        };
+
+        body.insert("type", "json").build()
+    }
+}
+
+/// Contains the outcome of creating a Mango index—see the `CreateIndex`
+/// action.
+///
+/// The CouchDB server reports whether it created the index or whether an
+/// identical index already existed. Either way, the outcome contains the
+/// index's name and the id of the design document containing it.
+///
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum IndexOutcome {
+    /// The server created the index.
+    Created {
+        design_doc: String,
+        name: String,
+    },
+
+    /// The index already existed, so the server changed nothing.
+    AlreadyExists {
+        design_doc: String,
+        name: String,
+    },
+}
+
+impl IndexOutcome {
+    /// Returns whether the server created the index.
+    pub fn is_created(&self) -> bool {
+        match self {
+            &IndexOutcome::Created { .. } => true,
+            &IndexOutcome::AlreadyExists { .. } => false,
+        }
+    }
+
+    /// Returns the id of the design document containing the index—e.g.,
+    /// `"_design/by-type"`.
+    pub fn design_document(&self) -> &String {
+        match self {
+            &IndexOutcome::Created { ref design_doc, .. } => design_doc,
+            &IndexOutcome::AlreadyExists { ref design_doc, .. } => design_doc,
+        }
+    }
+
+    /// Returns the index's name.
+    pub fn name(&self) -> &String {
+        match self {
+            &IndexOutcome::Created { ref name, .. } => name,
+            &IndexOutcome::AlreadyExists { ref name, .. } => name,
+        }
+    }
+}
+
+/// Contains the description of an existing Mango index—see the
+/// `ReadIndexes` action.
+///
+/// Besides the indexes the application created, a database always has the
+/// built-in index on document ids, named `_all_docs`, whose kind is
+/// `"special"` and which has no design document.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexInfo {
+    design_doc: Option<String>,
+    name: String,
+    kind: String,
+    fields: Vec<String>,
+    partial_filter_selector: Option<serde_json::Value>,
+}
+
+impl IndexInfo {
+    #[doc(hidden)]
+    pub fn new(design_doc: Option<String>,
+               name: String,
+               kind: String,
+               fields: Vec<String>,
+               partial_filter_selector: Option<serde_json::Value>)
+               -> Self {
+        IndexInfo {
+            design_doc: design_doc,
+            name: name,
+            kind: kind,
+            fields: fields,
+            partial_filter_selector: partial_filter_selector,
+        }
+    }
+
+    /// Returns the id of the design document containing the index—e.g.,
+    /// `"_design/by-type"`—or `None` for the built-in `_all_docs` index.
+    pub fn design_document(&self) -> Option<&str> {
+        self.design_doc.as_ref().map(|x| x.as_str())
+    }
+
+    /// Returns the index's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the index's kind—e.g., `"json"`, or `"special"` for the
+    /// built-in `_all_docs` index.
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// Returns the indexed fields, in index order.
+    pub fn fields(&self) -> &Vec<String> {
+        &self.fields
+    }
+
+    /// Returns the index's partial filter selector, if any.
+    pub fn partial_filter_selector(&self) -> Option<&serde_json::Value> {
+        self.partial_filter_selector.as_ref()
+    }
+
+    /// Returns whether the index is the built-in `_all_docs` index, which
+    /// cannot be deleted.
+    pub fn is_built_in(&self) -> bool {
+        self.design_doc.is_none()
+    }
+}
@@ -1,5 +1,6 @@
 extern crate base64;
 extern crate hyper;
+extern crate md5;
 #[macro_use(mime, __mime__ident_or_ext)]
 extern crate mime;
 extern crate regex;
@@ -14,11 +15,29 @@ extern crate uuid;
 mod test_macro;
 
 mod attachment;
+mod bulk;
+mod capabilities;
+mod changes;
 mod client;
+mod codec;
+mod database;
 mod design;
 mod document;
 mod error;
+mod find;
+mod index;
+mod local_document;
+mod membership;
+mod purge;
+mod raw;
+mod read_preference;
+mod redirect_policy;
+mod replication;
 mod revision;
+mod scheduler;
+mod seq;
+mod session;
+mod stats;
 mod transport;
 mod view;
 
@@ -26,13 +45,31 @@ pub mod action;
 pub mod path;
 pub mod testing;
 
-pub use attachment::{Attachment, SavedAttachment, UnsavedAttachment};
-pub use client::{Client, IntoUrl};
-pub use design::{Design, DesignBuilder, ViewFunction};
-pub use document::Document;
-pub use error::{Error, ErrorResponse};
+pub use attachment::{Attachment, AttachmentRange, SavedAttachment, UnsavedAttachment};
+pub use bulk::{BulkDocsResult, BulkWriteErrors, BulkWriteResponse, ImportReport};
+pub use capabilities::Capabilities;
+pub use changes::{ChangeRow, Changes};
+pub use client::{Client, ClientBuilder, IntoUrl};
+pub use codec::JsonCodec;
+pub use database::{ClusterInfo, Database, DatabaseInfo};
+pub use design::{Design, DesignBuilder, DesignDocumentRow, ViewFunction};
+pub use document::{Document, WriteOutcome};
+pub use error::{Error, ErrorKind, ErrorResponse, ReasonKind};
+pub use find::{ConflictedDoc, ExecutionStats, FindResponse};
+pub use index::{IndexDef, IndexInfo, IndexOutcome};
+pub use local_document::{LocalDocument, LocalDocumentRow};
+pub use membership::Membership;
+pub use purge::PurgedInfo;
+pub use raw::RawResponse;
+pub use read_preference::ReadPreference;
+pub use redirect_policy::RedirectPolicy;
+pub use replication::ReplicationReport;
 pub use path::{AttachmentName, AttachmentPath, DatabaseName, DatabasePath, DesignDocumentName, DesignDocumentPath,
                DocumentId, DocumentPath, IntoAttachmentPath, IntoDatabasePath, IntoDesignDocumentPath,
                IntoDocumentPath, IntoViewPath, LocalDocumentName, NormalDocumentName, ViewName, ViewPath};
-pub use revision::Revision;
-pub use view::{ViewResponse, ViewRow};
+pub use revision::{LocalRevision, Revision, RevisionInfo, RevisionStatus};
+pub use scheduler::SchedulerDoc;
+pub use seq::{Seq, Since};
+pub use session::SessionInfo;
+pub use stats::{Histogram, Stats};
+pub use view::{ComplexKey, ViewResponse, ViewRow};
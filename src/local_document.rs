@@ -0,0 +1,146 @@
+use {DocumentPath, Error, LocalRevision, serde, serde_json};
+
+/// Contains a specific version of a local document.
+///
+/// A `LocalDocument` is an in-memory representation of a local document—i.e.,
+/// a document whose id begins with `_local/`. Unlike a `Document`, it has a
+/// `LocalRevision` instead of a `Revision`, and it has no attachments.
+///
+/// Applications read local documents via the `ReadLocalDocument` action and
+/// write them via the `WriteLocalDocument` action.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocalDocument {
+    doc_path: DocumentPath,
+    revision: LocalRevision,
+    content: serde_json::Value,
+}
+
+impl LocalDocument {
+    #[doc(hidden)]
+    pub fn new_from_json(doc_path: DocumentPath, mut content: serde_json::Value) -> Result<Self, Error> {
+
+        let revision = {
+            let fields = try!(content.as_object_mut()
+                .ok_or(Error::UnexpectedResponse("The local document is not a JSON object")));
+            fields.remove("_id");
+            let revision = try!(fields.remove("_rev")
+                .ok_or(Error::UnexpectedResponse("The local document has no revision")));
+            try!(revision.as_str()
+                .ok_or(Error::UnexpectedResponse("The local document's revision is not a string"))
+                .and_then(LocalRevision::parse))
+        };
+
+        Ok(LocalDocument {
+            doc_path: doc_path,
+            revision: revision,
+            content: content,
+        })
+    }
+
+    pub fn path(&self) -> &DocumentPath {
+        &self.doc_path
+    }
+
+    /// Returns the local document's revision.
+    pub fn revision(&self) -> &LocalRevision {
+        &self.revision
+    }
+
+    /// Decodes and returns the local document content, from a JSON object into
+    /// a Rust type.
+    pub fn get_content<C: serde::Deserialize>(&self) -> Result<C, Error> {
+        serde_json::from_value(self.content.clone()).map_err(|e| Error::JsonDecode { cause: Box::new(e) })
+    }
+}
+
+/// Contains one row of a local document listing.
+///
+/// Applications list local documents via the `ReadLocalDocuments` action.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocalDocumentRow {
+    doc_path: DocumentPath,
+    revision: LocalRevision,
+    document: Option<LocalDocument>,
+}
+
+impl LocalDocumentRow {
+    #[doc(hidden)]
+    pub fn new(doc_path: DocumentPath, revision: LocalRevision, document: Option<LocalDocument>) -> Self {
+        LocalDocumentRow {
+            doc_path: doc_path,
+            revision: revision,
+            document: document,
+        }
+    }
+
+    /// Returns the local document's path.
+    pub fn path(&self) -> &DocumentPath {
+        &self.doc_path
+    }
+
+    /// Returns the local document's current revision.
+    pub fn revision(&self) -> &LocalRevision {
+        &self.revision
+    }
+
+    /// Returns the local document, if the action read documents—see
+    /// `ReadLocalDocuments::with_documents`.
+    pub fn document(&self) -> Option<&LocalDocument> {
+        self.document.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {Error, IntoDocumentPath, LocalRevision, serde_json};
+    use super::*;
+
+    #[test]
+    fn new_from_json_ok() {
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("_id", "_local/bar")
+            .insert("_rev", "0-3")
+            .insert("field", 42)
+            .build();
+
+        let expected = LocalDocument {
+            doc_path: "/foo/_local/bar".into_document_path().unwrap(),
+            revision: LocalRevision::parse("0-3").unwrap(),
+            content: serde_json::builder::ObjectBuilder::new().insert("field", 42).build(),
+        };
+
+        let got = LocalDocument::new_from_json("/foo/_local/bar".into_document_path().unwrap(), source).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn new_from_json_nok_normal_revision() {
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("_id", "_local/bar")
+            .insert("_rev", "1-1234567890abcdef1234567890abcdef")
+            .build();
+
+        match LocalDocument::new_from_json("/foo/_local/bar".into_document_path().unwrap(), source) {
+            Err(Error::RevisionParse { .. }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn new_from_json_nok_missing_revision() {
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("_id", "_local/bar")
+            .build();
+
+        match LocalDocument::new_from_json("/foo/_local/bar".into_document_path().unwrap(), source) {
+            Err(Error::UnexpectedResponse(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
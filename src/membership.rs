@@ -0,0 +1,177 @@
+use serde;
+
+/// Describes the nodes of a CouchDB cluster.
+///
+/// The CouchDB server sends membership info in response to the HTTP request
+/// `GET /_membership`. A server that is not clustered—e.g., CouchDB 1.x—has
+/// exactly one node, which Chill names `nonode@nohost`, same as CouchDB does
+/// for a single node that has no configured name.
+///
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Membership {
+    all_nodes: Vec<String>,
+    cluster_nodes: Vec<String>,
+}
+
+impl Membership {
+    #[doc(hidden)]
+    pub fn new_single_node() -> Self {
+        Membership {
+            all_nodes: vec![String::from("nonode@nohost")],
+            cluster_nodes: vec![String::from("nonode@nohost")],
+        }
+    }
+
+    /// Returns the names of all nodes the server is connected to.
+    pub fn all_nodes(&self) -> &Vec<String> {
+        &self.all_nodes
+    }
+
+    /// Returns the names of all nodes that are configured to be part of the
+    /// cluster.
+    pub fn cluster_nodes(&self) -> &Vec<String> {
+        &self.cluster_nodes
+    }
+}
+
+impl serde::Deserialize for Membership {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+        where D: serde::Deserializer
+    {
+        enum Field {
+            AllNodes,
+            ClusterNodes,
+            Ignored,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+                where D: serde::Deserializer
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                        where E: serde::de::Error
+                    {
+                        match value {
+                            "all_nodes" => Ok(Field::AllNodes),
+                            "cluster_nodes" => Ok(Field::ClusterNodes),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = Membership;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+                where V: serde::de::MapVisitor
+            {
+                let mut all_nodes = None;
+                let mut cluster_nodes = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::AllNodes) => {
+                            all_nodes = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::ClusterNodes) => {
+                            cluster_nodes = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde::de::impls::IgnoredAny>());
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                let all_nodes = match all_nodes {
+                    Some(x) => x,
+                    None => try!(visitor.missing_field("all_nodes")),
+                };
+
+                let cluster_nodes = match cluster_nodes {
+                    Some(x) => x,
+                    None => try!(visitor.missing_field("cluster_nodes")),
+                };
+
+                Ok(Membership {
+                    all_nodes: all_nodes,
+                    cluster_nodes: cluster_nodes,
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["all_nodes", "cluster_nodes"];
+        deserializer.deserialize_struct("Membership", FIELDS, Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use serde_json;
+    use super::*;
+
+    #[test]
+    fn membership_deserialize_ok() {
+
+        let expected = Membership {
+            all_nodes: vec![String::from("node1@127.0.0.1"), String::from("node2@127.0.0.1")],
+            cluster_nodes: vec![String::from("node1@127.0.0.1")],
+        };
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert_array("all_nodes", |x| x.push("node1@127.0.0.1").push("node2@127.0.0.1"))
+            .insert_array("cluster_nodes", |x| x.push("node1@127.0.0.1"))
+            .build();
+
+        let source = serde_json::to_string(&source).unwrap();
+        let got = serde_json::from_str(&source).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn membership_deserialize_ok_with_unknown_field() {
+
+        let expected = Membership {
+            all_nodes: vec![String::from("node1@127.0.0.1")],
+            cluster_nodes: vec![String::from("node1@127.0.0.1")],
+        };
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert_array("all_nodes", |x| x.push("node1@127.0.0.1"))
+            .insert_array("cluster_nodes", |x| x.push("node1@127.0.0.1"))
+            .insert_object("unknown", |x| x.insert("field", 42))
+            .build();
+
+        let source = serde_json::to_string(&source).unwrap();
+        let got = serde_json::from_str(&source).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn membership_deserialize_nok_missing_cluster_nodes() {
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert_array("all_nodes", |x| x.push("node1@127.0.0.1"))
+            .build();
+
+        let source = serde_json::to_string(&source).unwrap();
+        let got = serde_json::from_str::<Membership>(&source);
+        expect_json_error_missing_field!(got, "cluster_nodes");
+    }
+}
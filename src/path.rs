@@ -110,6 +110,60 @@ impl<'a> PathExtractor<'a> {
     }
 }
 
+// Splits an arbitrary path string into its segments, applying the same
+// validation as the typed paths. The root path ("/") has no segments.
+#[doc(hidden)]
+pub fn split_raw_path(path: &str) -> Result<Vec<&str>, Error> {
+
+    if path == "/" {
+        return Ok(Vec::new());
+    }
+
+    let mut extractor = try!(PathExtractor::begin(path));
+    let mut segments = Vec::new();
+    while !extractor.path.is_empty() && extractor.path != "/" {
+        segments.push(try!(extractor.extract_nonempty()));
+    }
+    try!(extractor.end());
+
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod split_raw_path_tests {
+
+    use Error;
+    use error::PathParseErrorKind;
+    use super::split_raw_path;
+
+    #[test]
+    fn split_raw_path_ok() {
+        assert_eq!(Vec::<&str>::new(), split_raw_path("/").unwrap());
+        assert_eq!(vec!["_up"], split_raw_path("/_up").unwrap());
+        assert_eq!(vec!["_node", "_local", "_stats"], split_raw_path("/_node/_local/_stats").unwrap());
+    }
+
+    #[test]
+    fn split_raw_path_nok() {
+        match split_raw_path("") {
+            Err(Error::PathParse(PathParseErrorKind::NoLeadingSlash)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+        match split_raw_path("_up") {
+            Err(Error::PathParse(PathParseErrorKind::NoLeadingSlash)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+        match split_raw_path("/alpha//bravo") {
+            Err(Error::PathParse(PathParseErrorKind::EmptySegment)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+        match split_raw_path("/alpha/") {
+            Err(Error::PathParse(PathParseErrorKind::TrailingSlash)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
+
 #[cfg(test)]
 mod path_extractor_tests {
 
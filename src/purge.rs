@@ -0,0 +1,33 @@
+use {DocumentId, Revision};
+
+/// Contains the record of one purge request for one document.
+///
+/// The CouchDB server sends purge records in response to the HTTP request
+/// `GET /db/_purged_infos`. An application that syncs purges between clusters
+/// replays each record on the target via `POST /db/_purge`.
+///
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PurgedInfo {
+    doc_id: DocumentId,
+    revisions: Vec<Revision>,
+}
+
+impl PurgedInfo {
+    #[doc(hidden)]
+    pub fn new(doc_id: DocumentId, revisions: Vec<Revision>) -> Self {
+        PurgedInfo {
+            doc_id: doc_id,
+            revisions: revisions,
+        }
+    }
+
+    /// Returns the id of the purged document.
+    pub fn document_id(&self) -> &DocumentId {
+        &self.doc_id
+    }
+
+    /// Returns the purged revisions of the document.
+    pub fn revisions(&self) -> &[Revision] {
+        &self.revisions
+    }
+}
@@ -0,0 +1,82 @@
+use std;
+
+/// Contains the response to an arbitrary HTTP request.
+///
+/// A `RawResponse` is the result of `Client::raw_request`, which exists for
+/// CouchDB endpoints that Chill does not otherwise support. Chill does not
+/// interpret the response—not even the status code—so the application must
+/// check for errors itself.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct RawResponse {
+    status_code: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl RawResponse {
+    #[doc(hidden)]
+    pub fn new(status_code: u16, headers: Vec<(String, String)>, body: Vec<u8>) -> Self {
+        RawResponse {
+            status_code: status_code,
+            headers: headers,
+            body: body,
+        }
+    }
+
+    /// Returns the response's HTTP status code.
+    pub fn status_code(&self) -> u16 {
+        self.status_code
+    }
+
+    /// Returns the response's headers as name-value pairs.
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Returns the value of the first header with the given name, if the header
+    /// exists.
+    ///
+    /// Header names are compared case-insensitively.
+    ///
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.headers.iter().find(|x| x.0.to_lowercase() == name).map(|x| x.1.as_str())
+    }
+
+    /// Returns the response's body.
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Returns the response's body as a string, if the body is valid UTF-8.
+    pub fn body_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.body).ok()
+    }
+
+    /// Takes ownership of the response's body.
+    pub fn into_body(self) -> Vec<u8> {
+        self.body
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn raw_response_header_is_case_insensitive() {
+        let response = RawResponse::new(200, vec![(String::from("Content-Type"), String::from("text/plain"))], vec![]);
+        assert_eq!(Some("text/plain"), response.header("content-type"));
+        assert_eq!(None, response.header("etag"));
+    }
+
+    #[test]
+    fn raw_response_body_str() {
+        let response = RawResponse::new(200, vec![], b"hello".to_vec());
+        assert_eq!(Some("hello"), response.body_str());
+        let response = RawResponse::new(200, vec![], vec![0xff]);
+        assert_eq!(None, response.body_str());
+    }
+}
@@ -0,0 +1,57 @@
+/// Specifies how a clustered CouchDB server should route and refresh view
+/// reads.
+///
+/// `ReadPreference` abstracts the `stable` and `update` query parameters of
+/// view requests, e.g., `GET /db/_design/ddoc/_view/view`. With `stable`, the
+/// server answers from the same set of shard replicas on every request, which
+/// avoids results flapping between nodes. With `update`, the server may answer
+/// from the index as is, without first bringing it up to date.
+///
+/// The default, `Fresh`, sends no parameters and is therefore a no-op against
+/// any server. The other preferences trade freshness for latency and matter
+/// most in multi-node deployments.
+///
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ReadPreference {
+    /// Specifies the server's default behavior: update the index before
+    /// answering, from any replica.
+    Fresh,
+
+    /// Specifies to answer from a stable set of shard replicas, updating the
+    /// index before answering.
+    Stable,
+
+    /// Specifies to answer from a stable set of shard replicas without
+    /// updating the index—results may be stale.
+    Stale,
+
+    /// Specifies to answer from a stable set of shard replicas without waiting
+    /// for the index to update, but to update the index afterwards.
+    StaleUpdateAfter,
+}
+
+impl Default for ReadPreference {
+    fn default() -> Self {
+        ReadPreference::Fresh
+    }
+}
+
+impl ReadPreference {
+    #[doc(hidden)]
+    pub fn stable(&self) -> Option<bool> {
+        match self {
+            &ReadPreference::Fresh => None,
+            _ => Some(true),
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn update(&self) -> Option<&'static str> {
+        match self {
+            &ReadPreference::Fresh => None,
+            &ReadPreference::Stable => None,
+            &ReadPreference::Stale => Some("false"),
+            &ReadPreference::StaleUpdateAfter => Some("lazy"),
+        }
+    }
+}
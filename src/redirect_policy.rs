@@ -0,0 +1,88 @@
+use url;
+
+// The most redirects the client follows for one request under the
+// `SameHostOnly` policy, so that a redirect loop fails instead of hanging.
+const MAX_SAME_HOST_REDIRECTS: u32 = 10;
+
+/// Specifies whether and how the client follows HTTP redirects—i.e., `3xx`
+/// responses with a `Location` header.
+///
+/// A proxy in front of the CouchDB server may redirect requests—e.g., to
+/// canonicalize a trailing slash or to upgrade from HTTP to HTTPS. Following a
+/// redirect to another host could leak the client's credentials to that host,
+/// so the default, `None`, follows no redirects. Instead, the action fails with
+/// `Error::Redirect`, whose `location` field tells where the server pointed.
+///
+/// When following a redirect, the client resends the request's method and body
+/// only for `307 Temporary Redirect` and `308 Permanent Redirect`. For other
+/// redirect statuses, the client follows only `GET` and `HEAD` requests. When
+/// following a redirect to another origin—i.e., a different scheme, host, or
+/// port, including an upgrade from `http` to `https`—the client drops the
+/// `Authorization` and `Cookie` headers.
+///
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RedirectPolicy {
+    /// Specifies to follow no redirects.
+    None,
+
+    /// Specifies to follow at most the given number of redirects per request,
+    /// to any host.
+    Limited(u8),
+
+    /// Specifies to follow redirects only to the same host, with the same or
+    /// a more secure scheme—e.g., from `http` to `https` but not vice versa.
+    SameHostOnly,
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        RedirectPolicy::None
+    }
+}
+
+impl RedirectPolicy {
+    #[doc(hidden)]
+    pub fn allows(&self, num_redirects: u32, from: &url::Url, to: &url::Url) -> bool {
+        match self {
+            &RedirectPolicy::None => false,
+            &RedirectPolicy::Limited(limit) => num_redirects < limit as u32,
+            &RedirectPolicy::SameHostOnly => {
+                num_redirects < MAX_SAME_HOST_REDIRECTS && from.host_str() == to.host_str() &&
+                !(from.scheme() == "https" && to.scheme() != "https")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use url;
+    use super::*;
+
+    fn url(s: &str) -> url::Url {
+        url::Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn none_allows_nothing() {
+        assert!(!RedirectPolicy::None.allows(0, &url("http://example.com/foo"), &url("http://example.com/foo/")));
+    }
+
+    #[test]
+    fn limited_allows_up_to_limit() {
+        let policy = RedirectPolicy::Limited(2);
+        assert!(policy.allows(0, &url("http://example.com/foo"), &url("http://other.example.com/foo")));
+        assert!(policy.allows(1, &url("http://example.com/foo"), &url("http://other.example.com/foo")));
+        assert!(!policy.allows(2, &url("http://example.com/foo"), &url("http://other.example.com/foo")));
+    }
+
+    #[test]
+    fn same_host_only_allows_same_host_and_upgrade() {
+        let policy = RedirectPolicy::SameHostOnly;
+        assert!(policy.allows(0, &url("http://example.com/foo"), &url("http://example.com/foo/")));
+        assert!(policy.allows(0, &url("http://example.com:5984/foo"), &url("https://example.com:6984/foo")));
+        assert!(!policy.allows(0, &url("https://example.com/foo"), &url("http://example.com/foo")));
+        assert!(!policy.allows(0, &url("http://example.com/foo"), &url("http://evil.example.org/foo")));
+    }
+}
@@ -0,0 +1,82 @@
+use {BulkDocsResult, DocumentId, Seq};
+
+/// Summarizes the outcome of pulling one batch of changes from a source
+/// database into a target database—see the `PullReplication` action.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReplicationReport {
+    last_seq: Seq,
+    documents_read: u64,
+    documents_written: u64,
+    conflicts: Vec<DocumentId>,
+    failures: Vec<BulkDocsResult>,
+}
+
+impl ReplicationReport {
+    #[doc(hidden)]
+    pub fn new(last_seq: Seq) -> Self {
+        ReplicationReport {
+            last_seq: last_seq,
+            documents_read: 0,
+            documents_written: 0,
+            conflicts: Vec::new(),
+            failures: Vec::new(),
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn add_read(&mut self, doc_id: &DocumentId, num_leaf_revisions: usize) {
+        self.documents_read += 1;
+        if num_leaf_revisions > 1 {
+            self.conflicts.push(doc_id.clone());
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn add_written(&mut self, num_documents: u64) {
+        self.documents_written += num_documents;
+    }
+
+    #[doc(hidden)]
+    pub fn add_failure(&mut self, result: BulkDocsResult) {
+        self.failures.push(result);
+    }
+
+    /// Returns the source's update sequence as of the end of the batch.
+    ///
+    /// To pull the next batch, pass this sequence to
+    /// `PullReplication::with_since`.
+    ///
+    pub fn last_seq(&self) -> &Seq {
+        &self.last_seq
+    }
+
+    /// Returns the number of changed documents read from the source.
+    pub fn documents_read(&self) -> u64 {
+        self.documents_read
+    }
+
+    /// Returns the number of document revisions written to the target.
+    ///
+    /// Revisions the target already has are not written, so this may be less
+    /// than the number of documents read.
+    ///
+    pub fn documents_written(&self) -> u64 {
+        self.documents_written
+    }
+
+    /// Returns the ids of the documents that have conflicting leaf revisions
+    /// in the source, in changes-feed order.
+    ///
+    /// All leaf revisions are replicated, so the target has the same
+    /// conflicts. The application may resolve them—see `FindConflicts`.
+    ///
+    pub fn conflicts(&self) -> &Vec<DocumentId> {
+        &self.conflicts
+    }
+
+    /// Returns the results of the document revisions that failed to be read
+    /// from the source or written to the target.
+    pub fn failures(&self) -> &Vec<BulkDocsResult> {
+        &self.failures
+    }
+}
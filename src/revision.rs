@@ -13,6 +13,17 @@ use uuid;
 /// In serialized form, a revision looks like
 /// `1-9c65296036141e575d32ba9c034dd3ee`.
 ///
+/// `Revision` implements `serde::Serialize` and `serde::Deserialize` using
+/// this serialized form, so applications may embed a revision as a field in
+/// their own types. Deserializing a badly formatted revision fails with a
+/// serde error—e.g., `Error::JsonDecode` when decoding JSON.
+///
+/// Revisions are ordered first by sequence number and then by digest. This
+/// matches only the tiebreak the CouchDB server uses among a document's
+/// non-deleted leaf revisions. The server prefers a non-deleted leaf over a
+/// deleted one regardless of order, so the greatest revision need not be the
+/// winner if any of the revisions is a deletion.
+///
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Revision {
     sequence_number: u64,
@@ -29,6 +40,43 @@ impl Revision {
         Revision::from_str(s)
     }
 
+    /// Constructs a new `Revision` from the given sequence number and digest.
+    ///
+    /// The sequence number must be nonzero, and the digest must be 32
+    /// hexadecimal digits—e.g., `1234567890abcdef1234567890abcdef`. These are
+    /// the same rules `parse` enforces.
+    ///
+    pub fn try_new(sequence_number: u64, digest: &str) -> Result<Self, Error> {
+
+        use error::RevisionParseErrorKind;
+
+        if sequence_number == 0 {
+            return Err(Error::RevisionParse { kind: RevisionParseErrorKind::ZeroSequenceNumber });
+        }
+
+        let digest_uuid = try!(uuid::Uuid::parse_str(digest)
+            .map_err(|e| Error::RevisionParse { kind: RevisionParseErrorKind::DigestParse(e) }));
+
+        if !is_all_hex(digest) {
+            return Err(Error::RevisionParse { kind: RevisionParseErrorKind::DigestNotAllHex });
+        }
+
+        Ok(Revision {
+            sequence_number: sequence_number,
+            digest: digest_uuid,
+        })
+    }
+
+    /// Returns whether the given string is a valid revision digest.
+    ///
+    /// A valid digest is 32 hexadecimal digits. Both lowercase and uppercase
+    /// digits are accepted, though the CouchDB server generates only
+    /// lowercase digests and Chill formats digests in lowercase.
+    ///
+    pub fn is_valid_digest(s: &str) -> bool {
+        s.len() == 32 && is_all_hex(s)
+    }
+
     /// Returns the sequence number part of the revision.
     ///
     /// The sequence number is the `123` part of the revision
@@ -39,6 +87,10 @@ impl Revision {
     }
 }
 
+fn is_all_hex(s: &str) -> bool {
+    s.chars().all(|c| c.is_digit(16))
+}
+
 impl std::fmt::Display for Revision {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{}-{}", self.sequence_number, self.digest.simple())
@@ -56,27 +108,31 @@ impl std::str::FromStr for Revision {
         let sequence_number_str = try!(parts.next()
             .ok_or(Error::RevisionParse { kind: RevisionParseErrorKind::TooFewParts }));
 
-        let sequence_number = match try!(u64::from_str_radix(sequence_number_str, 10)
-            .map_err(|e| Error::RevisionParse { kind: RevisionParseErrorKind::NumberParse(e) })) {
-            0 => {
-                return Err(Error::RevisionParse { kind: RevisionParseErrorKind::ZeroSequenceNumber });
-            }
-            x @ _ => x,
-        };
+        let sequence_number = try!(u64::from_str_radix(sequence_number_str, 10)
+            .map_err(|e| Error::RevisionParse { kind: RevisionParseErrorKind::NumberParse(e) }));
+
+        if sequence_number == 0 {
+            return Err(Error::RevisionParse { kind: RevisionParseErrorKind::ZeroSequenceNumber });
+        }
 
         let digest_str = try!(parts.next().ok_or(Error::RevisionParse { kind: RevisionParseErrorKind::TooFewParts }));
 
-        let digest = try!(uuid::Uuid::parse_str(digest_str)
-            .map_err(|e| Error::RevisionParse { kind: RevisionParseErrorKind::DigestParse(e) }));
+        Revision::try_new(sequence_number, digest_str)
+    }
+}
 
-        if digest_str.chars().any(|c| !c.is_digit(16)) {
-            return Err(Error::RevisionParse { kind: RevisionParseErrorKind::DigestNotAllHex });
+impl Ord for Revision {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match self.sequence_number.cmp(&other.sequence_number) {
+            std::cmp::Ordering::Equal => self.digest.as_bytes().cmp(other.digest.as_bytes()),
+            x @ _ => x,
         }
+    }
+}
 
-        Ok(Revision {
-            sequence_number: sequence_number,
-            digest: digest,
-        })
+impl PartialOrd for Revision {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
     }
 }
 
@@ -116,12 +172,261 @@ impl serde::Deserialize for Revision {
     }
 }
 
+/// A local document revision, which identifies a version of a local document.
+///
+/// Local documents—i.e., documents whose id begins with `_local/`—aren't
+/// replicated and have no revision history. Instead of a sequence number and
+/// digest, a local revision comprises a zero and a **number** that starts at
+/// `1` when the document is created and increments by one each time the
+/// document is updated.
+///
+/// In serialized form, a local revision looks like `0-1`.
+///
+/// `LocalRevision` implements `serde::Serialize` and `serde::Deserialize`
+/// using this serialized form.
+///
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LocalRevision {
+    number: u64,
+}
+
+impl LocalRevision {
+    /// Constructs a new `LocalRevision` from the given string.
+    ///
+    /// The string must be of the form `0-42`.
+    ///
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        use std::str::FromStr;
+        LocalRevision::from_str(s)
+    }
+
+    /// Returns the number part of the local revision.
+    ///
+    /// The number is the `42` part of the local revision `0-42`.
+    ///
+    pub fn number(&self) -> u64 {
+        self.number
+    }
+}
+
+impl std::fmt::Display for LocalRevision {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "0-{}", self.number)
+    }
+}
+
+impl std::str::FromStr for LocalRevision {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+
+        use error::RevisionParseErrorKind;
+
+        let mut parts = s.splitn(2, '-');
+
+        let prefix_str = try!(parts.next().ok_or(Error::RevisionParse { kind: RevisionParseErrorKind::TooFewParts }));
+        if prefix_str != "0" {
+            return Err(Error::RevisionParse { kind: RevisionParseErrorKind::NonzeroLocalPrefix });
+        }
+
+        let number_str = try!(parts.next().ok_or(Error::RevisionParse { kind: RevisionParseErrorKind::TooFewParts }));
+
+        let number = try!(u64::from_str_radix(number_str, 10)
+            .map_err(|e| Error::RevisionParse { kind: RevisionParseErrorKind::NumberParse(e) }));
+
+        Ok(LocalRevision { number: number })
+    }
+}
+
+impl From<LocalRevision> for String {
+    fn from(revision: LocalRevision) -> Self {
+        revision.to_string()
+    }
+}
+
+impl serde::Serialize for LocalRevision {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+        where S: serde::Serializer
+    {
+        let s = self.to_string();
+        serializer.serialize_str(&s)
+    }
+}
+
+impl serde::Deserialize for LocalRevision {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+        where D: serde::Deserializer
+    {
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = LocalRevision;
+
+            fn visit_str<E>(&mut self, v: &str) -> Result<Self::Value, E>
+                where E: serde::de::Error
+            {
+                use std::error::Error;
+                LocalRevision::parse(v).map_err(|e| E::invalid_value(e.description()))
+            }
+        }
+
+        deserializer.deserialize(Visitor)
+    }
+}
+
+/// Describes the availability of one revision of a document.
+///
+/// The CouchDB server sends revision info for a document when the client
+/// explicitly requests it while reading the document—see the
+/// `ReadDocument::with_revisions_info` method.
+///
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RevisionInfo {
+    revision: Revision,
+    status: RevisionStatus,
+}
+
+impl RevisionInfo {
+    #[doc(hidden)]
+    pub fn new(revision: Revision, status: RevisionStatus) -> Self {
+        RevisionInfo {
+            revision: revision,
+            status: status,
+        }
+    }
+
+    /// Returns the revision.
+    pub fn revision(&self) -> &Revision {
+        &self.revision
+    }
+
+    /// Returns the availability of the revision.
+    pub fn status(&self) -> RevisionStatus {
+        self.status
+    }
+}
+
+impl serde::Deserialize for RevisionInfo {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+        where D: serde::Deserializer
+    {
+        enum Field {
+            Rev,
+            Status,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+                where D: serde::Deserializer
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                        where E: serde::de::Error
+                    {
+                        match value {
+                            "rev" => Ok(Field::Rev),
+                            "status" => Ok(Field::Status),
+                            _ => Err(E::unknown_field(value)),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = RevisionInfo;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+                where V: serde::de::MapVisitor
+            {
+                let mut rev = None;
+                let mut status = None;
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Rev) => {
+                            rev = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Status) => {
+                            status = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                Ok(RevisionInfo {
+                    revision: match rev {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("rev")),
+                    },
+                    status: match status {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("status")),
+                    },
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["rev", "status"];
+        deserializer.deserialize_struct("RevisionInfo", FIELDS, Visitor)
+    }
+}
+
+/// Specifies whether the CouchDB server has the content of a revision.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RevisionStatus {
+    /// The revision's content is available.
+    Available,
+
+    /// The revision marks the document as deleted.
+    Deleted,
+
+    /// The revision's content is unavailable, e.g., because it was compacted
+    /// away.
+    Missing,
+}
+
+impl serde::Deserialize for RevisionStatus {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+        where D: serde::Deserializer
+    {
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = RevisionStatus;
+
+            fn visit_str<E>(&mut self, v: &str) -> Result<Self::Value, E>
+                where E: serde::de::Error
+            {
+                match v {
+                    "available" => Ok(RevisionStatus::Available),
+                    "deleted" => Ok(RevisionStatus::Deleted),
+                    "missing" => Ok(RevisionStatus::Missing),
+                    _ => Err(E::invalid_value("Unknown revision status")),
+                }
+            }
+        }
+
+        deserializer.deserialize(Visitor)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use Error;
     use serde_json;
-    use super::Revision;
+    use super::{LocalRevision, Revision, RevisionInfo, RevisionStatus};
 
     #[test]
     fn parse_ok() {
@@ -138,6 +443,102 @@ mod tests {
         Revision::parse("bad_revision").unwrap_err();
     }
 
+    #[test]
+    fn parse_nok_local_revision() {
+        use error::RevisionParseErrorKind;
+        match Revision::parse("0-1") {
+            Err(Error::RevisionParse { kind: RevisionParseErrorKind::ZeroSequenceNumber }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn local_revision_parse_ok() {
+        let expected = LocalRevision { number: 1 };
+        let got = LocalRevision::parse("0-1").unwrap();
+        assert_eq!(expected, got);
+        assert_eq!(42, LocalRevision::parse("0-42").unwrap().number());
+    }
+
+    #[test]
+    fn local_revision_parse_nok() {
+        use error::RevisionParseErrorKind;
+
+        match LocalRevision::parse("1-1234567890abcdeffedcba0987654321") {
+            Err(Error::RevisionParse { kind: RevisionParseErrorKind::NonzeroLocalPrefix }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+
+        match LocalRevision::parse("0") {
+            Err(Error::RevisionParse { kind: RevisionParseErrorKind::TooFewParts }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+
+        match LocalRevision::parse("0-abc") {
+            Err(Error::RevisionParse { kind: RevisionParseErrorKind::NumberParse(..) }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn local_revision_display() {
+        assert_eq!("0-17", LocalRevision::parse("0-17").unwrap().to_string());
+    }
+
+    #[test]
+    fn local_revision_serialize_and_deserialize() {
+        let expected = LocalRevision::parse("0-3").unwrap();
+        let encoded = serde_json::to_string(&expected).unwrap();
+        assert_eq!(r#""0-3""#, encoded);
+        let got = serde_json::from_str::<LocalRevision>(&encoded).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn try_new_ok() {
+        let expected = Revision::parse("42-1234567890abcdeffedcba0987654321").unwrap();
+        let got = Revision::try_new(42, "1234567890abcdeffedcba0987654321").unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn try_new_nok() {
+        use error::RevisionParseErrorKind;
+
+        match Revision::try_new(0, "1234567890abcdeffedcba0987654321") {
+            Err(Error::RevisionParse { kind: RevisionParseErrorKind::ZeroSequenceNumber }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+
+        match Revision::try_new(1, "12345678-90ab-cdef-fedc-ba0987654321") {
+            Err(Error::RevisionParse { kind: RevisionParseErrorKind::DigestNotAllHex }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+
+        match Revision::try_new(1, "z234567890abcdeffedcba0987654321") {
+            Err(Error::RevisionParse { .. }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn is_valid_digest_lowercase() {
+        assert!(Revision::is_valid_digest("1234567890abcdeffedcba0987654321"));
+    }
+
+    #[test]
+    fn is_valid_digest_uppercase() {
+        assert!(Revision::is_valid_digest("1234567890ABCDEFFEDCBA0987654321"));
+    }
+
+    #[test]
+    fn is_valid_digest_nok() {
+        assert!(!Revision::is_valid_digest("z234567890abcdeffedcba0987654321"));
+        assert!(!Revision::is_valid_digest("12345678-90ab-cdef-fedc-ba0987654321"));
+        assert!(!Revision::is_valid_digest("1234567890abcdeffedcba098765432"));
+        assert!(!Revision::is_valid_digest(""));
+    }
+
     #[test]
     fn sequence_number() {
         let rev = Revision::parse("999-1234567890abcdef1234567890abcdef").unwrap();
@@ -230,6 +631,27 @@ mod tests {
         assert!(r1 == r2);
     }
 
+    #[test]
+    fn ord_different_numbers() {
+        let r1 = Revision::parse("2-00000000000000000000000000000000").unwrap();
+        let r2 = Revision::parse("1-ffffffffffffffffffffffffffffffff").unwrap();
+        assert!(r1 > r2);
+    }
+
+    #[test]
+    fn ord_different_digests() {
+        let r1 = Revision::parse("2-1234567890abcdef1234567890abcdef").unwrap();
+        let r2 = Revision::parse("2-1234567890abcdef1234567890abcdf0").unwrap();
+        assert!(r1 < r2);
+    }
+
+    #[test]
+    fn ord_numbers_compare_numerically() {
+        let r1 = Revision::parse("10-00000000000000000000000000000000").unwrap();
+        let r2 = Revision::parse("9-00000000000000000000000000000000").unwrap();
+        assert!(r1 > r2);
+    }
+
     #[test]
     fn serialization_ok() {
         let expected = serde_json::Value::String("42-1234567890abcdeffedcba0987654321".to_string());
@@ -254,6 +676,14 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn serialization_round_trip() {
+        let expected = Revision::parse("42-1234567890abcdeffedcba0987654321").unwrap();
+        let s = serde_json::to_string(&expected).unwrap();
+        let got = serde_json::from_str::<Revision>(&s).unwrap();
+        assert_eq!(expected, got);
+    }
+
     #[test]
     fn deserialization_nok() {
         let source = serde_json::Value::String("bad_revision".to_string());
@@ -261,4 +691,22 @@ mod tests {
         let got = serde_json::from_str::<Revision>(&s);
         expect_json_error_invalid_value!(got);
     }
+
+    #[test]
+    fn revision_info_deserialize_ok() {
+        let expected = RevisionInfo {
+            revision: Revision::parse("2-1234567890abcdef1234567890abcdef").unwrap(),
+            status: RevisionStatus::Deleted,
+        };
+        let source = r#"{"rev": "2-1234567890abcdef1234567890abcdef", "status": "deleted"}"#;
+        let got = serde_json::from_str(source).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn revision_info_deserialize_nok_bad_status() {
+        let source = r#"{"rev": "2-1234567890abcdef1234567890abcdef", "status": "bogus"}"#;
+        let got = serde_json::from_str::<RevisionInfo>(source);
+        expect_json_error_invalid_value!(got);
+    }
 }
@@ -0,0 +1,341 @@
+use {serde, std};
+
+/// A database update sequence, which identifies a point in a database's
+/// history of changes.
+///
+/// CouchDB 1.x represents an update sequence as an integer, whereas CouchDB 2.x
+/// and later represent it as an opaque string—e.g.,
+/// `42-g1AAAABteJzLYWBgYMpgTmHgz8tPSTV0MDQy0TMx0TM0MdEzNjPRMwXpMgbpMWOA6gcAGVIKzw`.
+/// `Seq` holds either form so that applications work with both server
+/// versions.
+///
+/// Applications should treat an update sequence as opaque. However, the
+/// `as_u64` method is available for the common case of needing a rough
+/// measure of progress.
+///
+/// Two `Seq` values are equal if their string forms are equal, regardless of
+/// whether the server sent them as a number or as a string—e.g.,
+/// `Seq::from(7)` equals `Seq::from("7")`.
+///
+#[derive(Clone, Debug)]
+pub struct Seq {
+    raw: String,
+    is_number: bool,
+}
+
+impl Seq {
+    /// Returns the update sequence in its string form.
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    /// Returns the update sequence's numeric prefix, if any.
+    ///
+    /// For CouchDB 1.x, the result is the update sequence itself. For CouchDB
+    /// 2.x and later, the result is the number preceding the first hyphen in
+    /// the opaque string, which is the sum of the update sequences of all
+    /// shards.
+    ///
+    pub fn as_u64(&self) -> Option<u64> {
+        let end = self.raw.find(|c: char| !c.is_digit(10)).unwrap_or(self.raw.len());
+        u64::from_str_radix(&self.raw[..end], 10).ok()
+    }
+}
+
+impl Eq for Seq {}
+
+impl PartialEq for Seq {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl std::hash::Hash for Seq {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.raw.hash(state);
+    }
+}
+
+impl std::fmt::Display for Seq {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.raw.fmt(f)
+    }
+}
+
+impl From<u64> for Seq {
+    fn from(n: u64) -> Self {
+        Seq {
+            raw: n.to_string(),
+            is_number: true,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Seq {
+    fn from(s: &'a str) -> Self {
+        Seq::from(String::from(s))
+    }
+}
+
+impl From<String> for Seq {
+    fn from(s: String) -> Self {
+        Seq {
+            raw: s,
+            is_number: false,
+        }
+    }
+}
+
+impl serde::Serialize for Seq {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+        where S: serde::Serializer
+    {
+        match (self.is_number, self.as_u64()) {
+            (true, Some(n)) => serializer.serialize_u64(n),
+            _ => serializer.serialize_str(&self.raw),
+        }
+    }
+}
+
+impl serde::Deserialize for Seq {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+        where D: serde::Deserializer
+    {
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = Seq;
+
+            fn visit_u64<E>(&mut self, v: u64) -> Result<Self::Value, E>
+                where E: serde::de::Error
+            {
+                Ok(Seq::from(v))
+            }
+
+            fn visit_i64<E>(&mut self, v: i64) -> Result<Self::Value, E>
+                where E: serde::de::Error
+            {
+                if v < 0 {
+                    return Err(E::invalid_value("Update sequence is negative"));
+                }
+                Ok(Seq::from(v as u64))
+            }
+
+            fn visit_str<E>(&mut self, v: &str) -> Result<Self::Value, E>
+                where E: serde::de::Error
+            {
+                Ok(Seq::from(v))
+            }
+
+            fn visit_string<E>(&mut self, v: String) -> Result<Self::Value, E>
+                where E: serde::de::Error
+            {
+                Ok(Seq::from(v))
+            }
+        }
+
+        deserializer.deserialize(Visitor)
+    }
+}
+
+/// Specifies where a changes feed starts.
+///
+/// The changes feed's `since` parameter is an update sequence, which is an
+/// integer for CouchDB 1.x and an opaque string for CouchDB 2.x and later.
+/// `Since` holds a `Seq` in its original form so that a checkpoint persisted
+/// as a string is sent back to the server verbatim—never truncated to an
+/// integer.
+///
+/// `Since` converts from a `Seq`, an integer, or a string. As a special case,
+/// the string `"now"` converts to `Since::Now`.
+///
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Since {
+    /// Starts after the given update sequence.
+    Seq(Seq),
+
+    /// Starts at the database's current update sequence, i.e., yields only
+    /// changes that happen from now on.
+    Now,
+
+    /// Starts at the beginning of the database's history.
+    Zero,
+}
+
+impl Since {
+    /// Returns the string form of the starting point, as sent in the `since`
+    /// query parameter.
+    pub fn as_str(&self) -> &str {
+        match self {
+            &Since::Seq(ref seq) => seq.as_str(),
+            &Since::Now => "now",
+            &Since::Zero => "0",
+        }
+    }
+}
+
+impl Default for Since {
+    fn default() -> Self {
+        Since::Zero
+    }
+}
+
+impl std::fmt::Display for Since {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl From<Seq> for Since {
+    fn from(seq: Seq) -> Self {
+        Since::Seq(seq)
+    }
+}
+
+impl From<u64> for Since {
+    fn from(n: u64) -> Self {
+        match n {
+            0 => Since::Zero,
+            _ => Since::Seq(Seq::from(n)),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Since {
+    fn from(s: &'a str) -> Self {
+        match s {
+            "now" => Since::Now,
+            _ => Since::Seq(Seq::from(s)),
+        }
+    }
+}
+
+impl From<String> for Since {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "now" => Since::Now,
+            _ => Since::Seq(Seq::from(s)),
+        }
+    }
+}
+
+impl serde::Serialize for Since {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+        where S: serde::Serializer
+    {
+        match self {
+            &Since::Seq(ref seq) => seq.serialize(serializer),
+            &Since::Now => serializer.serialize_str("now"),
+            &Since::Zero => serializer.serialize_u64(0),
+        }
+    }
+}
+
+impl serde::Deserialize for Since {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+        where D: serde::Deserializer
+    {
+        let seq = try!(Seq::deserialize(deserializer));
+        Ok(match (seq.is_number, seq.as_str()) {
+            (true, "0") => Since::Zero,
+            (false, "now") => Since::Now,
+            _ => Since::Seq(seq),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use serde_json;
+    use super::*;
+
+    #[test]
+    fn as_u64_number() {
+        assert_eq!(Some(42), Seq::from(42).as_u64());
+    }
+
+    #[test]
+    fn as_u64_opaque_string() {
+        let seq = Seq::from("42-g1AAAABteJzLYWBgYMpgTmHgz8tPSTV0MDQy0TMx0TM0MdEzNjPRMwXpMgbpMWOA6gcAGVIKzw");
+        assert_eq!(Some(42), seq.as_u64());
+    }
+
+    #[test]
+    fn as_u64_no_numeric_prefix() {
+        let seq = Seq::from("g1AAAABteJzLYWBgYMpgTmHgz8tPSTV0MDQy0TMx0TM0MdEzNjPRMwXpMgbpMWOA6gcAGVIKzw");
+        assert_eq!(None, seq.as_u64());
+    }
+
+    #[test]
+    fn eq_ignores_form() {
+        assert_eq!(Seq::from(7), Seq::from("7"));
+        assert_eq!(Seq::from(7), serde_json::from_str::<Seq>(r#""7""#).unwrap());
+        assert!(Seq::from(7) != Seq::from("7-abc"));
+    }
+
+    #[test]
+    fn deserialize_ok_number() {
+        let expected = Seq::from(42);
+        let got: Seq = serde_json::from_str("42").unwrap();
+        assert_eq!(expected, got);
+        assert_eq!("42", got.as_str());
+    }
+
+    #[test]
+    fn deserialize_ok_string() {
+        let expected = Seq::from("42-g1AAAABteJzLYWBgYMpgTmHgz8tPSTV0MDQy0TMx0TM0MdEzNjPRMwXpMgbpMWOA6gcAGVIKzw");
+        let source = r#""42-g1AAAABteJzLYWBgYMpgTmHgz8tPSTV0MDQy0TMx0TM0MdEzNjPRMwXpMgbpMWOA6gcAGVIKzw""#;
+        let got: Seq = serde_json::from_str(source).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn serialize_preserves_form() {
+        assert_eq!("42", serde_json::to_string(&Seq::from(42)).unwrap());
+        assert_eq!(r#""42-abc""#, serde_json::to_string(&Seq::from("42-abc")).unwrap());
+    }
+
+    #[test]
+    fn since_from_str() {
+        assert_eq!(Since::Now, Since::from("now"));
+        assert_eq!(Since::Seq(Seq::from("42-abc")), Since::from("42-abc"));
+        assert_eq!(Since::Seq(Seq::from(42)), Since::from(42));
+    }
+
+    #[test]
+    fn since_as_str() {
+        assert_eq!("0", Since::Zero.as_str());
+        assert_eq!("now", Since::Now.as_str());
+        assert_eq!("42", Since::from(42).as_str());
+        assert_eq!("42-abc", Since::from("42-abc").as_str());
+    }
+
+    #[test]
+    fn since_round_trip_number() {
+        let since = Since::from(42);
+        let encoded = serde_json::to_string(&since).unwrap();
+        assert_eq!("42", encoded);
+        let decoded: Since = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(since, decoded);
+    }
+
+    #[test]
+    fn since_round_trip_opaque_string() {
+        let since = Since::from("42-g1AAAABteJzLYWBgYMpgTmHgz8tPSTV0MDQy0TMx0TM0MdEzNjPRMwXpMgbpMWOA6gcAGVIKzw");
+        let encoded = serde_json::to_string(&since).unwrap();
+        assert_eq!(r#""42-g1AAAABteJzLYWBgYMpgTmHgz8tPSTV0MDQy0TMx0TM0MdEzNjPRMwXpMgbpMWOA6gcAGVIKzw""#,
+                   encoded);
+        let decoded: Since = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(since, decoded);
+    }
+
+    #[test]
+    fn since_round_trip_now_and_zero() {
+        for since in vec![Since::Now, Since::Zero] {
+            let encoded = serde_json::to_string(&since).unwrap();
+            let decoded: Since = serde_json::from_str(&encoded).unwrap();
+            assert_eq!(since, decoded);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+/// Describes the client's session with the CouchDB server—i.e., who the
+/// server thinks the client is.
+///
+/// The CouchDB server sends session info in response to the HTTP request
+/// `GET /_session`. If the client is not authenticated, then the session is
+/// anonymous: it has no user name, though it may have roles—e.g., `_admin` if
+/// the server is in “admin party” mode.
+///
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SessionInfo {
+    ok: bool,
+    name: Option<String>,
+    roles: Vec<String>,
+}
+
+impl SessionInfo {
+    #[doc(hidden)]
+    pub fn new(ok: bool, name: Option<String>, roles: Vec<String>) -> Self {
+        SessionInfo {
+            ok: ok,
+            name: name,
+            roles: roles,
+        }
+    }
+
+    /// Returns whether the server reported success.
+    pub fn is_ok(&self) -> bool {
+        self.ok
+    }
+
+    /// Returns whether the session is authenticated—i.e., it has a user name.
+    pub fn is_authenticated(&self) -> bool {
+        self.name.is_some()
+    }
+
+    /// Returns the name of the authenticated user, if any.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(|x| x.as_str())
+    }
+
+    /// Returns the session's roles.
+    pub fn roles(&self) -> &Vec<String> {
+        &self.roles
+    }
+}
@@ -0,0 +1,294 @@
+use {serde, serde_json, std};
+
+/// Contains statistics about a CouchDB server node.
+///
+/// The CouchDB server sends statistics in response to the HTTP request `GET
+/// /_node/{node}/_stats`. The statistics form a tree whose shape varies from
+/// one CouchDB version to another, so `Stats` holds the tree as is and offers
+/// lenient, path-based access to it. Convenience methods are available for
+/// some common statistics.
+///
+/// Each leaf of the tree is a metric. For a counter or gauge, the metric's
+/// value is a number. For a histogram, the metric's value is an object
+/// containing fields such as `min`, `max`, and `arithmetic_mean`.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stats {
+    tree: serde_json::Value,
+}
+
+impl Stats {
+    /// Returns the raw metric at the given path, if the metric exists.
+    ///
+    /// For example, the path of the number of open databases is
+    /// `["couchdb", "open_databases"]`.
+    ///
+    pub fn get(&self, path: &[&str]) -> Option<&serde_json::Value> {
+        path.iter().fold(Some(&self.tree), |node, key| node.and_then(|x| x.find(key)))
+    }
+
+    /// Returns the numeric value of the counter or gauge metric at the given
+    /// path, if the metric exists.
+    pub fn number(&self, path: &[&str]) -> Option<f64> {
+        self.metric_value(path).and_then(|x| x.as_f64())
+    }
+
+    /// Returns the number of open databases, if known.
+    pub fn open_databases(&self) -> Option<u64> {
+        self.metric_value(&["couchdb", "open_databases"]).and_then(|x| x.as_u64())
+    }
+
+    /// Returns the total number of HTTP requests the node has handled, if
+    /// known.
+    pub fn httpd_requests(&self) -> Option<u64> {
+        self.metric_value(&["httpd", "requests"]).and_then(|x| x.as_u64())
+    }
+
+    /// Returns the mean request time, in milliseconds, if known.
+    pub fn request_time_mean(&self) -> Option<f64> {
+        self.metric_value(&["couchdb", "request_time"]).and_then(|x| {
+            match x {
+                &serde_json::Value::Object(..) => x.find("arithmetic_mean").and_then(|x| x.as_f64()),
+                _ => x.as_f64(),
+            }
+        })
+    }
+
+    /// Returns the histogram metric at the given path, if the metric exists
+    /// and is a histogram.
+    pub fn histogram(&self, path: &[&str]) -> Option<Histogram> {
+        self.get(path).and_then(Histogram::from_metric)
+    }
+
+    /// Returns the histogram of request times, in milliseconds, if known.
+    pub fn request_time(&self) -> Option<Histogram> {
+        self.histogram(&["couchdb", "request_time"])
+    }
+
+    // CouchDB 2.x and later put the metric's value in the `value` field,
+    // whereas CouchDB 1.x puts it in the `current` field.
+    fn metric_value(&self, path: &[&str]) -> Option<&serde_json::Value> {
+        self.get(path).and_then(|x| x.find("value").or_else(|| x.find("current")))
+    }
+}
+
+/// Contains the summary of a histogram metric—e.g., request times.
+///
+/// CouchDB 2.x and later send a histogram's minimum, maximum, arithmetic
+/// mean, and a list of percentiles. CouchDB 1.x sends only the minimum,
+/// maximum, and mean. Each field is optional so that a histogram from any
+/// version decodes.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    min: Option<f64>,
+    max: Option<f64>,
+    arithmetic_mean: Option<f64>,
+    percentiles: Vec<(f64, f64)>,
+}
+
+impl Histogram {
+    // Returns None if the metric is not an object. CouchDB 2.x and later nest
+    // the histogram in the `value` field, whereas CouchDB 1.x puts its fields
+    // directly in the metric.
+    fn from_metric(metric: &serde_json::Value) -> Option<Self> {
+
+        let fields = match metric.find("value") {
+            Some(x @ &serde_json::Value::Object(..)) => x,
+            Some(..) => return None,
+            None => metric,
+        };
+
+        if !fields.is_object() {
+            return None;
+        }
+
+        let number = |key: &str| fields.find(key).and_then(|x| x.as_f64());
+
+        Some(Histogram {
+            min: number("min"),
+            max: number("max"),
+            arithmetic_mean: number("arithmetic_mean").or_else(|| number("mean")),
+            percentiles: fields.find("percentile").map(decode_percentiles).unwrap_or(Vec::new()),
+        })
+    }
+
+    /// Returns the smallest sample, if known.
+    pub fn min(&self) -> Option<f64> {
+        self.min
+    }
+
+    /// Returns the largest sample, if known.
+    pub fn max(&self) -> Option<f64> {
+        self.max
+    }
+
+    /// Returns the arithmetic mean of the samples, if known.
+    pub fn arithmetic_mean(&self) -> Option<f64> {
+        self.arithmetic_mean
+    }
+
+    /// Returns the available percentiles, as pairs of a percentage—e.g.,
+    /// `99.9`—and the sample value at that percentage, in ascending order of
+    /// percentage.
+    pub fn percentiles(&self) -> &Vec<(f64, f64)> {
+        &self.percentiles
+    }
+
+    /// Returns the sample value at the given percentage—e.g., `95.0`—if the
+    /// server sent that percentile.
+    pub fn percentile(&self, percentage: f64) -> Option<f64> {
+        self.percentiles.iter().find(|x| (x.0 - percentage).abs() < 1e-9).map(|x| x.1)
+    }
+}
+
+// CouchDB sends percentiles as a list of [key, value] pairs, where the key is
+// a whole number and 999 stands for 99.9. Entries that are not such pairs are
+// skipped.
+fn decode_percentiles(percentiles: &serde_json::Value) -> Vec<(f64, f64)> {
+
+    fn percentage(key: f64) -> f64 {
+        if key > 100.0 { key / 10.0 } else { key }
+    }
+
+    let mut decoded = match percentiles {
+        &serde_json::Value::Array(ref entries) => {
+            entries.iter()
+                .filter_map(|x| x.as_array())
+                .filter_map(|x| match (x.get(0).and_then(|x| x.as_f64()), x.get(1).and_then(|x| x.as_f64())) {
+                    (Some(key), Some(value)) => Some((percentage(key), value)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        }
+        &serde_json::Value::Object(ref entries) => {
+            entries.iter()
+                .filter_map(|(key, value)| match (key.parse::<f64>(), value.as_f64()) {
+                    (Ok(key), Some(value)) => Some((percentage(key), value)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        }
+        _ => Vec::new(),
+    };
+
+    decoded.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    decoded
+}
+
+impl serde::Deserialize for Stats {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+        where D: serde::Deserializer
+    {
+        let tree = try!(serde_json::Value::deserialize(deserializer));
+        Ok(Stats { tree: tree })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use serde_json;
+    use super::*;
+
+    fn make_stats(raw: &str) -> Stats {
+        serde_json::from_str(raw).unwrap()
+    }
+
+    #[test]
+    fn get_ok() {
+        let stats = make_stats(r#"{"couchdb": {"open_databases": {"value": 17, "type": "counter"}}}"#);
+        let expected = serde_json::builder::ObjectBuilder::new()
+            .insert("value", 17)
+            .insert("type", "counter")
+            .build();
+        assert_eq!(Some(&expected), stats.get(&["couchdb", "open_databases"]));
+    }
+
+    #[test]
+    fn get_nok_unknown_path() {
+        let stats = make_stats(r#"{"couchdb": {"open_databases": {"value": 17, "type": "counter"}}}"#);
+        assert_eq!(None, stats.get(&["couchdb", "no_such_metric"]));
+        assert_eq!(None, stats.get(&["no_such_branch", "open_databases"]));
+    }
+
+    #[test]
+    fn open_databases_v2() {
+        let stats = make_stats(r#"{"couchdb": {"open_databases": {"value": 17, "type": "counter"}}}"#);
+        assert_eq!(Some(17), stats.open_databases());
+    }
+
+    #[test]
+    fn open_databases_v1() {
+        let stats = make_stats(r#"{"couchdb": {"open_databases": {"current": 17, "sum": 17, "mean": 0.5}}}"#);
+        assert_eq!(Some(17), stats.open_databases());
+    }
+
+    #[test]
+    fn httpd_requests() {
+        let stats = make_stats(r#"{"httpd": {"requests": {"value": 42, "type": "counter"}}}"#);
+        assert_eq!(Some(42), stats.httpd_requests());
+        assert_eq!(Some(42.0), stats.number(&["httpd", "requests"]));
+    }
+
+    #[test]
+    fn request_time_mean_histogram() {
+        let stats = make_stats(r#"{"couchdb": {"request_time": {"value": {"min": 1.0, "max": 9.0,
+                                                                          "arithmetic_mean": 3.5},
+                                                               "type": "histogram"}}}"#);
+        assert_eq!(Some(3.5), stats.request_time_mean());
+    }
+
+    #[test]
+    fn request_time_v3() {
+        let stats = make_stats(r#"{"couchdb": {"request_time": {"value": {
+            "min": 0.5, "max": 120.25, "arithmetic_mean": 4.75, "geometric_mean": 2.5, "median": 3.0, "n": 512,
+            "percentile": [[50, 3.0], [75, 5.5], [90, 9.0], [95, 14.0], [99, 60.0], [999, 110.0]],
+            "histogram": [[10.0, 500], [130.0, 12]]},
+            "type": "histogram", "desc": "length of a request inside CouchDB without MochiWeb"}}}"#);
+        let got = stats.request_time().unwrap();
+        assert_eq!(Some(0.5), got.min());
+        assert_eq!(Some(120.25), got.max());
+        assert_eq!(Some(4.75), got.arithmetic_mean());
+        assert_eq!(6, got.percentiles().len());
+        assert_eq!(Some(14.0), got.percentile(95.0));
+        assert_eq!(Some(110.0), got.percentile(99.9));
+        assert_eq!(None, got.percentile(25.0));
+    }
+
+    #[test]
+    fn request_time_v1() {
+        let stats = make_stats(r#"{"couchdb": {"request_time": {
+            "description": "length of a request inside CouchDB without MochiWeb",
+            "current": 1207.0, "sum": 1207.0, "mean": 6.5, "stddev": 10.25, "min": 0.5, "max": 80.0}}}"#);
+        let got = stats.request_time().unwrap();
+        assert_eq!(Some(0.5), got.min());
+        assert_eq!(Some(80.0), got.max());
+        assert_eq!(Some(6.5), got.arithmetic_mean());
+        assert!(got.percentiles().is_empty());
+    }
+
+    #[test]
+    fn request_time_tolerates_partial_histogram() {
+        let stats = make_stats(r#"{"couchdb": {"request_time": {"value": {"arithmetic_mean": 2.0,
+                                                                          "percentile": [[50, 1.0], [75]]},
+                                                               "type": "histogram"}}}"#);
+        let got = stats.request_time().unwrap();
+        assert_eq!(None, got.min());
+        assert_eq!(Some(2.0), got.arithmetic_mean());
+        assert_eq!(&vec![(50.0, 1.0)], got.percentiles());
+    }
+
+    #[test]
+    fn histogram_nok_counter() {
+        let stats = make_stats(r#"{"httpd": {"requests": {"value": 42, "type": "counter"}}}"#);
+        assert_eq!(None, stats.histogram(&["httpd", "requests"]));
+    }
+
+    #[test]
+    fn unknown_branches_are_tolerated() {
+        let stats = make_stats(r#"{"mem3": {"shard_cache": {"eviction": {"value": 0}}}, "global_changes": {}}"#);
+        assert_eq!(None, stats.open_databases());
+        assert_eq!(Some(0.0), stats.number(&["mem3", "shard_cache", "eviction"]));
+    }
+}
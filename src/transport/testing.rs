@@ -1,5 +1,6 @@
-use {Error, url};
-use super::{AsyncActionHandler, JsonResponse, Request, ResponseHandler, ResponseHeaders, StatusCode, Transport};
+use {Error, hyper, std, url};
+use super::{AsyncActionHandler, JsonResponse, Request, ResponseHandler, ResponseHeaders, ResponsePart,
+            ServerCapabilities, StatusCode, Transport};
 
 pub struct JsonResponseBuilder {
     inner: JsonResponse,
@@ -12,6 +13,8 @@ impl JsonResponseBuilder {
                 status_code: status_code,
                 headers: ResponseHeaders::new(),
                 content: Vec::new(),
+                parts: Vec::new(),
+                json_codec: None,
             },
         }
     }
@@ -24,21 +27,123 @@ impl JsonResponseBuilder {
         self.inner.content = raw_json.as_ref().bytes().collect();
         self
     }
+
+    pub fn with_raw_content(mut self, content: Vec<u8>) -> Self {
+        self.inner.content = content;
+        self
+    }
+
+    pub fn with_raw_header(mut self, name: &str, value: &str) -> Self {
+        self.inner.headers.headers.set_raw(String::from(name), vec![value.as_bytes().to_vec()]);
+        self
+    }
+
+    pub fn with_part<S: Into<String>>(mut self, filename: S, content: Vec<u8>) -> Self {
+        self.inner.parts.push(ResponsePart {
+            filename: Some(filename.into()),
+            content: content,
+        });
+        self
+    }
 }
 
-pub struct MockTransport;
+const MULTIPART_BOUNDARY: &'static str = "mockboundary";
+
+fn encode_multipart_content(content: Vec<u8>, parts: Vec<ResponsePart>) -> Vec<u8> {
+    let mut encoded = format!("--{}\r\nContent-Type: application/json\r\n\r\n", MULTIPART_BOUNDARY).into_bytes();
+    encoded.extend_from_slice(&content);
+    for part in parts {
+        let headers = match part.filename {
+            None => String::new(),
+            Some(ref filename) => format!("Content-Disposition: attachment; filename=\"{}\"\r\n", filename),
+        };
+        encoded.extend_from_slice(format!("\r\n--{}\r\n{}\r\n", MULTIPART_BOUNDARY, headers).as_bytes());
+        encoded.extend_from_slice(&part.content);
+    }
+    encoded.extend_from_slice(format!("\r\n--{}--", MULTIPART_BOUNDARY).as_bytes());
+    encoded
+}
+
+pub struct MockTransport {
+    responses: std::cell::RefCell<std::collections::VecDeque<JsonResponse>>,
+    requests: std::cell::RefCell<Vec<Request>>,
+    server_capabilities: ServerCapabilities,
+}
 
 impl MockTransport {
     pub fn new() -> Self {
-        MockTransport
+        MockTransport {
+            responses: std::cell::RefCell::new(std::collections::VecDeque::new()),
+            requests: std::cell::RefCell::new(Vec::new()),
+            server_capabilities: ServerCapabilities::new(),
+        }
+    }
+
+    // Queues a response for the send method to answer with, for testing
+    // actions that send more than one request.
+    pub fn with_response(self, response: JsonResponse) -> Self {
+        self.responses.borrow_mut().push_back(response);
+        self
+    }
+
+    // Removes and returns the requests sent so far, for testing which requests
+    // an action sends.
+    pub fn take_requests(&self) -> Vec<Request> {
+        std::mem::replace(&mut *self.requests.borrow_mut(), Vec::new())
     }
 }
 
 impl Transport for MockTransport {
-    fn send<H, T>(&self, _request: Request, _response_handler: H) -> Result<T, Error>
+    fn send<H, T>(&self, request: Request, mut response_handler: H) -> Result<T, Error>
         where H: ResponseHandler<T>
     {
-        unimplemented!();
+        let context = request.context();
+
+        let response = match self.responses.borrow_mut().pop_front() {
+            Some(x) => x,
+            None => panic!("MockTransport has no queued response for {:?}", request),
+        };
+
+        self.requests.borrow_mut().push(request);
+
+        // Send the response the way the server would: any parts go in a
+        // multipart/related content after the JSON content, and a response
+        // without a content type gets a JSON one.
+        let JsonResponse { status_code, headers, content, parts, .. } = response;
+        let mut headers = headers.headers;
+        let content = if parts.is_empty() {
+            if !headers.has::<hyper::header::ContentType>() {
+                headers.set(hyper::header::ContentType(mime!(Application / Json)));
+            }
+            content
+        } else {
+            headers.set_raw("Content-Type",
+                            vec![format!("multipart/related; boundary={}", MULTIPART_BOUNDARY).into_bytes()]);
+            encode_multipart_content(content, parts)
+        };
+
+        try!(response_handler.handle_response_status_and_headers(status_code, ResponseHeaders::from(headers)));
+        try!(response_handler.handle_response_content(&content));
+        response_handler.handle_response_eof().map_err(|e| e.with_request_context(&context))
+    }
+
+    // Reads the whole body so that the test may check the request's content.
+    fn send_streaming<H, T>(&self,
+                            request: Request,
+                            body: &mut std::io::Read,
+                            _body_length: Option<u64>,
+                            response_handler: H)
+                            -> Result<T, Error>
+        where H: ResponseHandler<T>
+    {
+        let mut content = Vec::new();
+        try!(body.read_to_end(&mut content).map_err(|e| {
+            Error::Io {
+                cause: e,
+                description: "Failed to read the request content from its source",
+            }
+        }));
+        self.send(request.with_raw_body(content), response_handler)
     }
 
     fn send_async<H, A, T, U>(&self, _request: Request, _response_handler: H, _action_handler: A) -> Result<U, Error>
@@ -51,4 +156,49 @@ impl Transport for MockTransport {
     fn make_base_url(&self) -> url::Url {
         url::Url::parse("http://example.com:5984").unwrap()
     }
+
+    fn server_capabilities(&self) -> Option<&ServerCapabilities> {
+        Some(&self.server_capabilities)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use Error;
+    use super::*;
+    use transport::{JsonResponse, JsonResponseDecoder, StatusCode, Transport};
+
+    #[test]
+    fn send_forwards_queued_headers() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_raw_header("ETag", r#""1-1234567890abcdef1234567890abcdef""#)
+            .unwrap());
+        let handler = |response: JsonResponse| -> Result<_, Error> {
+            Ok(response.headers().etag().map(|x| String::from(x)))
+        };
+        let got = transport.send(transport.head(vec!["foo", "bar"]), JsonResponseDecoder::new(handler)).unwrap();
+        assert_eq!(Some(String::from("1-1234567890abcdef1234567890abcdef")), got);
+    }
+
+    #[test]
+    fn send_forwards_queued_parts() {
+        let transport = MockTransport::new().with_response(JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"_id":"bar"}"#)
+            .with_part("baz", b"baz content".to_vec())
+            .unwrap());
+        let handler = |response: JsonResponse| -> Result<_, Error> {
+            let content = response.content.clone();
+            let parts = response.into_parts()
+                .into_iter()
+                .map(|x| (x.filename().map(|x| String::from(x)), x.into_content()))
+                .collect::<Vec<_>>();
+            Ok((content, parts))
+        };
+        let got = transport.send(transport.get(vec!["foo", "bar"]),
+                                 JsonResponseDecoder::new(handler).with_multipart())
+            .unwrap();
+        let expected = (br#"{"_id":"bar"}"#.to_vec(), vec![(Some(String::from("baz")), b"baz content".to_vec())]);
+        assert_eq!(expected, got);
+    }
 }
@@ -1,4 +1,4 @@
-use {DatabaseName, Document, DocumentId, DocumentPath, Error, serde, serde_json, std};
+use {DatabaseName, Document, DocumentId, DocumentPath, Error, Seq, serde, serde_json, std};
 use document::JsonDecodableDocument;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -6,7 +6,7 @@ pub struct ViewResponse {
     total_rows: Option<u64>,
     offset: Option<u64>,
     rows: Vec<ViewRow>,
-    update_seq: Option<u64>,
+    update_seq: Option<Seq>,
 }
 
 impl ViewResponse {
@@ -48,14 +48,104 @@ impl ViewResponse {
     }
 
     /// Returns the update sequence number that the view reflects, if available.
+    ///
+    /// CouchDB 2.x and later send the update sequence as an opaque string, in
+    /// which case the result is the string's numeric prefix—see
+    /// `Seq::as_u64`. Use `update_sequence` to get the sequence as is.
+    ///
     pub fn update_sequence_number(&self) -> Option<u64> {
-        self.update_seq
+        self.update_seq.as_ref().and_then(|x| x.as_u64())
+    }
+
+    /// Returns the update sequence that the view reflects, if available.
+    ///
+    /// The update sequence is available if the action requested it—see
+    /// `ExecuteView::with_update_seq`—or if the server sends it by default.
+    ///
+    pub fn update_sequence(&self) -> Option<&Seq> {
+        self.update_seq.as_ref()
     }
 
     /// Returns the vector containing all rows in the view response.
     pub fn rows(&self) -> &Vec<ViewRow> {
         &self.rows
     }
+
+    /// Converts the view response into its rows, in view order.
+    pub fn into_rows(self) -> Vec<ViewRow> {
+        self.rows
+    }
+
+    /// Decodes the rows of a group-reduced view into a map from group key to
+    /// reduced value.
+    ///
+    /// Each row must have a key, though the key may be null—e.g., for
+    /// documents whose grouped field is null. A null key is decoded into `K`
+    /// like any other key, so `K` should be an `Option` type if null keys may
+    /// occur. A row without a key results in an `UnexpectedResponse` error.
+    /// Use `reduced_value` for ungrouped reductions.
+    ///
+    pub fn grouped_values<K, V>(&self) -> Result<std::collections::HashMap<K, V>, Error>
+        where K: serde::Deserialize + Eq + std::hash::Hash,
+              V: serde::Deserialize
+    {
+        let mut values = std::collections::HashMap::with_capacity(self.rows.len());
+        for row in self.rows.iter() {
+            let key = match row.key {
+                None => return Err(Error::UnexpectedResponse("View row is missing its group key")),
+                Some(ref key) => {
+                    try!(serde_json::from_value(key.clone()).map_err(|e| Error::JsonDecode { cause: Box::new(e) }))
+                }
+            };
+            values.insert(key, try!(row.value()));
+        }
+        Ok(values)
+    }
+
+    /// Decodes the value of an ungrouped reduction, if available.
+    ///
+    /// An ungrouped reduce yields at most one row, whose key is null. The
+    /// return value is `None` if the view contains no rows, e.g., because the
+    /// key range is empty. Any other row layout results in an
+    /// `UnexpectedResponse` error.
+    ///
+    pub fn reduced_value<V: serde::Deserialize>(&self) -> Result<Option<V>, Error> {
+        match self.rows.len() {
+            0 => Ok(None),
+            1 => {
+                let row = &self.rows[0];
+                match row.key {
+                    None |
+                    Some(serde_json::Value::Null) => row.value().map(|x| Some(x)),
+                    _ => Err(Error::UnexpectedResponse("Reduced view row has a non-null key")),
+                }
+            }
+            _ => Err(Error::UnexpectedResponse("Reduced view has more than one row")),
+        }
+    }
+}
+
+/// Iterates through the rows in view order.
+///
+/// The iterator composes with the standard iterator adapters—e.g., `filter_map`
+/// and `take`—and its size hint is exact. The server's response is read in
+/// full before the action returns, so stopping the iteration early saves
+/// decoding but not reading.
+///
+impl IntoIterator for ViewResponse {
+    type Item = ViewRow;
+    type IntoIter = std::vec::IntoIter<ViewRow>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.rows.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ViewResponse {
+    type Item = &'a ViewRow;
+    type IntoIter = std::slice::Iter<'a, ViewRow>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.rows.iter()
+    }
 }
 
 #[doc(hidden)]
@@ -103,17 +193,23 @@ impl ViewRow {
             None => None,
             Some(ref key) => {
                 // TODO: Optimize this to eliminate cloning and re-decoding.
-                try!(serde_json::from_value(key.clone()).map_err(|e| Error::JsonDecode { cause: e }))
+                try!(serde_json::from_value(key.clone()).map_err(|e| Error::JsonDecode { cause: Box::new(e) }))
             }
         };
 
         Ok(decoded)
     }
 
+    // Returns the row's key as sent by the server, without decoding it.
+    #[doc(hidden)]
+    pub fn raw_key(&self) -> Option<&serde_json::Value> {
+        self.key.as_ref()
+    }
+
     /// Returns the row's value.
     pub fn value<V: serde::Deserialize>(&self) -> Result<V, Error> {
         // TODO: Optimize this to eliminate cloning and re-decoding.
-        serde_json::from_value(self.value.clone()).map_err(|e| Error::JsonDecode { cause: e })
+        serde_json::from_value(self.value.clone()).map_err(|e| Error::JsonDecode { cause: Box::new(e) })
     }
 
     /// Returns the row's related document path, if available.
@@ -129,11 +225,80 @@ impl ViewRow {
     }
 }
 
+/// Builds an array key for querying a view whose map function emits array
+/// keys—e.g., `["2024", 3, "foo"]`.
+///
+/// A `ComplexKey` serializes as a JSON array of its elements, so it may be
+/// passed wherever a view action accepts a key—e.g.,
+/// `ExecuteView::with_start_key`. The action JSON-encodes and URL-encodes the
+/// key.
+///
+/// # Examples
+///
+/// ```
+/// extern crate chill;
+/// extern crate serde_json;
+///
+/// let start_key = chill::ComplexKey::new().with("2024").with(3);
+/// let end_key = chill::ComplexKey::new().with("2024").with(3).with_max();
+///
+/// assert_eq!(r#"["2024",3]"#, serde_json::to_string(&start_key).unwrap());
+/// assert_eq!(r#"["2024",3,{}]"#, serde_json::to_string(&end_key).unwrap());
+/// ```
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ComplexKey {
+    elements: Vec<serde_json::Value>,
+}
+
+impl ComplexKey {
+    /// Constructs an empty key.
+    pub fn new() -> Self {
+        ComplexKey { elements: Vec::new() }
+    }
+
+    /// Returns a copy of the key with the given element appended.
+    pub fn with<T: serde::Serialize>(mut self, element: T) -> Self {
+        self.elements.push(serde_json::to_value(&element));
+        self
+    }
+
+    /// Returns a copy of the key with an empty object appended.
+    ///
+    /// An object sorts after all other JSON values in CouchDB's view
+    /// collation, so, as an end key, `["2024", 3, {}]` includes all rows whose
+    /// key starts with `"2024"` and `3`.
+    ///
+    pub fn with_max(mut self) -> Self {
+        self.elements.push(serde_json::builder::ObjectBuilder::new().build());
+        self
+    }
+
+    /// Returns the key's elements.
+    pub fn elements(&self) -> &Vec<serde_json::Value> {
+        &self.elements
+    }
+}
+
+impl From<Vec<serde_json::Value>> for ComplexKey {
+    fn from(elements: Vec<serde_json::Value>) -> Self {
+        ComplexKey { elements: elements }
+    }
+}
+
+impl serde::Serialize for ComplexKey {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+        where S: serde::Serializer
+    {
+        self.elements.serialize(serializer)
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct ViewResponseJsonable {
     total_rows: Option<u64>,
     offset: Option<u64>,
-    update_seq: Option<u64>,
+    update_seq: Option<Seq>,
     rows: Vec<ViewRowJsonable>,
 }
 
@@ -291,7 +456,9 @@ impl serde::Deserialize for ViewRowJsonable {
                             id = Some(try!(visitor.visit_value()));
                         }
                         Some(Field::Key) => {
-                            key = try!(visitor.visit_value()); // allow null
+                            // Keep a null key, which differs from a missing
+                            // key—e.g., a null group key.
+                            key = Some(try!(visitor.visit_value()));
                         }
                         Some(Field::Value) => {
                             value = Some(try!(visitor.visit_value()));
@@ -341,7 +508,7 @@ impl ViewResponseBuilder<IsReduced> {
             db_name: None,
             target: ViewResponse {
                 rows: vec![ViewRow {
-                               key: None,
+                               key: Some(serde_json::Value::Null),
                                value: serde_json::to_value(&value),
                                doc_path: None,
                                doc: None,
@@ -438,7 +605,7 @@ impl<T> ViewResponseBuilder<T> {
     /// By default, the view response's update sequence number is `None`.
     ///
     pub fn with_update_sequence_number(mut self, update_seq: u64) -> Self {
-        self.target.update_seq = Some(update_seq);
+        self.target.update_seq = Some(Seq::from(update_seq));
         self
     }
 
@@ -453,7 +620,7 @@ mod tests {
 
     use super::*;
     use super::ViewRowJsonable;
-    use {DocumentId, Error, IntoDocumentPath, Revision, serde_json, std};
+    use {DocumentId, Error, IntoDocumentPath, Revision, Seq, serde_json, std};
     use document::JsonDecodableDocument;
 
     #[test]
@@ -537,7 +704,7 @@ mod tests {
 
         let expected = ViewRowJsonable {
             id: None,
-            key: None,
+            key: Some(serde_json::Value::Null),
             value: serde_json::Value::U64(42),
             doc: None,
         };
@@ -575,6 +742,8 @@ mod tests {
                 doc_id: DocumentId::from("foo"),
                 revision: Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
                 deleted: false,
+                conflicts: Vec::new(),
+                revisions_info: Vec::new(),
                 attachments: std::collections::HashMap::new(),
                 content: serde_json::builder::ObjectBuilder::new()
                     .insert("doc_field", 17)
@@ -607,7 +776,7 @@ mod tests {
             update_seq: None,
             rows: vec![ViewRowJsonable {
                            id: None,
-                           key: None,
+                           key: Some(serde_json::Value::Null),
                            value: serde_json::Value::U64(42),
                            doc: None,
                        }],
@@ -627,10 +796,10 @@ mod tests {
         let expected = ViewResponseJsonable {
             total_rows: None,
             offset: None,
-            update_seq: Some(17),
+            update_seq: Some(Seq::from(17)),
             rows: vec![ViewRowJsonable {
                            id: None,
-                           key: None,
+                           key: Some(serde_json::Value::Null),
                            value: serde_json::Value::U64(42),
                            doc: None,
                        }],
@@ -727,7 +896,7 @@ mod tests {
         let expected = ViewResponseJsonable {
             total_rows: Some(10),
             offset: Some(5),
-            update_seq: Some(17),
+            update_seq: Some(Seq::from(17)),
             rows: vec![ViewRowJsonable {
                            id: Some(DocumentId::from("foo")),
                            key: Some(serde_json::Value::String(String::from("bar"))),
@@ -744,15 +913,31 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn view_response_deserialize_ok_unreduced_with_opaque_update_seq() {
+
+        let expected = ViewResponseJsonable {
+            total_rows: Some(10),
+            offset: Some(5),
+            update_seq: Some(Seq::from("17-g1AAAAB")),
+            rows: vec![],
+        };
+
+        let json_text = r#"{"total_rows": 10, "offset": 5, "update_seq": "17-g1AAAAB", "rows": []}"#;
+
+        let got = serde_json::from_str(&json_text).unwrap();
+        assert_eq!(expected, got);
+    }
+
     #[test]
     fn view_response_builder_reduced() {
 
         let expected = ViewResponse {
             total_rows: None,
             offset: None,
-            update_seq: Some(99),
+            update_seq: Some(Seq::from(99)),
             rows: vec![ViewRow {
-                           key: None,
+                           key: Some(serde_json::Value::Null),
                            value: serde_json::Value::U64(42),
                            doc_path: None,
                            doc: None,
@@ -769,7 +954,7 @@ mod tests {
         let expected = ViewResponse {
             total_rows: None,
             offset: None,
-            update_seq: Some(99),
+            update_seq: Some(Seq::from(99)),
             rows: Vec::new(),
         };
 
@@ -783,7 +968,7 @@ mod tests {
         let expected = ViewResponse {
             total_rows: None,
             offset: None,
-            update_seq: Some(99),
+            update_seq: Some(Seq::from(99)),
             rows: vec![ViewRow {
                            key: Some(serde_json::Value::Array(vec![serde_json::Value::U64(1)])),
                            value: serde_json::Value::String(String::from("alpha")),
@@ -813,7 +998,7 @@ mod tests {
         let expected = ViewResponse {
             total_rows: Some(42),
             offset: Some(17),
-            update_seq: Some(99),
+            update_seq: Some(Seq::from(99)),
             rows: vec![ViewRow {
                            key: Some(serde_json::Value::U64(1)),
                            value: serde_json::Value::String(String::from("bravo")),
@@ -836,4 +1021,88 @@ mod tests {
 
         assert_eq!(expected, got);
     }
+
+    #[test]
+    fn view_response_grouped_values_ok() {
+        let view = ViewResponseBuilder::new_grouped()
+            .with_row("alpha", 17)
+            .with_row("bravo", 42)
+            .unwrap();
+        let got = view.grouped_values::<String, u64>().unwrap();
+        let mut expected = std::collections::HashMap::new();
+        expected.insert(String::from("alpha"), 17);
+        expected.insert(String::from("bravo"), 42);
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn view_response_into_iter_composes_with_adapters() {
+        let view = ViewResponseBuilder::new_grouped()
+            .with_row("alpha", 17)
+            .with_row("bravo", 42)
+            .with_row("charlie", 5)
+            .unwrap();
+        assert_eq!((3, Some(3)), (&view).into_iter().size_hint());
+        let got = view.into_iter()
+            .filter_map(|x| x.value::<u64>().ok())
+            .filter(|x| *x > 10)
+            .take(1)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![17], got);
+    }
+
+    #[test]
+    fn view_response_grouped_values_ok_null_key() {
+        let source = r#"{"rows":[{"key":null,"value":3},{"key":"alpha","value":17}]}"#;
+        let decoded = serde_json::from_str(source).unwrap();
+        let view = ViewResponse::new_from_decoded(DatabaseName::from("foo"), decoded);
+        let got = view.grouped_values::<Option<String>, u64>().unwrap();
+        let mut expected = std::collections::HashMap::new();
+        expected.insert(None, 3);
+        expected.insert(Some(String::from("alpha")), 17);
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn view_response_grouped_values_nok_missing_key() {
+        let source = r#"{"rows":[{"value":42}]}"#;
+        let decoded = serde_json::from_str(source).unwrap();
+        let view = ViewResponse::new_from_decoded(DatabaseName::from("foo"), decoded);
+        match view.grouped_values::<String, u64>() {
+            Err(Error::UnexpectedResponse(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn view_response_reduced_value_ok_some() {
+        let view = ViewResponseBuilder::new_reduced(42).unwrap();
+        assert_eq!(Some(42), view.reduced_value::<u64>().unwrap());
+    }
+
+    #[test]
+    fn view_response_reduced_value_ok_null_key() {
+        let source = r#"{"rows":[{"key":null,"value":42}]}"#;
+        let decoded = serde_json::from_str(source).unwrap();
+        let view = ViewResponse::new_from_decoded(DatabaseName::from("foo"), decoded);
+        assert_eq!(Some(42), view.reduced_value::<u64>().unwrap());
+    }
+
+    #[test]
+    fn view_response_reduced_value_ok_none() {
+        let view = ViewResponseBuilder::new_reduced_empty().unwrap();
+        assert_eq!(None, view.reduced_value::<u64>().unwrap());
+    }
+
+    #[test]
+    fn view_response_reduced_value_nok_grouped() {
+        let view = ViewResponseBuilder::new_grouped()
+            .with_row("alpha", 17)
+            .with_row("bravo", 42)
+            .unwrap();
+        match view.reduced_value::<u64>() {
+            Err(Error::UnexpectedResponse(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
 }
@@ -1007,3 +1007,79 @@ fn execute_view_ok_grouped_with_level() {
 
     assert_eq!(expected, got);
 }
+
+#[test]
+fn client_clones_share_server_across_threads() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let threads = (0..4)
+        .map(|i| {
+            let client = client.clone();
+            std::thread::spawn(move || {
+                let content = serde_json::builder::ObjectBuilder::new()
+                    .insert("thread", i)
+                    .build();
+                client.create_document("/baseball", &content)
+                    .with_document_id(format!("doc_{}", i))
+                    .run()
+                    .unwrap();
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for t in threads {
+        t.join().unwrap();
+    }
+
+    for i in 0..4 {
+        let doc = client.read_document(("/baseball", format!("doc_{}", i))).run().unwrap();
+        let content: serde_json::Value = doc.get_content().unwrap();
+        assert_eq!(Some(i), content.find("thread").and_then(|x| x.as_u64()));
+    }
+}
+
+#[test]
+fn execute_view_keys_concurrently_ok() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let up_content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Babe Ruth")
+        .insert("home_runs", 714)
+        .build();
+
+    let (babe_id, _) = client.create_document("/baseball", &up_content).run().unwrap();
+
+    let up_content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Hank Aaron")
+        .insert("home_runs", 755)
+        .build();
+
+    let (hank_id, _) = client.create_document("/baseball", &up_content).run().unwrap();
+
+    let up_content = chill::DesignBuilder::new()
+        .insert_view("home_runs",
+                     chill::ViewFunction::new("function(doc) { emit(doc.home_runs, doc.home_runs) }"))
+        .unwrap();
+
+    client.create_document("/baseball", &up_content)
+        .with_document_id("_design/stats")
+        .run()
+        .unwrap();
+
+    let expected = vec![chill::testing::ViewResponseBuilder::new_unreduced("baseball", 2, 1)
+                            .with_row(hank_id, 755, 755)
+                            .unwrap(),
+                        chill::testing::ViewResponseBuilder::new_unreduced("baseball", 2, 0)
+                            .with_row(babe_id, 714, 714)
+                            .unwrap(),
+                        chill::testing::ViewResponseBuilder::new_unreduced("baseball", 2, 2).unwrap()];
+
+    let got = client.execute_view_keys_concurrently("/baseball/_design/stats/_view/home_runs", vec![755, 714, 800], 2)
+        .unwrap();
+
+    assert_eq!(expected, got);
+}